@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+use clap::Parser;
+use presenceforge::Activity;
+
+/// Export the JSON Schema for presence files
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Write the schema to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<std::path::PathBuf>,
+}
+
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    let schema = serde_json::to_string_pretty(&Activity::json_schema())?;
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, schema)?;
+            println!("Wrote activity schema to {}", path.display());
+        }
+        None => println!("{schema}"),
+    }
+
+    Ok(())
+}
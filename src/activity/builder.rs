@@ -1,13 +1,14 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // Copyright (c) 2025-2026 Sreehari Anil and project contributors
 
+use crate::activity::timestamp::{PresenceTimestamp, MAX_TIMESTAMP_SECS};
 #[cfg(feature = "secrets")]
 use crate::activity::types::ActivitySecrets;
 use crate::activity::types::{
     Activity, ActivityAssets, ActivityButton, ActivityParty, ActivityTimestamps,
+    ActivityValidationError,
 };
 use crate::error::{DiscordIpcError, Result};
-use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Builder for creating Discord Rich Presence activities
 #[derive(Debug, Default)]
@@ -39,22 +40,40 @@ impl ActivityBuilder {
     ///
     /// Returns an error if the system time is before the UNIX epoch (Jan 1, 1970).
     /// This should never happen on properly configured systems.
-    pub fn start_timestamp_now(mut self) -> Result<Self> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(DiscordIpcError::SystemTimeError)?;
+    pub fn start_timestamp_now(self) -> Result<Self> {
+        Ok(self.start_at(PresenceTimestamp::now()?))
+    }
+
+    /// Set the start timestamp from a unit-safe [`PresenceTimestamp`]
+    pub fn start_at(mut self, timestamp: PresenceTimestamp) -> Self {
+        self.get_timestamps().start = Some(timestamp.as_unix_secs());
+        self
+    }
 
-        self.get_timestamps().start = Some(now.as_secs());
+    /// Set the end timestamp from a unit-safe [`PresenceTimestamp`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `ActivityValidationError::EndTimestampOutOfRange` if the timestamp does
+    /// not fit the signed end field.
+    pub fn end_at(mut self, timestamp: PresenceTimestamp) -> Result<Self> {
+        let end = i64::try_from(timestamp.as_unix_secs()).map_err(|_| {
+            DiscordIpcError::InvalidActivity(ActivityValidationError::EndTimestampOutOfRange {
+                max: MAX_TIMESTAMP_SECS,
+                actual: i64::MAX,
+            })
+        })?;
+        self.get_timestamps().end = Some(end);
         Ok(self)
     }
 
-    /// Set the start timestamp
+    /// Set the start timestamp in Unix seconds
     pub fn start_timestamp(mut self, timestamp: u64) -> Self {
         self.get_timestamps().start = Some(timestamp);
         self
     }
 
-    /// Set the end timestamp
+    /// Set the end timestamp in Unix seconds
     pub fn end_timestamp(mut self, timestamp: i64) -> Self {
         self.get_timestamps().end = Some(timestamp);
         self
@@ -65,14 +84,8 @@ impl ActivityBuilder {
     /// # Errors
     ///
     /// Returns an error if the system time is before the UNIX epoch.
-    pub fn end_timestamp_from_now(mut self, duration: std::time::Duration) -> Result<Self> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(DiscordIpcError::SystemTimeError)?;
-
-        let end = now + duration;
-        self.get_timestamps().end = Some(end.as_secs() as i64);
-        Ok(self)
+    pub fn end_timestamp_from_now(self, duration: std::time::Duration) -> Result<Self> {
+        self.end_at(PresenceTimestamp::from_now(duration)?)
     }
 
     /// Set the large image asset
@@ -178,6 +191,7 @@ impl ActivityBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
 
     #[test]
     fn builder_sets_basic_fields() {
@@ -271,6 +285,33 @@ mod tests {
         assert!(end <= now + 62);
     }
 
+    #[test]
+    fn presence_timestamps_are_applied_in_seconds() {
+        let activity = ActivityBuilder::new()
+            .start_at(PresenceTimestamp::from_unix_millis(1_700_000_000_000))
+            .end_at(PresenceTimestamp::from_unix_secs(1_700_000_600))
+            .unwrap()
+            .build();
+
+        let timestamps = activity.timestamps.unwrap();
+        assert_eq!(timestamps.start, Some(1_700_000_000));
+        assert_eq!(timestamps.end, Some(1_700_000_600));
+    }
+
+    #[test]
+    fn end_timestamps_beyond_i64_are_rejected() {
+        let err = ActivityBuilder::new()
+            .end_at(PresenceTimestamp::from_unix_secs(u64::MAX))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DiscordIpcError::InvalidActivity(ActivityValidationError::EndTimestampOutOfRange {
+                max: MAX_TIMESTAMP_SECS,
+                ..
+            })
+        ));
+    }
+
     #[cfg(feature = "secrets")]
     #[test]
     fn secrets_are_applied_when_feature_enabled() {
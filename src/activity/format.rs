@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Loading and saving activities from configuration files
+//!
+//! Enabled per format with the `toml` and `yaml` features. The `schema` feature
+//! exports a JSON Schema for `Activity` so editors can autocomplete presence files.
+
+#[cfg(any(feature = "toml", feature = "yaml", feature = "schema"))]
+use crate::activity::types::Activity;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use crate::error::{DiscordIpcError, Result};
+
+#[cfg(feature = "toml")]
+impl Activity {
+    /// Parse an activity from a TOML document and validate it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use presenceforge::Activity;
+    ///
+    /// let activity = Activity::from_toml_str(r#"
+    ///     state = "In a match"
+    ///     details = "Ranked"
+    ///
+    ///     [assets]
+    ///     large_image = "map_dust"
+    /// "#)?;
+    /// assert_eq!(activity.state.as_deref(), Some("In a match"));
+    /// # Ok::<(), presenceforge::DiscordIpcError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ActivityFormat` if the document cannot be parsed, or
+    /// `DiscordIpcError::InvalidActivity` if the activity fails validation.
+    pub fn from_toml_str(input: &str) -> Result<Self> {
+        let activity: Self = toml::from_str(input)
+            .map_err(|err| DiscordIpcError::activity_format("TOML", err.to_string()))?;
+        activity.validate()?;
+        Ok(activity)
+    }
+
+    /// Serialize the activity as a TOML document
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ActivityFormat` if the activity cannot be represented in TOML.
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .map_err(|err| DiscordIpcError::activity_format("TOML", err.to_string()))
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl Activity {
+    /// Parse an activity from a YAML document and validate it
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ActivityFormat` if the document cannot be parsed, or
+    /// `DiscordIpcError::InvalidActivity` if the activity fails validation.
+    pub fn from_yaml_str(input: &str) -> Result<Self> {
+        let activity: Self = serde_yaml::from_str(input)
+            .map_err(|err| DiscordIpcError::activity_format("YAML", err.to_string()))?;
+        activity.validate()?;
+        Ok(activity)
+    }
+
+    /// Serialize the activity as a YAML document
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ActivityFormat` if the activity cannot be represented in YAML.
+    pub fn to_yaml_string(&self) -> Result<String> {
+        serde_yaml::to_string(self)
+            .map_err(|err| DiscordIpcError::activity_format("YAML", err.to_string()))
+    }
+}
+
+#[cfg(feature = "schema")]
+impl Activity {
+    /// JSON Schema describing activity files
+    ///
+    /// Point an editor at the exported schema to get completion and inline
+    /// validation for presence files written in JSON, TOML or YAML.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(Self))
+            .expect("JSON Schema is always representable as JSON")
+    }
+}
+
+#[cfg(all(test, any(feature = "toml", feature = "yaml", feature = "schema")))]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "toml", feature = "yaml"))]
+    use crate::activity::ActivityBuilder;
+    #[cfg(feature = "toml")]
+    use crate::activity::ActivityValidationError;
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_roundtrip_preserves_fields() {
+        let activity = ActivityBuilder::new()
+            .state("Exploring")
+            .large_image("logo")
+            .party("group", 1, 4)
+            .button("Join", "https://example.com/join")
+            .build();
+
+        let text = activity.to_toml_string().unwrap();
+        let parsed = Activity::from_toml_str(&text).unwrap();
+
+        assert_eq!(parsed.state.as_deref(), Some("Exploring"));
+        assert_eq!(parsed.party.and_then(|p| p.size), Some([1, 4]));
+        assert_eq!(parsed.buttons.unwrap()[0].label, "Join");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_load_runs_validation() {
+        let err = Activity::from_toml_str("state = \"ok\"\n[party]\nsize = [5, 4]\n").unwrap_err();
+        assert!(matches!(
+            err,
+            DiscordIpcError::InvalidActivity(ActivityValidationError::PartySizeExceedsMax { .. })
+        ));
+
+        let err = Activity::from_toml_str("state = [").unwrap_err();
+        assert!(matches!(err, DiscordIpcError::ActivityFormat { .. }));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_roundtrip_preserves_fields() {
+        let activity = ActivityBuilder::new()
+            .details("Ranked")
+            .start_timestamp(1_700_000_000)
+            .build();
+
+        let text = activity.to_yaml_string().unwrap();
+        let parsed = Activity::from_yaml_str(&text).unwrap();
+
+        assert_eq!(parsed.details.as_deref(), Some("Ranked"));
+        assert_eq!(parsed.timestamps.and_then(|t| t.start), Some(1_700_000_000));
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn json_schema_lists_activity_properties() {
+        let schema = Activity::json_schema();
+        let properties = schema["properties"].as_object().expect("schema properties");
+        assert!(properties.contains_key("state"));
+        assert!(properties.contains_key("buttons"));
+        assert_eq!(properties["state"]["maxLength"], 128);
+    }
+}
@@ -2,7 +2,14 @@
 // Copyright (c) 2025-2026 Sreehari Anil and project contributors
 
 pub mod builder;
+mod format;
+pub mod rotation;
+pub mod stack;
+pub mod timestamp;
 pub mod types;
 
 pub use builder::*;
+pub use rotation::ActivityRotation;
+pub use stack::{PresenceStack, PresenceUpdate};
+pub use timestamp::*;
 pub use types::*;
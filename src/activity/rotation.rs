@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Timed rotation through several activities
+//!
+//! Build an [`ActivityRotation`] here, then drive a client with
+//! `ActivityRotation::spawn` (sync) or `ActivityRotation::spawn_async` (async).
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::activity::types::Activity;
+use crate::ipc::RateLimitConfig;
+
+enum RotationSource {
+    Fixed(Box<Activity>),
+    Dynamic(Box<dyn FnMut() -> Activity + Send>),
+}
+
+/// Control messages sent to a running rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RotationCommand {
+    Pause,
+    Resume,
+    Stop,
+}
+
+struct RotationEntry {
+    source: RotationSource,
+    duration: Duration,
+}
+
+/// A list of activities shown in turn, each for its own duration
+///
+/// Entries are either fixed activities or closures that build a fresh activity every
+/// time their turn comes up. Durations shorter than Discord's update budget allows
+/// (4 seconds with the default 5 updates per 20 seconds) are stretched to that minimum.
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::{ActivityBuilder, ActivityRotation, DiscordIpcClient};
+/// use std::time::Duration;
+///
+/// let mut client = DiscordIpcClient::new("client_id")?;
+/// client.connect()?;
+///
+/// let rotation = ActivityRotation::new()
+///     .add(ActivityBuilder::new().state("Map: Dust").build(), Duration::from_secs(15))
+///     .add_with(Duration::from_secs(10), || {
+///         ActivityBuilder::new().state(format!("Players: {}", 12)).build()
+///     })
+///     .spawn(client)?;
+///
+/// rotation.pause();
+/// rotation.resume();
+/// let client = rotation.stop()?;
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+#[derive(Default)]
+pub struct ActivityRotation {
+    entries: Vec<RotationEntry>,
+    rate_limit: RateLimitConfig,
+    next: usize,
+}
+
+impl fmt::Debug for ActivityRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActivityRotation")
+            .field("entries", &self.entries.len())
+            .field("rate_limit", &self.rate_limit)
+            .finish()
+    }
+}
+
+impl ActivityRotation {
+    /// Create an empty rotation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a fixed activity shown for `duration`
+    pub fn add(mut self, activity: Activity, duration: Duration) -> Self {
+        self.entries.push(RotationEntry {
+            source: RotationSource::Fixed(Box::new(activity)),
+            duration,
+        });
+        self
+    }
+
+    /// Add an activity built by `make` each time its turn comes up
+    pub fn add_with<F>(mut self, duration: Duration, make: F) -> Self
+    where
+        F: FnMut() -> Activity + Send + 'static,
+    {
+        self.entries.push(RotationEntry {
+            source: RotationSource::Dynamic(Box::new(make)),
+            duration,
+        });
+        self
+    }
+
+    /// Use a different update budget when enforcing the minimum entry duration
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Number of entries in the rotation
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the rotation has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The shortest time an entry is shown without exceeding the update budget
+    pub fn min_duration(&self) -> Duration {
+        self.rate_limit.window / self.rate_limit.max_updates.max(1)
+    }
+
+    /// Produce the next activity and how long to show it, advancing the rotation
+    pub(crate) fn next_entry(&mut self) -> Option<(Activity, Duration)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let min_duration = self.min_duration();
+        let index = self.next;
+        self.next = (index + 1) % self.entries.len();
+        let entry = &mut self.entries[index];
+
+        let activity = match &mut entry.source {
+            RotationSource::Fixed(activity) => (**activity).clone(),
+            RotationSource::Dynamic(make) => make(),
+        };
+        Some((activity, entry.duration.max(min_duration)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+
+    #[test]
+    fn cycles_entries_and_enforces_min_duration() {
+        let mut count = 0;
+        let mut rotation = ActivityRotation::new()
+            .add(
+                ActivityBuilder::new().state("fixed").build(),
+                Duration::from_secs(10),
+            )
+            .add_with(Duration::from_millis(10), move || {
+                count += 1;
+                ActivityBuilder::new()
+                    .state(format!("call {count}"))
+                    .build()
+            });
+
+        let (first, duration) = rotation.next_entry().unwrap();
+        assert_eq!(first.state.as_deref(), Some("fixed"));
+        assert_eq!(duration, Duration::from_secs(10));
+
+        let (second, duration) = rotation.next_entry().unwrap();
+        assert_eq!(second.state.as_deref(), Some("call 1"));
+        assert_eq!(duration, Duration::from_secs(4));
+
+        rotation.next_entry();
+        let (again, _) = rotation.next_entry().unwrap();
+        assert_eq!(again.state.as_deref(), Some("call 2"));
+    }
+
+    #[test]
+    fn empty_rotation_has_no_entries() {
+        assert!(ActivityRotation::new().next_entry().is_none());
+    }
+}
@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Layered presence with priorities and expiry
+//!
+//! Build a [`PresenceStack`] here, then push its changes to a client with
+//! `PresenceStack::apply` (sync) or `PresenceStack::apply_async` (async), or hand
+//! both to `PresenceStack::spawn` / `spawn_async`, which also restore lower layers
+//! when the top one expires.
+
+use std::time::{Duration, Instant};
+
+use crate::activity::types::Activity;
+
+#[derive(Debug, Clone)]
+struct Layer {
+    name: String,
+    priority: i32,
+    activity: Activity,
+    expires_at: Option<Instant>,
+    revision: u64,
+}
+
+/// What Discord was last told to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Applied {
+    #[default]
+    Nothing,
+    Layer(u64),
+    Unknown,
+}
+
+/// Messages from a stack handle to the thread or task driving the stack
+#[derive(Debug)]
+pub(crate) enum StackCommand {
+    Push {
+        name: String,
+        priority: i32,
+        activity: Box<Activity>,
+        ttl: Option<Duration>,
+    },
+    Remove(String),
+    Stop,
+}
+
+/// A change to send to Discord after the stack was modified
+#[derive(Debug, Clone)]
+pub enum PresenceUpdate {
+    /// Show this activity
+    Set(Box<Activity>),
+    /// No layer is left; clear the activity
+    Clear,
+}
+
+/// Named presence layers; the highest-priority live layer is shown
+///
+/// Pushing to an existing layer name replaces that layer. When the top layer is
+/// removed or expires, the next one is shown again. Ties in priority go to the most
+/// recently pushed layer.
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::{ActivityBuilder, DiscordIpcClient, PresenceStack};
+/// use std::time::Duration;
+///
+/// let mut client = DiscordIpcClient::new("client_id")?;
+/// client.connect()?;
+///
+/// let mut stack = PresenceStack::new();
+/// stack.push("game", 0, ActivityBuilder::new().state("In a match").build());
+/// stack.push_for(
+///     "achievement",
+///     10,
+///     ActivityBuilder::new().state("Achievement unlocked!").build(),
+///     Duration::from_secs(8),
+/// );
+/// stack.apply(&mut client)?; // shows the achievement
+///
+/// // Later, e.g. from the main loop: restores "In a match" once the banner expires
+/// stack.apply(&mut client)?;
+///
+/// // Or let a background thread apply it, including when layers expire
+/// let stack = stack.spawn(client)?;
+/// stack.remove("game");
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct PresenceStack {
+    layers: Vec<Layer>,
+    revision: u64,
+    applied: Applied,
+}
+
+impl PresenceStack {
+    /// Create an empty stack
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a layer that stays until it is removed
+    pub fn push(&mut self, name: impl Into<String>, priority: i32, activity: Activity) {
+        self.insert(name.into(), priority, activity, None);
+    }
+
+    /// Set a layer that expires after `ttl`
+    ///
+    /// A `ttl` too large to represent never expires.
+    pub fn push_for(
+        &mut self,
+        name: impl Into<String>,
+        priority: i32,
+        activity: Activity,
+        ttl: Duration,
+    ) {
+        self.insert(
+            name.into(),
+            priority,
+            activity,
+            Instant::now().checked_add(ttl),
+        );
+    }
+
+    /// Remove a layer, returning its activity
+    pub fn remove(&mut self, name: &str) -> Option<Activity> {
+        let index = self.layers.iter().position(|layer| layer.name == name)?;
+        Some(self.layers.remove(index).activity)
+    }
+
+    /// Returns `true` if a live layer with this name exists
+    pub fn contains(&self, name: &str) -> bool {
+        let now = Instant::now();
+        self.layers
+            .iter()
+            .any(|layer| layer.name == name && is_live(layer, now))
+    }
+
+    /// Name of the layer that is currently on top
+    pub fn top_layer(&self) -> Option<&str> {
+        self.top(Instant::now()).map(|layer| layer.name.as_str())
+    }
+
+    /// The activity that should currently be shown
+    pub fn current(&self) -> Option<&Activity> {
+        self.top(Instant::now()).map(|layer| &layer.activity)
+    }
+
+    /// When the next layer expires, so callers know when to call `apply` again
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.expires_at)
+            .min()
+    }
+
+    /// Drop expired layers and return the change to send, if the visible activity changed
+    ///
+    /// Use this to drive a client yourself; `apply` and `apply_async` call it for you.
+    pub fn take_update(&mut self) -> Option<PresenceUpdate> {
+        self.take_update_at(Instant::now())
+    }
+
+    pub(crate) fn take_update_at(&mut self, now: Instant) -> Option<PresenceUpdate> {
+        self.layers.retain(|layer| is_live(layer, now));
+
+        let top = self
+            .top(now)
+            .map(|layer| (layer.revision, layer.activity.clone()));
+        match top {
+            Some((revision, _)) if self.applied == Applied::Layer(revision) => None,
+            Some((revision, activity)) => {
+                self.applied = Applied::Layer(revision);
+                Some(PresenceUpdate::Set(Box::new(activity)))
+            }
+            None if self.applied == Applied::Nothing => None,
+            None => {
+                self.applied = Applied::Nothing;
+                Some(PresenceUpdate::Clear)
+            }
+        }
+    }
+
+    /// Forget what was last applied, e.g. after reconnecting, so the next update resends
+    pub fn invalidate(&mut self) {
+        self.applied = Applied::Unknown;
+    }
+
+    /// Carry out a command from a stack handle; returns `false` on `Stop`
+    pub(crate) fn handle(&mut self, command: StackCommand) -> bool {
+        match command {
+            StackCommand::Push {
+                name,
+                priority,
+                activity,
+                ttl: Some(ttl),
+            } => self.push_for(name, priority, *activity, ttl),
+            StackCommand::Push {
+                name,
+                priority,
+                activity,
+                ttl: None,
+            } => self.push(name, priority, *activity),
+            StackCommand::Remove(name) => {
+                self.remove(&name);
+            }
+            StackCommand::Stop => return false,
+        }
+        true
+    }
+
+    fn insert(
+        &mut self,
+        name: String,
+        priority: i32,
+        activity: Activity,
+        expires_at: Option<Instant>,
+    ) {
+        self.revision += 1;
+        let layer = Layer {
+            name,
+            priority,
+            activity,
+            expires_at,
+            revision: self.revision,
+        };
+
+        match self.layers.iter_mut().find(|l| l.name == layer.name) {
+            Some(existing) => *existing = layer,
+            None => self.layers.push(layer),
+        }
+    }
+
+    fn top(&self, now: Instant) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .filter(|layer| is_live(layer, now))
+            .max_by_key(|layer| (layer.priority, layer.revision))
+    }
+}
+
+fn is_live(layer: &Layer, now: Instant) -> bool {
+    layer.expires_at.map_or(true, |expires_at| expires_at > now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+
+    fn activity(state: &str) -> Activity {
+        ActivityBuilder::new().state(state).build()
+    }
+
+    fn state(update: Option<PresenceUpdate>) -> Option<String> {
+        match update {
+            Some(PresenceUpdate::Set(activity)) => activity.state.clone(),
+            Some(PresenceUpdate::Clear) => Some("<clear>".to_string()),
+            None => None,
+        }
+    }
+
+    #[test]
+    fn highest_priority_wins_and_expiry_restores_lower_layer() {
+        let mut stack = PresenceStack::new();
+        let now = Instant::now();
+
+        stack.push("game", 0, activity("game"));
+        stack.push_for("banner", 10, activity("banner"), Duration::from_secs(5));
+        assert_eq!(state(stack.take_update_at(now)).as_deref(), Some("banner"));
+        assert!(stack.take_update_at(now).is_none());
+
+        let later = now + Duration::from_secs(6);
+        assert_eq!(state(stack.take_update_at(later)).as_deref(), Some("game"));
+        assert!(stack.next_expiry().is_none());
+    }
+
+    #[test]
+    fn huge_ttl_never_expires() {
+        let mut stack = PresenceStack::new();
+        stack.push_for("forever", 0, activity("forever"), Duration::MAX);
+        assert!(stack.next_expiry().is_none());
+        assert_eq!(state(stack.take_update()).as_deref(), Some("forever"));
+    }
+
+    #[test]
+    fn removing_layers_restores_and_finally_clears() {
+        let mut stack = PresenceStack::new();
+        let now = Instant::now();
+
+        stack.push("game", 0, activity("game"));
+        stack.push("streaming", 5, activity("streaming"));
+        assert_eq!(
+            state(stack.take_update_at(now)).as_deref(),
+            Some("streaming")
+        );
+
+        // Replacing a lower layer does not change what is shown
+        stack.push("game", 0, activity("menu"));
+        assert!(stack.take_update_at(now).is_none());
+
+        stack.remove("streaming");
+        assert_eq!(state(stack.take_update_at(now)).as_deref(), Some("menu"));
+
+        stack.remove("game");
+        assert_eq!(state(stack.take_update_at(now)).as_deref(), Some("<clear>"));
+        assert!(stack.take_update_at(now).is_none());
+    }
+}
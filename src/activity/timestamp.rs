@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Unit-safe timestamps for activity start and end times
+
+use crate::activity::types::ActivityValidationError;
+use crate::error::{DiscordIpcError, Result};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Largest Unix timestamp, in seconds, accepted for activity timestamps.
+///
+/// This is roughly the year 5138. Current Unix times in milliseconds are over
+/// ten times larger, so a value above this limit almost certainly has the wrong unit.
+pub const MAX_TIMESTAMP_SECS: u64 = 100_000_000_000;
+
+/// A point in time for activity timestamps, stored as whole seconds since the Unix epoch.
+///
+/// Use this instead of raw integers so the unit is always explicit. It serializes as
+/// the number of seconds, which is what `ActivityTimestamps` sends to Discord.
+///
+/// # Examples
+///
+/// ```
+/// use presenceforge::{ActivityBuilder, PresenceTimestamp};
+/// use std::time::Duration;
+///
+/// let activity = ActivityBuilder::new()
+///     .start_at(PresenceTimestamp::now()?)
+///     .end_at(PresenceTimestamp::from_now(Duration::from_secs(600))?)?
+///     .build();
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PresenceTimestamp(u64);
+
+impl PresenceTimestamp {
+    /// The current time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system time is before the UNIX epoch.
+    pub fn now() -> Result<Self> {
+        Self::from_system_time(SystemTime::now())
+    }
+
+    /// A time `offset` from now
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system time is before the UNIX epoch.
+    pub fn from_now(offset: Duration) -> Result<Self> {
+        Ok(Self::now()?.saturating_add(offset))
+    }
+
+    /// A time `offset` before now
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system time is before the UNIX epoch.
+    pub fn ago(offset: Duration) -> Result<Self> {
+        Ok(Self::now()?.saturating_sub(offset))
+    }
+
+    /// Convert a `SystemTime`, truncating to whole seconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::SystemTimeError` if `time` is before the UNIX epoch.
+    pub fn from_system_time(time: SystemTime) -> Result<Self> {
+        let since_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(DiscordIpcError::SystemTimeError)?;
+        Ok(Self(since_epoch.as_secs()))
+    }
+
+    /// Create a timestamp from seconds since the Unix epoch
+    pub const fn from_unix_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    /// Create a timestamp from signed seconds since the Unix epoch
+    ///
+    /// # Errors
+    ///
+    /// Returns `ActivityValidationError::TimestampBeforeEpoch` for negative values.
+    pub fn from_unix_secs_signed(secs: i64) -> Result<Self> {
+        u64::try_from(secs).map(Self).map_err(|_| {
+            DiscordIpcError::InvalidActivity(ActivityValidationError::TimestampBeforeEpoch {
+                value: secs,
+            })
+        })
+    }
+
+    /// Create a timestamp from milliseconds since the Unix epoch, truncating to seconds
+    pub const fn from_unix_millis(millis: u64) -> Self {
+        Self(millis / 1000)
+    }
+
+    /// Seconds since the Unix epoch
+    pub const fn as_unix_secs(self) -> u64 {
+        self.0
+    }
+
+    /// Milliseconds since the Unix epoch
+    pub const fn as_unix_millis(self) -> u64 {
+        self.0.saturating_mul(1000)
+    }
+
+    /// Convert back to a `SystemTime`
+    pub fn to_system_time(self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.0)
+    }
+
+    /// Move the timestamp forward by `offset`, saturating on overflow
+    pub fn saturating_add(self, offset: Duration) -> Self {
+        Self(self.0.saturating_add(offset.as_secs()))
+    }
+
+    /// Move the timestamp back by `offset`, stopping at the Unix epoch
+    pub fn saturating_sub(self, offset: Duration) -> Self {
+        Self(self.0.saturating_sub(offset.as_secs()))
+    }
+
+    /// Returns `true` if the value is within the range Discord can display as seconds.
+    pub const fn is_plausible(self) -> bool {
+        self.0 <= MAX_TIMESTAMP_SECS
+    }
+}
+
+impl TryFrom<SystemTime> for PresenceTimestamp {
+    type Error = DiscordIpcError;
+
+    fn try_from(time: SystemTime) -> Result<Self> {
+        Self::from_system_time(time)
+    }
+}
+
+impl From<PresenceTimestamp> for SystemTime {
+    fn from(timestamp: PresenceTimestamp) -> Self {
+        timestamp.to_system_time()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> TryFrom<chrono::DateTime<Tz>> for PresenceTimestamp {
+    type Error = DiscordIpcError;
+
+    fn try_from(time: chrono::DateTime<Tz>) -> Result<Self> {
+        Self::from_unix_secs_signed(time.timestamp())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for PresenceTimestamp {
+    type Error = DiscordIpcError;
+
+    fn try_from(time: time::OffsetDateTime) -> Result<Self> {
+        Self::from_unix_secs_signed(time.unix_timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn millis_are_truncated_to_seconds() {
+        let timestamp = PresenceTimestamp::from_unix_millis(1_700_000_000_999);
+        assert_eq!(timestamp.as_unix_secs(), 1_700_000_000);
+        assert_eq!(timestamp.as_unix_millis(), 1_700_000_000_000);
+        assert!(timestamp.is_plausible());
+        assert!(!PresenceTimestamp::from_unix_secs(1_700_000_000_000).is_plausible());
+    }
+
+    #[test]
+    fn offsets_from_now_use_seconds() {
+        let now = PresenceTimestamp::now().unwrap();
+        let later = PresenceTimestamp::from_now(Duration::from_secs(60)).unwrap();
+        let earlier = PresenceTimestamp::ago(Duration::from_secs(60)).unwrap();
+
+        assert!(later.as_unix_secs() - now.as_unix_secs() >= 59);
+        assert!(now.as_unix_secs() - earlier.as_unix_secs() >= 59);
+    }
+
+    #[test]
+    fn serializes_as_plain_seconds() {
+        let value = serde_json::to_value(PresenceTimestamp::from_unix_secs(1234)).unwrap();
+        assert_eq!(value, serde_json::json!(1234));
+    }
+
+    #[test]
+    fn system_time_roundtrip() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let timestamp = PresenceTimestamp::try_from(time).unwrap();
+        assert_eq!(SystemTime::from(timestamp), time);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_datetime_converts() {
+        use chrono::TimeZone;
+
+        let time = chrono::Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let timestamp = PresenceTimestamp::try_from(time).unwrap();
+        assert_eq!(timestamp.as_unix_secs(), 1_700_000_000);
+
+        let before_epoch = chrono::Utc.timestamp_opt(-5, 0).unwrap();
+        assert!(PresenceTimestamp::try_from(before_epoch).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_offset_datetime_converts() {
+        let time = time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let timestamp = PresenceTimestamp::try_from(time).unwrap();
+        assert_eq!(timestamp.as_unix_secs(), 1_700_000_000);
+    }
+}
@@ -3,6 +3,7 @@
 
 #![allow(clippy::collapsible_if)]
 
+use crate::activity::timestamp::MAX_TIMESTAMP_SECS;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -53,6 +54,17 @@ pub enum ActivityValidationError {
         current: u32,
         max: u32,
     },
+    StartTimestampOutOfRange {
+        max: u64,
+        actual: u64,
+    },
+    EndTimestampOutOfRange {
+        max: u64,
+        actual: i64,
+    },
+    TimestampBeforeEpoch {
+        value: i64,
+    },
 }
 
 impl fmt::Display for ActivityValidationError {
@@ -117,6 +129,17 @@ impl fmt::Display for ActivityValidationError {
                 f,
                 "Current party size cannot be greater than max party size ({current}/{max})"
             ),
+            Self::StartTimestampOutOfRange { max, actual } => write!(
+                f,
+                "Start timestamp must be Unix seconds no greater than {max} (got {actual}; milliseconds?)"
+            ),
+            Self::EndTimestampOutOfRange { max, actual } => write!(
+                f,
+                "End timestamp must be Unix seconds between 0 and {max} (got {actual}; milliseconds?)"
+            ),
+            Self::TimestampBeforeEpoch { value } => {
+                write!(f, "Timestamp is before the Unix epoch (got {value})")
+            }
         }
     }
 }
@@ -125,11 +148,14 @@ impl std::error::Error for ActivityValidationError {}
 
 /// Rich Presence Activity
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Activity {
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 128)))]
     pub state: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 128)))]
     pub details: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -146,6 +172,7 @@ pub struct Activity {
     pub secrets: Option<ActivitySecrets>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 2)))]
     pub buttons: Option<Vec<ActivityButton>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -267,6 +294,27 @@ impl Activity {
             }
         }
 
+        // Validate timestamps are in seconds, catching values given in milliseconds
+        if let Some(timestamps) = &self.timestamps {
+            if let Some(start) = timestamps.start {
+                if start > MAX_TIMESTAMP_SECS {
+                    return Err(ActivityValidationError::StartTimestampOutOfRange {
+                        max: MAX_TIMESTAMP_SECS,
+                        actual: start,
+                    });
+                }
+            }
+
+            if let Some(end) = timestamps.end {
+                if end < 0 || end as u64 > MAX_TIMESTAMP_SECS {
+                    return Err(ActivityValidationError::EndTimestampOutOfRange {
+                        max: MAX_TIMESTAMP_SECS,
+                        actual: end,
+                    });
+                }
+            }
+        }
+
         // Validate party size
         if let Some(size) = self.party.as_ref().and_then(|n| n.size) {
             if size[0] > size[1] {
@@ -281,8 +329,12 @@ impl Activity {
     }
 }
 
-/// Activity timestamps
+/// Activity timestamps, in seconds since the Unix epoch
+///
+/// Prefer building these through [`PresenceTimestamp`](crate::activity::PresenceTimestamp)
+/// (for example with `ActivityBuilder::start_at`) so the unit is explicit.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ActivityTimestamps {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start: Option<u64>,
@@ -293,22 +345,28 @@ pub struct ActivityTimestamps {
 
 /// Activity assets (images)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ActivityAssets {
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 256)))]
     pub large_image: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 128)))]
     pub large_text: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 256)))]
     pub small_image: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(length(max = 128)))]
     pub small_text: Option<String>,
 }
 
 /// Activity party information
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ActivityParty {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
@@ -320,6 +378,7 @@ pub struct ActivityParty {
 /// Activity secrets for join/spectate
 #[cfg(feature = "secrets")]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ActivitySecrets {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub join: Option<String>,
@@ -333,11 +392,169 @@ pub struct ActivitySecrets {
 
 /// Activity button
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ActivityButton {
+    #[cfg_attr(feature = "schema", schemars(length(max = 32)))]
     pub label: String,
+    #[cfg_attr(feature = "schema", schemars(length(max = 512)))]
     pub url: String,
 }
 
+/// Activity as stored by Discord, echoed back in the SET_ACTIVITY response.
+///
+/// Discord fills in fields the client never sends (`application_id`, `name`, `type`)
+/// and may normalize others, e.g. asset keys resolved to `mp:` media proxy paths or
+/// timestamps converted to milliseconds. Fields Discord rejected are left as `None`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppliedActivity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub activity_type: Option<u8>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<ActivityTimestamps>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assets: Option<ActivityAssets>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub party: Option<ActivityParty>,
+
+    /// Button labels. Discord strips the URLs from the echoed activity.
+    #[serde(
+        default,
+        deserialize_with = "button_labels",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub buttons: Option<Vec<String>>,
+
+    #[cfg(feature = "secrets")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<ActivitySecrets>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u32>,
+}
+
+impl AppliedActivity {
+    /// List the fields set on `requested` that are missing from what Discord stored.
+    ///
+    /// Only presence is compared, since Discord rewrites some values (asset keys,
+    /// timestamp units) even when it accepts them.
+    pub fn dropped_fields(&self, requested: &Activity) -> Vec<&'static str> {
+        let mut dropped = Vec::new();
+
+        if requested.state.is_some() && self.state.is_none() {
+            dropped.push("state");
+        }
+        if requested.details.is_some() && self.details.is_none() {
+            dropped.push("details");
+        }
+
+        if let Some(timestamps) = &requested.timestamps {
+            let applied = self.timestamps.as_ref();
+            if timestamps.start.is_some() && applied.and_then(|t| t.start).is_none() {
+                dropped.push("timestamps.start");
+            }
+            if timestamps.end.is_some() && applied.and_then(|t| t.end).is_none() {
+                dropped.push("timestamps.end");
+            }
+        }
+
+        if let Some(assets) = &requested.assets {
+            let applied = self.assets.as_ref();
+            if assets.large_image.is_some()
+                && applied.and_then(|a| a.large_image.as_ref()).is_none()
+            {
+                dropped.push("assets.large_image");
+            }
+            if assets.large_text.is_some() && applied.and_then(|a| a.large_text.as_ref()).is_none()
+            {
+                dropped.push("assets.large_text");
+            }
+            if assets.small_image.is_some()
+                && applied.and_then(|a| a.small_image.as_ref()).is_none()
+            {
+                dropped.push("assets.small_image");
+            }
+            if assets.small_text.is_some() && applied.and_then(|a| a.small_text.as_ref()).is_none()
+            {
+                dropped.push("assets.small_text");
+            }
+        }
+
+        if requested.party.is_some() && self.party.is_none() {
+            dropped.push("party");
+        }
+
+        if let Some(buttons) = &requested.buttons {
+            let applied = self.buttons.as_ref().map(Vec::len).unwrap_or(0);
+            if applied < buttons.len() {
+                dropped.push("buttons");
+            }
+        }
+
+        #[cfg(feature = "secrets")]
+        if let Some(secrets) = &requested.secrets {
+            let applied = self.secrets.as_ref();
+            if secrets.join.is_some() && applied.and_then(|s| s.join.as_ref()).is_none() {
+                dropped.push("secrets.join");
+            }
+            if secrets.spectate.is_some() && applied.and_then(|s| s.spectate.as_ref()).is_none() {
+                dropped.push("secrets.spectate");
+            }
+            if secrets.match_secret.is_some()
+                && applied.and_then(|s| s.match_secret.as_ref()).is_none()
+            {
+                dropped.push("secrets.match");
+            }
+        }
+
+        if requested.instance.is_some() && self.instance.is_none() {
+            dropped.push("instance");
+        }
+
+        dropped
+    }
+}
+
+/// Accept echoed buttons either as bare labels or as `{ "label": .. }` objects.
+fn button_labels<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum EchoedButton {
+        Label(String),
+        Object { label: String },
+    }
+
+    let buttons = Option::<Vec<EchoedButton>>::deserialize(deserializer)?;
+    Ok(buttons.map(|buttons| {
+        buttons
+            .into_iter()
+            .map(|button| match button {
+                EchoedButton::Label(label) | EchoedButton::Object { label } => label,
+            })
+            .collect()
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,6 +662,33 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn millisecond_timestamps_fail() {
+        let activity = Activity {
+            timestamps: Some(ActivityTimestamps {
+                start: Some(1_700_000_000_000),
+                end: None,
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            activity.validate().unwrap_err(),
+            ActivityValidationError::StartTimestampOutOfRange { .. }
+        ));
+
+        let activity = Activity {
+            timestamps: Some(ActivityTimestamps {
+                start: Some(1_700_000_000),
+                end: Some(-1),
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            activity.validate().unwrap_err(),
+            ActivityValidationError::EndTimestampOutOfRange { .. }
+        ));
+    }
+
     #[test]
     #[cfg(feature = "secrets")]
     fn buttons_and_secrets_cannot_coexist() {
@@ -467,4 +711,85 @@ mod tests {
             ActivityValidationError::ButtonsAndSecretsConflict
         ));
     }
+
+    #[test]
+    fn applied_activity_parses_discord_echo() {
+        let applied: AppliedActivity = serde_json::from_value(serde_json::json!({
+            "application_id": "1234",
+            "name": "My Game",
+            "type": 0,
+            "state": "Exploring",
+            "assets": { "large_image": "mp:external/abc" },
+            "buttons": ["Join"],
+            "metadata": { "button_urls": ["https://example.com"] }
+        }))
+        .expect("echo should deserialize");
+
+        assert_eq!(applied.application_id.as_deref(), Some("1234"));
+        assert_eq!(applied.name.as_deref(), Some("My Game"));
+        assert_eq!(applied.activity_type, Some(0));
+        assert_eq!(applied.buttons, Some(vec!["Join".to_string()]));
+    }
+
+    #[test]
+    fn applied_activity_reports_dropped_fields() {
+        let requested = Activity {
+            state: Some("Exploring".to_string()),
+            details: Some("Testing".to_string()),
+            assets: Some(ActivityAssets {
+                large_image: Some("logo".to_string()),
+                small_image: Some("icon".to_string()),
+                ..ActivityAssets::default()
+            }),
+            ..activity_with_button("Join", "https://example.com")
+        };
+
+        let applied = AppliedActivity {
+            state: Some("Exploring".to_string()),
+            assets: Some(ActivityAssets {
+                large_image: Some("mp:external/abc".to_string()),
+                ..ActivityAssets::default()
+            }),
+            ..AppliedActivity::default()
+        };
+
+        assert_eq!(
+            applied.dropped_fields(&requested),
+            vec!["details", "assets.small_image", "buttons"]
+        );
+    }
+    #[cfg(feature = "secrets")]
+    #[test]
+    fn applied_activity_reports_dropped_secrets() {
+        let requested = Activity {
+            state: Some("In a match".to_string()),
+            secrets: Some(ActivitySecrets {
+                join: Some("join".to_string()),
+                spectate: Some("spectate".to_string()),
+                match_secret: Some("match".to_string()),
+            }),
+            ..Activity::default()
+        };
+
+        // Discord echoes the activity without the secrets it did not keep
+        let applied: AppliedActivity = serde_json::from_value(serde_json::json!({
+            "state": "In a match",
+            "secrets": { "match": "match" }
+        }))
+        .expect("echo should deserialize");
+
+        assert_eq!(
+            applied.dropped_fields(&requested),
+            vec!["secrets.join", "secrets.spectate"]
+        );
+
+        let stripped = AppliedActivity {
+            state: Some("In a match".to_string()),
+            ..AppliedActivity::default()
+        };
+        assert_eq!(
+            stripped.dropped_fields(&requested),
+            vec!["secrets.join", "secrets.spectate", "secrets.match"]
+        );
+    }
 }
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Callback task for [`EventHandlers`] on the async clients
+
+use crate::async_io::dispatcher::AsyncClientHandle;
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::{EventData, EventHandlers};
+
+impl EventHandlers {
+    /// Run the callbacks from a task on runtime `R`, fed by a dispatcher
+    ///
+    /// `on_ready` fires first with the handshake's READY payload, then every event
+    /// received through `client` is dispatched. `on_disconnected` fires once the
+    /// dispatcher stops, after which the task ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::SocketClosed` if the dispatcher has already stopped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # async fn example() -> presenceforge::Result {
+    /// use presenceforge::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    /// use presenceforge::EventHandlers;
+    ///
+    /// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+    /// client.connect().await?;
+    /// client.subscribe("ACTIVITY_JOIN", serde_json::json!({})).await?;
+    /// let handle = client.into_handle();
+    ///
+    /// EventHandlers::new()
+    ///     .on_join(|join| println!("Joining with secret {}", join.secret))
+    ///     .spawn_async::<TokioRuntime>(&handle)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_async<R: ManagerRuntime>(mut self, client: &AsyncClientHandle) -> Result {
+        let mut events = client.event_receiver()?;
+        let ready = client.ready().cloned();
+
+        R::spawn(async move {
+            if let Some(ready) = ready {
+                self.dispatch(&EventData::Ready(ready));
+            }
+            while let Some(event) = events.recv().await {
+                self.dispatch(&event);
+            }
+            self.dispatch_disconnected(&DiscordIpcError::SocketClosed);
+        });
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+    use crate::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    use crate::test_support::{encode_frame, MockDiscord};
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn task_fires_ready_then_events_then_disconnected() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        let (log, mut entries) = mpsc::unbounded();
+        EventHandlers::new()
+            .on_ready({
+                let log = log.clone();
+                move |_| log.unbounded_send("ready".to_string()).unwrap()
+            })
+            .on_spectate({
+                let log = log.clone();
+                move |spectate| {
+                    log.unbounded_send(format!("spectate {}", spectate.secret))
+                        .unwrap()
+                }
+            })
+            .on_disconnected(move |_| log.unbounded_send("disconnected".to_string()).unwrap())
+            .spawn_async::<TokioRuntime>(&handle)
+            .unwrap();
+
+        mock.send_raw(&encode_frame(
+            1,
+            &json!({ "cmd": "DISPATCH", "evt": "ACTIVITY_SPECTATE", "data": { "secret": "s" }, "nonce": null }),
+        ));
+        mock.drop_connections();
+
+        let entries: Vec<String> = entries.by_ref().take(3).collect().await;
+        assert_eq!(entries, ["ready", "spectate s", "disconnected"]);
+    }
+}
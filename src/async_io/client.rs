@@ -7,21 +7,30 @@
 
 use bytes::{BufMut, BytesMut};
 use serde_json::{json, Value};
-use std::collections::VecDeque;
-use std::process;
 use std::time::{Duration, Instant};
 
-use super::traits::ipc_utils::read_u32_le;
-use super::traits::{read_exact, write_all, AsyncRead, AsyncWrite};
-use crate::activity::Activity;
+use super::traits::{write_all, AsyncRead, AsyncWrite};
+use crate::activity::{Activity, AppliedActivity};
 use crate::debug_println;
-use crate::error::{DiscordIpcError, InvalidResponseKind, Result};
-use crate::ipc::protocol::{validate_handshake_response, IpcConfig};
+use crate::error::{
+    DiscordIpcError, InvalidResponseKind, ProtocolContext, ProtocolViolationKind, Result,
+};
+use crate::ipc::pending::{PendingQueue, PendingStats};
+use crate::ipc::process::{self, PidInfo};
+use crate::ipc::protocol::{
+    constants, parse_applied_activity, validate_handshake_response, IpcConfig,
+};
+use crate::ipc::rate_limit::{RateLimiter, UpdateDecision};
+use crate::ipc::state::StateTracker;
 use crate::ipc::{
-    Command, EventData, HandshakePayload, IpcMessage, IpcResponse, Opcode, ReadyEvent,
+    Command, ConnectionState, ConnectionStateReceiver, EventData, HandshakePayload, IpcMessage,
+    IpcResponse, Opcode, ReadyEvent,
 };
 use crate::nonce::generate_nonce;
 
+/// Bytes requested from the connection per read
+pub(crate) const READ_CHUNK_SIZE: usize = 4096;
+
 /// Async implementation of Discord IPC client
 pub struct AsyncDiscordIpcClient<T>
 where
@@ -31,9 +40,12 @@ where
     client_id: String,
     read_buf: BytesMut,
     write_buf: BytesMut,
-    pending_messages: VecDeque<PendingMessage>,
+    pending_messages: PendingQueue,
     connected: bool,
     ipc_config: IpcConfig,
+    rate_limiter: Option<RateLimiter>,
+    ready: Option<ReadyEvent>,
+    state: StateTracker,
 }
 
 impl<T> AsyncDiscordIpcClient<T>
@@ -57,13 +69,19 @@ where
         connection: T,
         ipc_config: IpcConfig,
     ) -> Self {
+        // The socket is already open, so the client starts out `Connecting`
+        let mut state = StateTracker::new();
+        state.reconnecting();
         Self {
             connection,
             client_id: client_id.into(),
             read_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
             write_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
-            pending_messages: VecDeque::new(),
+            pending_messages: PendingQueue::new(&ipc_config),
             connected: false,
+            rate_limiter: ipc_config.rate_limit.clone().map(RateLimiter::new),
+            ready: None,
+            state,
             ipc_config,
         }
     }
@@ -78,8 +96,18 @@ where
     ///
     /// Returns `DiscordIpcError::HandshakeFailed` if the handshake fails
     pub async fn connect(&mut self) -> Result<Value> {
+        let (response, ready) = self.handshake().await?;
+        self.mark_connected(ready);
+        Ok(response)
+    }
+
+    /// Send the handshake and validate Discord's answer, without marking the client connected
+    pub(crate) async fn handshake(&mut self) -> Result<(Value, ReadyEvent)> {
         self.pending_messages.clear();
         self.connected = false;
+        if let Some(limiter) = self.rate_limiter.as_mut() {
+            limiter.reset_connection();
+        }
 
         let handshake = HandshakePayload {
             v: self.ipc_config.ipc_version,
@@ -89,14 +117,22 @@ where
         let payload =
             serde_json::to_value(handshake).map_err(DiscordIpcError::SerializationFailed)?;
 
+        self.state.handshaking();
         self.send_message(Opcode::Handshake, &payload).await?;
 
         let (opcode, response) = self.recv_from_connection().await?;
         debug_println!("Handshake response: {}", response);
-        validate_handshake_response(opcode, &response)?;
+        let ready = validate_handshake_response(opcode, &response).map_err(|err| {
+            self.state.handshake_failed();
+            err
+        })?;
+        Ok((response, ready))
+    }
 
+    pub(crate) fn mark_connected(&mut self, ready: ReadyEvent) {
         self.connected = true;
-        Ok(response)
+        self.state.connected(&ready);
+        self.ready = Some(ready);
     }
 
     /// Perform handshake and return the typed READY payload when available.
@@ -126,6 +162,131 @@ where
     ///
     /// Returns a `DiscordIpcError` if serialization fails or if Discord returns an error
     pub async fn set_activity(&mut self, activity: &Activity) -> Result<()> {
+        self.set_activity_for_pid(self.ipc_config.activity_pid(), activity)
+            .await
+    }
+
+    /// Set Discord Rich Presence activity and return the activity Discord stored
+    ///
+    /// Discord echoes the applied activity in its response, including fields it fills
+    /// in itself (`application_id`, `name`) and normalized assets. Compare it with the
+    /// requested activity using [`AppliedActivity::dropped_fields`].
+    ///
+    /// Returns `None` if nothing was sent: the rate limiter held the activity back
+    /// (see [`flush_pending_update`](Self::flush_pending_update)) or Discord already
+    /// shows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if the request fails or the response carries no activity
+    pub async fn set_activity_with_response(
+        &mut self,
+        activity: &Activity,
+    ) -> Result<Option<AppliedActivity>> {
+        let pid = self.ipc_config.activity_pid();
+        if !self.submit(pid, Some(activity))? {
+            return Ok(None);
+        }
+
+        let response = self.send_set_activity(activity, pid).await?;
+        self.record_sent(pid, Some(activity));
+        parse_applied_activity(&response).map(Some)
+    }
+
+    /// Sets the activity on behalf of process `pid`
+    ///
+    /// Overrides `IpcConfig::pid` for this call only. The rate limiter keeps the
+    /// latest update of each process, all sharing one budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if serialization fails or if Discord returns an error
+    pub async fn set_activity_for_pid(&mut self, pid: u32, activity: &Activity) -> Result<()> {
+        if !self.submit(pid, Some(activity))? {
+            return Ok(());
+        }
+
+        self.send_set_activity(activity, pid).await?;
+        self.record_sent(pid, Some(activity));
+        Ok(())
+    }
+
+    /// Send the update held back by the rate limiter, if the window has room for it
+    ///
+    /// With rate limiting enabled, call this periodically (for example from your main
+    /// loop) so the most recent activity is delivered. [`next_update_at`](Self::next_update_at)
+    /// tells you when it will succeed.
+    ///
+    /// Returns `true` if an update was sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if sending fails; the update stays pending.
+    pub async fn flush_pending_update(&mut self) -> Result<bool> {
+        let Some(update) = self
+            .rate_limiter
+            .as_mut()
+            .and_then(|limiter| limiter.take_ready(Instant::now()))
+        else {
+            return Ok(false);
+        };
+
+        let sent = match &update.activity {
+            Some(activity) => self.send_set_activity(activity, update.pid).await,
+            None => self.send_clear_activity(update.pid).await,
+        };
+        if let Err(err) = sent {
+            if let Some(limiter) = self.rate_limiter.as_mut() {
+                limiter.restore(update);
+            }
+            return Err(err);
+        }
+        self.record_sent(update.pid, update.activity.as_ref());
+        Ok(true)
+    }
+
+    /// Returns `true` if the rate limiter is holding back an activity update
+    pub fn has_pending_update(&self) -> bool {
+        self.rate_limiter
+            .as_ref()
+            .is_some_and(|limiter| limiter.has_pending())
+    }
+
+    /// When the rate limiter will allow the next activity update
+    ///
+    /// Returns `None` if an update can be sent right away or rate limiting is disabled.
+    pub fn next_update_at(&self) -> Option<Instant> {
+        self.rate_limiter
+            .as_ref()
+            .and_then(|limiter| limiter.next_send_at(Instant::now()))
+    }
+
+    /// Ask the rate limiter whether to send an update now; `None` clears the activity
+    ///
+    /// Returns `false` if the update was held back or Discord already shows it.
+    fn submit(&mut self, pid: u32, activity: Option<&Activity>) -> Result<bool> {
+        let Some(limiter) = self.rate_limiter.as_mut() else {
+            return Ok(true);
+        };
+        if let Some(activity) = activity {
+            activity.validate()?;
+        }
+        Ok(matches!(
+            limiter.submit(pid, activity, Instant::now()),
+            UpdateDecision::Send
+        ))
+    }
+
+    fn record_sent(&mut self, pid: u32, activity: Option<&Activity>) {
+        if let Some(limiter) = self.rate_limiter.as_mut() {
+            match activity {
+                Some(activity) => limiter.record_set(pid, activity, Instant::now()),
+                None => limiter.record_clear(pid, Instant::now()),
+            }
+        }
+    }
+
+    async fn send_set_activity(&mut self, activity: &Activity, pid: u32) -> Result<Value> {
         // Validate the activity first
         activity.validate()?;
 
@@ -135,7 +296,7 @@ where
         let message = IpcMessage {
             cmd: Command::SetActivity,
             args: json!({
-                "pid": process::id(),
+                "pid": process::host_pid(pid),
                 "activity": activity
             }),
             nonce: nonce.clone(),
@@ -181,7 +342,7 @@ where
             }
         }
 
-        Ok(())
+        Ok(response)
     }
 
     /// Clears Discord Rich Presence activity
@@ -194,13 +355,36 @@ where
     ///
     /// Returns a `DiscordIpcError` if communication fails or if Discord returns an error
     pub async fn clear_activity(&mut self) -> Result<Value> {
+        self.clear_activity_for_pid(self.ipc_config.activity_pid())
+            .await
+    }
+
+    /// Clears the activity shown on behalf of process `pid`
+    ///
+    /// With rate limiting, a clear that does not fit in the window is held back like
+    /// any other update and `Value::Null` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if communication fails or if Discord returns an error
+    pub async fn clear_activity_for_pid(&mut self, pid: u32) -> Result<Value> {
+        if !self.submit(pid, None)? {
+            return Ok(Value::Null);
+        }
+
+        let response = self.send_clear_activity(pid).await?;
+        self.record_sent(pid, None);
+        Ok(response)
+    }
+
+    async fn send_clear_activity(&mut self, pid: u32) -> Result<Value> {
         // Generate a cryptographically secure unique nonce
         let nonce = generate_nonce("clear-activity");
 
         let message = IpcMessage {
             cmd: Command::SetActivity,
             args: json!({
-                "pid": process::id(),
+                "pid": process::host_pid(pid),
                 "activity": Value::Null
             }),
             nonce: nonce.clone(),
@@ -317,8 +501,7 @@ where
             let (opcode, payload) = self.recv_from_connection().await?;
 
             if !opcode.is_frame_response() {
-                self.pending_messages
-                    .push_back(PendingMessage::new(opcode, payload));
+                self.pending_messages.push(opcode, payload);
                 continue;
             }
 
@@ -329,8 +512,7 @@ where
                 return Ok(event);
             }
 
-            self.pending_messages
-                .push_back(PendingMessage::new(opcode, payload));
+            self.pending_messages.push(opcode, payload);
         }
     }
 
@@ -357,11 +539,28 @@ where
         self.write_buf.extend_from_slice(&raw);
 
         // Write entire buffer at once
-        write_all(&mut self.connection, &self.write_buf).await?;
+        if let Err(err) = write_all(&mut self.connection, &self.write_buf).await {
+            let err = DiscordIpcError::from(err);
+            self.state.failed(&err);
+            return Err(err);
+        }
 
         Ok(())
     }
 
+    /// Send Discord a close frame if connected and mark the client closed
+    ///
+    /// Failing to send the frame is ignored. The socket itself closes when the client
+    /// is dropped.
+    pub async fn close(&mut self) {
+        if self.connected {
+            let _ = self.send_message(Opcode::Close, &json!({})).await;
+        }
+        self.pending_messages.clear();
+        self.connected = false;
+        self.state.closed();
+    }
+
     /// Receives a raw IPC message
     ///
     /// # Returns
@@ -375,32 +574,146 @@ where
         self.next_message().await
     }
 
+    /// Which PID activities are sent with, after translating out of a PID namespace
+    ///
+    /// Check this inside Flatpak or a container: with `PidSource::Unresolved`,
+    /// Discord cannot clear the activity when the process dies.
+    pub fn pid_info(&self) -> PidInfo {
+        process::resolve_pid(self.ipc_config.activity_pid())
+    }
+
     /// Returns `true` once a handshake has been successfully completed.
     pub fn is_connected(&self) -> bool {
         self.connected
     }
 
+    /// The current [`ConnectionState`]
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.get()
+    }
+
+    /// Watch [`ConnectionState`] changes from another task
+    ///
+    /// The receiver keeps working across `reconnect()` on the runtime clients and
+    /// after `into_handle()`.
+    pub fn state_receiver(&self) -> ConnectionStateReceiver {
+        self.state.receiver()
+    }
+
+    /// Counters for messages queued while waiting for something else
+    ///
+    /// The queue is bounded by `IpcConfig::pending_capacity` and
+    /// `IpcConfig::pending_max_age`; see [`PendingStats`] for what was dropped.
+    pub fn pending_stats(&mut self) -> PendingStats {
+        self.pending_messages.stats()
+    }
+
     /// Remove pending responses older than the provided `max_age` and return how many were dropped.
     pub fn cleanup_pending(&mut self, max_age: Duration) -> usize {
-        if max_age.is_zero() {
-            let dropped = self.pending_messages.len();
-            self.pending_messages.clear();
-            return dropped;
+        self.pending_messages.cleanup(max_age)
+    }
+
+    /// Hand the connection and everything that outlives it to a dispatcher
+    #[cfg(any(
+        feature = "tokio-runtime",
+        feature = "async-std-runtime",
+        feature = "smol-runtime"
+    ))]
+    pub(crate) fn into_parts(self) -> ClientParts<T> {
+        ClientParts {
+            connection: self.connection,
+            read_buf: self.read_buf,
+            ipc_config: self.ipc_config,
+            rate_limiter: self.rate_limiter,
+            ready: self.ready,
+            state: self.state,
         }
+    }
 
-        let now = Instant::now();
-        let original_len = self.pending_messages.len();
-        self.pending_messages
-            .retain(|message| now.saturating_duration_since(message.received_at) <= max_age);
-        original_len - self.pending_messages.len()
+    /// Swap in a newly opened transport, keeping the rate limiter and state observers
+    #[cfg(any(
+        feature = "tokio-runtime",
+        feature = "async-std-runtime",
+        feature = "smol-runtime"
+    ))]
+    pub(crate) fn replace_connection(&mut self, connection: T) {
+        self.connection = connection;
+        self.read_buf.clear();
+        self.pending_messages.clear();
+        self.connected = false;
+        self.ready = None;
+    }
+
+    /// Notice a connection Discord has closed without waiting for data
+    ///
+    /// Reads only what has already arrived; complete frames are queued like any other
+    /// unclaimed message. The transport's `read` must be cancel-safe.
+    #[cfg(any(
+        feature = "tokio-runtime",
+        feature = "async-std-runtime",
+        feature = "smol-runtime"
+    ))]
+    pub(crate) fn check_connection(&mut self) -> Result {
+        use futures::FutureExt;
+
+        loop {
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            let result = match self.connection.read(&mut chunk).now_or_never() {
+                None => return Ok(()),
+                Some(Ok(0)) => Err(DiscordIpcError::SocketClosed),
+                Some(Ok(n)) => {
+                    self.read_buf.extend_from_slice(&chunk[..n]);
+                    self.queue_buffered_frames()
+                }
+                Some(Err(err)) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Some(Err(err)) => Err(DiscordIpcError::ConnectionFailed(err)),
+            };
+            if let Err(err) = result {
+                self.state.failed(&err);
+                return Err(err);
+            }
+        }
+    }
+
+    #[cfg(any(
+        feature = "tokio-runtime",
+        feature = "async-std-runtime",
+        feature = "smol-runtime"
+    ))]
+    fn queue_buffered_frames(&mut self) -> Result {
+        while let Some((opcode, payload)) =
+            take_frame(&mut self.read_buf, self.ipc_config.max_payload_size)?
+        {
+            self.state.frame(opcode, &payload);
+            self.pending_messages.push(opcode, payload);
+        }
+        Ok(())
+    }
+
+    /// Shut down a connection the runtime clients have rejected
+    #[cfg(any(
+        feature = "tokio-runtime",
+        feature = "async-std-runtime",
+        feature = "smol-runtime"
+    ))]
+    pub(crate) async fn shutdown_connection(&mut self) {
+        let _ = self.connection.shutdown().await;
+        self.connected = false;
+    }
+
+    /// Transitions for the runtime clients' `reconnect()`
+    #[cfg(any(
+        feature = "tokio-runtime",
+        feature = "async-std-runtime",
+        feature = "smol-runtime"
+    ))]
+    pub(crate) fn state_mut(&mut self) -> &mut StateTracker {
+        &mut self.state
     }
 
     async fn next_message(&mut self) -> Result<(Opcode, Value)> {
         if let Some(message) = self.pending_messages.pop_front() {
-            let PendingMessage {
-                opcode, payload, ..
-            } = message;
-            return Ok((opcode, payload));
+            return Ok(message);
         }
 
         self.recv_from_connection().await
@@ -417,56 +730,44 @@ where
                 return Ok((opcode, response));
             }
 
-            self.pending_messages
-                .push_back(PendingMessage::new(opcode, response));
+            self.pending_messages.push(opcode, response);
         }
     }
 
     async fn recv_from_connection(&mut self) -> Result<(Opcode, Value)> {
-        // Read header using utility function
-        let opcode_raw = read_u32_le(&mut self.connection).await?;
-        let length = read_u32_le(&mut self.connection).await?;
-
-        // Validate payload size to prevent excessive memory allocation
-        if length > self.ipc_config.max_payload_size {
-            return Err(DiscordIpcError::invalid_response(
-                InvalidResponseKind::PayloadTooLarge,
-                format!(
-                    "Payload size {} exceeds maximum allowed size of {} bytes",
-                    length, self.ipc_config.max_payload_size
-                ),
-            ));
+        match self.read_frame().await {
+            Ok((opcode, payload)) => {
+                self.state.frame(opcode, &payload);
+                Ok((opcode, payload))
+            }
+            Err(err) => {
+                self.state.failed(&err);
+                Err(err)
+            }
         }
+    }
 
-        let opcode = Opcode::try_from(opcode_raw)?;
-
-        // Reuse read buffer for payload
-        self.read_buf.clear();
-        self.read_buf.resize(length as usize, 0);
-
-        read_exact(&mut self.connection, &mut self.read_buf[..])
-            .await
-            .map_err(|_| DiscordIpcError::SocketClosed)?;
-
-        let value: Value = serde_json::from_slice(&self.read_buf)?;
+    async fn read_frame(&mut self) -> Result<(Opcode, Value)> {
+        loop {
+            if let Some(frame) = take_frame(&mut self.read_buf, self.ipc_config.max_payload_size)? {
+                return Ok(frame);
+            }
 
-        Ok((opcode, value))
+            // Whole reads go into the buffer, so a frame split across a cancelled
+            // call is picked up again by the next one
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            match self.connection.read(&mut chunk).await {
+                Ok(0) => return Err(DiscordIpcError::SocketClosed),
+                Ok(n) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(DiscordIpcError::ConnectionFailed(err)),
+            }
+        }
     }
 
     fn take_pending_by_nonce(&mut self, expected_nonce: &str) -> Option<(Opcode, Value)> {
-        let position = self
-            .pending_messages
-            .iter()
-            .position(|message| Self::value_has_nonce(&message.payload, expected_nonce));
-
-        position.and_then(|index| {
-            self.pending_messages.remove(index).map(|message| {
-                let PendingMessage {
-                    opcode, payload, ..
-                } = message;
-                (opcode, payload)
-            })
-        })
+        self.pending_messages
+            .take(|payload| Self::value_has_nonce(payload, expected_nonce))
     }
 
     fn value_has_nonce(value: &Value, expected_nonce: &str) -> bool {
@@ -478,20 +779,14 @@ where
     }
 
     fn take_pending_event(&mut self) -> Result<Option<EventData>> {
-        let position = self
-            .pending_messages
-            .iter()
-            .position(|message| Self::value_is_event(&message.payload));
-
-        if let Some(index) = position {
-            if let Some(message) = self.pending_messages.remove(index) {
-                let response: IpcResponse = serde_json::from_value(message.payload)
+        match self.pending_messages.take(Self::value_is_event) {
+            Some((_, payload)) => {
+                let response: IpcResponse = serde_json::from_value(payload)
                     .map_err(DiscordIpcError::DeserializationFailed)?;
-                return response.parse_event();
+                response.parse_event()
             }
+            None => Ok(None),
         }
-
-        Ok(None)
     }
 
     fn value_is_event(value: &Value) -> bool {
@@ -499,19 +794,51 @@ where
     }
 }
 
-#[derive(Debug)]
-struct PendingMessage {
-    opcode: Opcode,
-    payload: Value,
-    received_at: Instant,
+/// What a dispatcher takes over from a client
+#[cfg(any(
+    feature = "tokio-runtime",
+    feature = "async-std-runtime",
+    feature = "smol-runtime"
+))]
+pub(crate) struct ClientParts<T> {
+    pub(crate) connection: T,
+    pub(crate) read_buf: BytesMut,
+    pub(crate) ipc_config: IpcConfig,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) ready: Option<ReadyEvent>,
+    pub(crate) state: StateTracker,
 }
 
-impl PendingMessage {
-    fn new(opcode: Opcode, payload: Value) -> Self {
-        Self {
-            opcode,
-            payload,
-            received_at: Instant::now(),
-        }
+/// Split the next complete frame off `buf`, if one has arrived
+pub(crate) fn take_frame(
+    buf: &mut BytesMut,
+    max_payload_size: u32,
+) -> Result<Option<(Opcode, Value)>> {
+    if buf.len() < constants::IPC_HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let opcode_raw = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+    let length = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+    // Validate payload size to prevent excessive memory allocation
+    if length > max_payload_size {
+        return Err(DiscordIpcError::protocol_violation(
+            ProtocolViolationKind::PayloadTooLarge,
+            format!(
+                "Payload size {} exceeds maximum allowed size of {} bytes",
+                length, max_payload_size
+            ),
+            ProtocolContext::with_payload(opcode_raw, length as usize),
+        ));
+    }
+    let opcode = Opcode::try_from(opcode_raw)?;
+
+    let frame_len = constants::IPC_HEADER_SIZE + length as usize;
+    if buf.len() < frame_len {
+        return Ok(None);
     }
+
+    let frame = buf.split_to(frame_len);
+    let value = serde_json::from_slice(&frame[constants::IPC_HEADER_SIZE..])?;
+    Ok(Some((opcode, value)))
 }
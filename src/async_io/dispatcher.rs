@@ -0,0 +1,890 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Concurrent requests over one async connection
+//!
+//! [`AsyncDiscordIpcClient`] takes `&mut self` for every call, so a task waiting for
+//! an event holds up every other request. Converting a connected client into a
+//! dispatcher moves the connection into a background task that reads continuously,
+//! routes replies to waiting requests by nonce and forwards events to every
+//! [`AsyncEventReceiver`]. Callers share a cloneable [`AsyncClientHandle`].
+//!
+//! The runtime wrappers spawn the dispatcher for you with `into_handle()`.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use bytes::{BufMut, BytesMut};
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
+use futures::StreamExt;
+use serde_json::{json, Value};
+
+use super::client::{take_frame, AsyncDiscordIpcClient, ClientParts, READ_CHUNK_SIZE};
+use super::traits::{write_all, AsyncRead, AsyncWrite};
+use crate::activity::{Activity, AppliedActivity};
+use crate::debug_println;
+use crate::error::{DiscordIpcError, InvalidResponseKind, Result};
+use crate::ipc::broadcast::{EventBroadcaster, EventSubscription};
+use crate::ipc::process::{self, PidInfo};
+use crate::ipc::protocol::{constants, parse_applied_activity};
+use crate::ipc::rate_limit::{RateLimiter, UpdateDecision};
+use crate::ipc::state::StateTracker;
+use crate::ipc::{
+    Command, ConnectionState, ConnectionStateReceiver, EventData, IpcMessage, IpcResponse, Opcode,
+    ReadyEvent,
+};
+use crate::nonce::generate_nonce;
+
+type Reply = oneshot::Sender<Result<(Opcode, Value)>>;
+
+/// Work sent from handles to the dispatcher task
+#[derive(Debug)]
+enum DispatchCommand {
+    Request {
+        operation: &'static str,
+        payload: Value,
+        nonce: String,
+        reply: Reply,
+    },
+    Close,
+}
+
+/// A request written to Discord and not yet answered
+struct Waiting {
+    operation: &'static str,
+    reply: Reply,
+    deadline: Option<Instant>,
+}
+
+/// What woke the dispatcher
+enum Step {
+    Read(io::Result<usize>),
+    Command(Option<DispatchCommand>),
+    Expired,
+}
+
+impl<T> AsyncDiscordIpcClient<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    /// Move a connected client into a dispatcher so several tasks can use it at once
+    ///
+    /// Returns the shared handle and the dispatcher, which must be driven with
+    /// [`AsyncDispatcher::run`] (usually by spawning it). Replies and events queued
+    /// on the client are discarded; unparsed bytes and the rate limiter carry over.
+    ///
+    /// The dispatcher cancels its pending read whenever a request has to be written,
+    /// so the connection's `read` must be cancel-safe. The Unix sockets of every
+    /// runtime and Tokio's named pipes are; the async-std and smol named pipes are
+    /// not, so their wrappers only offer `into_handle()` on Unix.
+    pub fn into_dispatcher(self) -> (AsyncClientHandle, AsyncDispatcher<T>) {
+        let ClientParts {
+            connection,
+            read_buf,
+            ipc_config,
+            rate_limiter,
+            ready,
+            state,
+        } = self.into_parts();
+        let (command_tx, command_rx) = mpsc::unbounded();
+        let broadcaster = EventBroadcaster::new();
+
+        let handle = AsyncClientHandle {
+            commands: command_tx,
+            rate_limiter: Arc::new(Mutex::new(rate_limiter)),
+            ready: ready.map(Arc::new),
+            broadcaster: broadcaster.clone(),
+            states: state.receiver(),
+            pid: ipc_config.activity_pid(),
+        };
+        let dispatcher = AsyncDispatcher {
+            connection,
+            commands: command_rx,
+            read_buf,
+            write_buf: BytesMut::with_capacity(READ_CHUNK_SIZE),
+            max_payload_size: ipc_config.max_payload_size,
+            read_timeout: ipc_config.read_timeout,
+            waiting: HashMap::new(),
+            broadcaster,
+            state,
+        };
+        (handle, dispatcher)
+    }
+}
+
+/// Background task owning a connection on behalf of [`AsyncClientHandle`]s
+pub struct AsyncDispatcher<T> {
+    connection: T,
+    commands: mpsc::UnboundedReceiver<DispatchCommand>,
+    read_buf: BytesMut,
+    write_buf: BytesMut,
+    max_payload_size: u32,
+    read_timeout: Option<Duration>,
+    waiting: HashMap<String, Waiting>,
+    broadcaster: EventBroadcaster,
+    state: StateTracker,
+}
+
+impl<T> AsyncDispatcher<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    /// Drive the connection until it closes, `close()` is called or every handle is dropped
+    ///
+    /// Requests still waiting when it stops fail with `DiscordIpcError::SocketClosed`
+    /// and event receivers end. When it stops because of `close()` or the last handle,
+    /// Discord is sent a close frame and the connection is shut down.
+    ///
+    /// `sleep` is the runtime's timer, e.g. `tokio::time::sleep`. It enforces
+    /// `IpcConfig::read_timeout`: a request Discord has not answered in time fails with
+    /// `DiscordIpcError::RequestTimeout` while the dispatcher keeps running.
+    pub async fn run<S, F>(mut self, mut sleep: S)
+    where
+        S: FnMut(Duration) -> F + Send,
+        F: Future<Output = ()> + Send,
+    {
+        loop {
+            loop {
+                match take_frame(&mut self.read_buf, self.max_payload_size) {
+                    Ok(Some((opcode, payload))) => self.route(opcode, payload),
+                    Ok(None) => break,
+                    Err(err) => {
+                        debug_println!("Dispatcher stopping on malformed frame: {}", err);
+                        self.state.failed(&err);
+                        return;
+                    }
+                }
+            }
+
+            self.expire();
+
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            let timer = match self.next_deadline() {
+                Some(at) => Either::Left(Box::pin(sleep(
+                    at.saturating_duration_since(Instant::now()),
+                ))),
+                None => Either::Right(future::pending()),
+            };
+            // Commands are polled first so requests go out even while events stream in
+            let step = {
+                let read = self.connection.read(&mut chunk);
+                match future::select(future::select(self.commands.next(), read), timer).await {
+                    Either::Left((Either::Left((command, _)), _)) => Step::Command(command),
+                    Either::Left((Either::Right((read, _)), _)) => Step::Read(read),
+                    Either::Right(_) => Step::Expired,
+                }
+            };
+
+            match step {
+                Step::Read(Ok(0)) | Step::Read(Err(_)) => {
+                    debug_println!("Dispatcher connection closed");
+                    self.state.failed(&DiscordIpcError::SocketClosed);
+                    return;
+                }
+                Step::Read(Ok(n)) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Step::Command(Some(DispatchCommand::Request {
+                    operation,
+                    payload,
+                    nonce,
+                    reply,
+                })) => {
+                    if let Err(err) = self.write(Opcode::Frame, &payload).await {
+                        self.state.failed(&err);
+                        let _ = reply.send(Err(err));
+                        return;
+                    }
+                    let deadline = self
+                        .read_timeout
+                        .and_then(|timeout| Instant::now().checked_add(timeout));
+                    self.waiting.insert(
+                        nonce,
+                        Waiting {
+                            operation,
+                            reply,
+                            deadline,
+                        },
+                    );
+                }
+                Step::Command(Some(DispatchCommand::Close)) | Step::Command(None) => {
+                    self.close().await;
+                    return;
+                }
+                Step::Expired => {}
+            }
+        }
+    }
+
+    /// When the next waiting request runs out of time
+    fn next_deadline(&self) -> Option<Instant> {
+        self.waiting
+            .values()
+            .filter_map(|waiting| waiting.deadline)
+            .min()
+    }
+
+    /// Fail the requests Discord did not answer in time
+    fn expire(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<String> = self
+            .waiting
+            .iter()
+            .filter(|(_, waiting)| waiting.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(nonce, _)| nonce.clone())
+            .collect();
+        for nonce in expired {
+            if let Some(waiting) = self.waiting.remove(&nonce) {
+                let err = DiscordIpcError::request_timeout(waiting.operation, Some(nonce));
+                let _ = waiting.reply.send(Err(err));
+            }
+        }
+    }
+
+    /// Say goodbye to Discord rather than just dropping the socket
+    async fn close(&mut self) {
+        let _ = self.write(Opcode::Close, &json!({})).await;
+        let _ = self.connection.shutdown().await;
+        self.state.closed();
+    }
+
+    async fn write(&mut self, opcode: Opcode, payload: &Value) -> Result {
+        let raw = serde_json::to_vec(payload)?;
+        self.write_buf.clear();
+        self.write_buf
+            .reserve(constants::IPC_HEADER_SIZE + raw.len());
+        self.write_buf.put_u32_le(opcode.into());
+        self.write_buf.put_u32_le(raw.len() as u32);
+        self.write_buf.extend_from_slice(&raw);
+
+        write_all(&mut self.connection, &self.write_buf).await?;
+        Ok(())
+    }
+
+    /// Replies go to the request with the same nonce; everything else carrying an event
+    /// goes to the event subscribers
+    fn route(&mut self, opcode: Opcode, payload: Value) {
+        self.state.frame(opcode, &payload);
+        if let Some(nonce) = payload.get("nonce").and_then(|nonce| nonce.as_str()) {
+            if let Some(waiting) = self.waiting.remove(nonce) {
+                let _ = waiting.reply.send(Ok((opcode, payload)));
+                return;
+            }
+        }
+
+        let event = serde_json::from_value::<IpcResponse>(payload)
+            .map_err(DiscordIpcError::DeserializationFailed)
+            .and_then(|response| response.parse_event());
+        match event {
+            Ok(Some(event)) => self.broadcaster.publish(&event),
+            Ok(None) => debug_println!("Dispatcher dropping unrouted frame"),
+            Err(err) => debug_println!("Dispatcher dropping malformed event: {}", err),
+        }
+    }
+}
+
+impl<T> Drop for AsyncDispatcher<T> {
+    fn drop(&mut self) {
+        // Also covers a dispatcher dropped without running to completion
+        self.broadcaster.close();
+    }
+}
+
+/// Cloneable, `Send + Sync` handle for issuing requests from many tasks at once
+///
+/// Requests from different clones may be in flight at the same time; each waits only
+/// for its own reply. Once the dispatcher stops, every method fails with
+/// `DiscordIpcError::SocketClosed`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "tokio-runtime")]
+/// # async fn example() -> presenceforge::Result {
+/// use presenceforge::async_io::tokio::TokioDiscordIpcClient;
+/// use presenceforge::ActivityBuilder;
+///
+/// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+/// client.connect().await?;
+/// let handle = client.into_handle();
+///
+/// let mut events = handle.event_receiver()?;
+/// tokio::spawn(async move {
+///     while let Some(event) = events.recv().await {
+///         println!("{event:?}");
+///     }
+/// });
+///
+/// // Not blocked by the task waiting for events
+/// handle
+///     .set_activity(&ActivityBuilder::new().state("Playing").build())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncClientHandle {
+    commands: mpsc::UnboundedSender<DispatchCommand>,
+    rate_limiter: Arc<Mutex<Option<RateLimiter>>>,
+    ready: Option<Arc<ReadyEvent>>,
+    broadcaster: EventBroadcaster,
+    states: ConnectionStateReceiver,
+    pid: u32,
+}
+
+impl AsyncClientHandle {
+    /// The READY payload from the handshake, if Discord sent one
+    pub fn ready(&self) -> Option<&ReadyEvent> {
+        self.ready.as_deref()
+    }
+
+    /// Sets Discord Rich Presence activity
+    ///
+    /// Honors the rate limit from `IpcConfig`, shared by every clone of the handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if validation or the request fails
+    pub async fn set_activity(&self, activity: &Activity) -> Result {
+        self.set_activity_for_pid(self.pid, activity).await
+    }
+
+    /// Sets Discord Rich Presence activity and returns the activity Discord stored
+    ///
+    /// Returns `None` if the rate limiter held the activity back or Discord already shows it.
+    pub async fn set_activity_with_response(
+        &self,
+        activity: &Activity,
+    ) -> Result<Option<AppliedActivity>> {
+        if !self.submit(self.pid, Some(activity))? {
+            return Ok(None);
+        }
+
+        let response = self.send_set_activity(activity, self.pid).await?;
+        self.record_sent(self.pid, Some(activity));
+        parse_applied_activity(&response).map(Some)
+    }
+
+    /// Sets the activity on behalf of process `pid`
+    pub async fn set_activity_for_pid(&self, pid: u32, activity: &Activity) -> Result {
+        if !self.submit(pid, Some(activity))? {
+            return Ok(());
+        }
+
+        self.send_set_activity(activity, pid).await?;
+        self.record_sent(pid, Some(activity));
+        Ok(())
+    }
+
+    /// Sends the update held back by the rate limiter, if the window has room for it
+    ///
+    /// Returns `true` if an update was sent.
+    pub async fn flush_pending_update(&self) -> Result<bool> {
+        let Some(update) = self
+            .limiter()
+            .as_mut()
+            .and_then(|limiter| limiter.take_ready(Instant::now()))
+        else {
+            return Ok(false);
+        };
+
+        let sent = match &update.activity {
+            Some(activity) => self.send_set_activity(activity, update.pid).await,
+            None => self.send_clear_activity(update.pid).await,
+        };
+        if let Err(err) = sent {
+            if let Some(limiter) = self.limiter().as_mut() {
+                limiter.restore(update);
+            }
+            return Err(err);
+        }
+        self.record_sent(update.pid, update.activity.as_ref());
+        Ok(true)
+    }
+
+    /// Returns `true` if the rate limiter is holding back an activity update
+    pub fn has_pending_update(&self) -> bool {
+        self.limiter()
+            .as_ref()
+            .is_some_and(|limiter| limiter.has_pending())
+    }
+
+    /// When the rate limiter will allow the next activity update
+    pub fn next_update_at(&self) -> Option<Instant> {
+        self.limiter()
+            .as_ref()
+            .and_then(|limiter| limiter.next_send_at(Instant::now()))
+    }
+
+    /// Clears Discord Rich Presence activity
+    pub async fn clear_activity(&self) -> Result<Value> {
+        self.clear_activity_for_pid(self.pid).await
+    }
+
+    /// Clears the activity shown on behalf of process `pid`
+    ///
+    /// Returns `Value::Null` if the rate limiter held the clear back.
+    pub async fn clear_activity_for_pid(&self, pid: u32) -> Result<Value> {
+        if !self.submit(pid, None)? {
+            return Ok(Value::Null);
+        }
+
+        let response = self.send_clear_activity(pid).await?;
+        self.record_sent(pid, None);
+        Ok(response)
+    }
+
+    async fn send_clear_activity(&self, pid: u32) -> Result<Value> {
+        let nonce = generate_nonce("clear-activity");
+        let message = IpcMessage {
+            cmd: Command::SetActivity,
+            args: json!({
+                "pid": process::host_pid(pid),
+                "activity": Value::Null
+            }),
+            nonce: nonce.clone(),
+            evt: None,
+        };
+
+        self.request("SET_ACTIVITY", serde_json::to_value(message)?, nonce)
+            .await
+    }
+
+    /// Subscribe to a Discord IPC event; deliveries arrive on every event receiver
+    pub async fn subscribe<S: Into<String>>(&self, event: S, args: Value) -> Result {
+        self.send_subscription(Command::Subscribe, event.into(), args)
+            .await
+    }
+
+    /// Unsubscribe from a Discord IPC event
+    pub async fn unsubscribe<S: Into<String>>(&self, event: S, args: Value) -> Result {
+        self.send_subscription(Command::Unsubscribe, event.into(), args)
+            .await
+    }
+
+    /// Receive every event dispatched from now on
+    ///
+    /// Each receiver gets its own copy of every event. Events that arrive while no
+    /// receiver exists are dropped. The receiver is an [`event_subscription`](Self::event_subscription)
+    /// of the default capacity that skips over the events it lost by falling behind;
+    /// use a subscription directly to be told about them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::SocketClosed` if the dispatcher has stopped.
+    pub fn event_receiver(&self) -> Result<AsyncEventReceiver> {
+        if self.is_closed() {
+            return Err(DiscordIpcError::SocketClosed);
+        }
+        Ok(AsyncEventReceiver {
+            events: self.event_subscription(0),
+        })
+    }
+
+    /// Receive every event dispatched from now on, buffering at most `capacity` of them
+    ///
+    /// A subscriber that falls behind loses its oldest events and is told so with
+    /// `DiscordIpcError::EventsLagged`.
+    /// A capacity of `0` uses [`DEFAULT_SUBSCRIPTION_CAPACITY`](crate::ipc::broadcast::DEFAULT_SUBSCRIPTION_CAPACITY).
+    pub fn event_subscription(&self, capacity: usize) -> EventSubscription {
+        self.broadcaster.subscribe(capacity)
+    }
+
+    /// Which PID activities are sent with, after translating out of a PID namespace
+    pub fn pid_info(&self) -> PidInfo {
+        process::resolve_pid(self.pid)
+    }
+
+    /// The current [`ConnectionState`]
+    pub fn connection_state(&self) -> ConnectionState {
+        self.states.get()
+    }
+
+    /// Watch [`ConnectionState`] changes; the dispatcher reports when it stops
+    pub fn state_receiver(&self) -> ConnectionStateReceiver {
+        self.states.clone()
+    }
+
+    /// Returns `true` once the dispatcher has stopped
+    pub fn is_closed(&self) -> bool {
+        self.commands.is_closed()
+    }
+
+    /// Stop the dispatcher and close the connection for every clone of this handle
+    pub fn close(&self) {
+        let _ = self.commands.unbounded_send(DispatchCommand::Close);
+    }
+
+    async fn send_set_activity(&self, activity: &Activity, pid: u32) -> Result<Value> {
+        activity.validate()?;
+
+        let nonce = generate_nonce("set-activity");
+        let message = IpcMessage {
+            cmd: Command::SetActivity,
+            args: json!({
+                "pid": process::host_pid(pid),
+                "activity": activity
+            }),
+            nonce: nonce.clone(),
+            evt: None,
+        };
+
+        self.request("SET_ACTIVITY", serde_json::to_value(message)?, nonce)
+            .await
+    }
+
+    async fn send_subscription(&self, cmd: Command, event: String, args: Value) -> Result {
+        let (operation, prefix) = match cmd {
+            Command::Unsubscribe => ("UNSUBSCRIBE", "unsubscribe"),
+            _ => ("SUBSCRIBE", "subscribe"),
+        };
+        let nonce = generate_nonce(prefix);
+        let message = IpcMessage {
+            cmd,
+            args,
+            nonce: nonce.clone(),
+            evt: Some(event),
+        };
+
+        self.request(operation, serde_json::to_value(message)?, nonce)
+            .await
+            .map(|_| ())
+    }
+
+    /// Send a frame and wait for the reply with the same nonce
+    async fn request(
+        &self,
+        operation: &'static str,
+        payload: Value,
+        nonce: String,
+    ) -> Result<Value> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .unbounded_send(DispatchCommand::Request {
+                operation,
+                payload,
+                nonce,
+                reply,
+            })
+            .map_err(|_| DiscordIpcError::SocketClosed)?;
+
+        let (opcode, response) = response
+            .await
+            .map_err(|_| DiscordIpcError::SocketClosed)??;
+        if !opcode.is_frame_response() {
+            return Err(DiscordIpcError::invalid_response(
+                InvalidResponseKind::UnexpectedOpcode,
+                format!("Expected frame response, got {:?}", opcode),
+            ));
+        }
+
+        if let Some(err) = response.get("error") {
+            return match (
+                err.get("code").and_then(|c| c.as_i64()),
+                err.get("message").and_then(|m| m.as_str()),
+            ) {
+                (Some(code), Some(message)) => {
+                    Err(DiscordIpcError::discord_error(code as i32, message))
+                }
+                _ => Err(DiscordIpcError::invalid_response(
+                    InvalidResponseKind::InvalidErrorPayload,
+                    format!("Invalid error format in response: {}", err),
+                )),
+            };
+        }
+
+        Ok(response)
+    }
+
+    /// Ask the shared rate limiter whether to send an update now; `None` clears the activity
+    fn submit(&self, pid: u32, activity: Option<&Activity>) -> Result<bool> {
+        let mut limiter = self.limiter();
+        let Some(limiter) = limiter.as_mut() else {
+            return Ok(true);
+        };
+        if let Some(activity) = activity {
+            activity.validate()?;
+        }
+        Ok(matches!(
+            limiter.submit(pid, activity, Instant::now()),
+            UpdateDecision::Send
+        ))
+    }
+
+    fn record_sent(&self, pid: u32, activity: Option<&Activity>) {
+        if let Some(limiter) = self.limiter().as_mut() {
+            match activity {
+                Some(activity) => limiter.record_set(pid, activity, Instant::now()),
+                None => limiter.record_clear(pid, Instant::now()),
+            }
+        }
+    }
+
+    fn limiter(&self) -> MutexGuard<'_, Option<RateLimiter>> {
+        self.rate_limiter
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Events forwarded by a dispatcher, obtained from [`AsyncClientHandle::event_receiver`]
+#[derive(Debug)]
+pub struct AsyncEventReceiver {
+    events: EventSubscription,
+}
+
+impl AsyncEventReceiver {
+    /// Wait for the next event; `None` once the dispatcher has stopped
+    pub async fn recv(&mut self) -> Option<EventData> {
+        loop {
+            match self.events.recv_async().await {
+                Ok(event) => return Some(event),
+                Err(DiscordIpcError::EventsLagged { .. }) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Return an event if one is already queued
+    pub fn try_recv(&mut self) -> Option<EventData> {
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => return event,
+                Err(DiscordIpcError::EventsLagged { .. }) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Events skipped so far because this receiver fell behind
+    pub fn lagged(&self) -> u64 {
+        self.events.lagged()
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::async_io::tokio::TokioDiscordIpcClient;
+    use crate::ipc::broadcast::DEFAULT_SUBSCRIPTION_CAPACITY;
+    use crate::ipc::protocol::IpcConfig;
+    use crate::test_support::{encode_frame, MockDiscord};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn requests_overlap_while_a_task_waits_for_events() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        let mut events = handle.event_receiver().unwrap();
+        let waiter = tokio::spawn(async move { events.recv().await });
+
+        let requests = (0..8).map(|i| {
+            let handle = handle.clone();
+            tokio::spawn(async move {
+                let activity = ActivityBuilder::new().state(format!("task {i}")).build();
+                handle.set_activity_with_response(&activity).await
+            })
+        });
+        for (i, request) in requests.enumerate() {
+            let applied = request.await.unwrap().unwrap().expect("not rate limited");
+            assert_eq!(applied.state, Some(format!("task {i}")));
+        }
+
+        mock.send_raw(&encode_frame(
+            1,
+            &json!({
+                "cmd": "DISPATCH",
+                "evt": "ACTIVITY_JOIN",
+                "data": { "secret": "s3cret" },
+                "nonce": null
+            }),
+        ));
+        let event = waiter.await.unwrap().expect("event");
+        assert!(matches!(event, EventData::ActivityJoin(join) if join.secret == "s3cret"));
+
+        handle.close();
+        while !handle.is_closed() {
+            tokio::task::yield_now().await;
+        }
+        assert!(matches!(
+            handle.clear_activity().await,
+            Err(DiscordIpcError::SocketClosed)
+        ));
+        let close = tokio::task::spawn_blocking(move || {
+            std::iter::from_fn(|| mock.next_frame()).find(|frame| frame.get("close").is_some())
+        })
+        .await
+        .unwrap();
+        assert!(close.is_some(), "dispatcher closes with a close frame");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscriptions_each_get_every_event_and_report_lag() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        let mut ui = handle.event_subscription(8);
+        let mut telemetry = handle.event_subscription(1);
+
+        for secret in ["a", "b", "c"] {
+            mock.send_raw(&encode_frame(
+                1,
+                &json!({
+                    "cmd": "DISPATCH",
+                    "evt": "ACTIVITY_JOIN",
+                    "data": { "secret": secret },
+                    "nonce": null
+                }),
+            ));
+        }
+        mock.drop_connections();
+
+        let mut secrets = Vec::new();
+        loop {
+            match ui.recv_async().await {
+                Ok(EventData::ActivityJoin(join)) => secrets.push(join.secret),
+                Ok(other) => panic!("unexpected event {other:?}"),
+                Err(err) => {
+                    assert!(matches!(err, DiscordIpcError::SocketClosed));
+                    break;
+                }
+            }
+        }
+        assert_eq!(secrets, ["a", "b", "c"]);
+
+        assert!(matches!(
+            telemetry.recv_async().await,
+            Err(DiscordIpcError::EventsLagged { skipped: 2 })
+        ));
+        assert!(
+            matches!(telemetry.recv_async().await, Ok(EventData::ActivityJoin(join)) if join.secret == "c")
+        );
+        assert!(matches!(
+            telemetry.recv_async().await,
+            Err(DiscordIpcError::SocketClosed)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn event_receivers_are_bounded_and_skip_lost_events() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        let mut events = handle.event_receiver().unwrap();
+        let mut everything = handle.event_subscription(2 * DEFAULT_SUBSCRIPTION_CAPACITY);
+        let total = DEFAULT_SUBSCRIPTION_CAPACITY + 2;
+        for i in 0..total {
+            mock.send_raw(&encode_frame(
+                1,
+                &json!({
+                    "cmd": "DISPATCH",
+                    "evt": "ACTIVITY_JOIN",
+                    "data": { "secret": i.to_string() },
+                    "nonce": null
+                }),
+            ));
+        }
+        mock.drop_connections();
+        while everything.recv_async().await.is_ok() {}
+
+        let mut secrets = Vec::new();
+        while let Some(EventData::ActivityJoin(join)) = events.recv().await {
+            secrets.push(join.secret);
+        }
+        assert_eq!(secrets.len(), DEFAULT_SUBSCRIPTION_CAPACITY);
+        assert_eq!(secrets[0], "2");
+        assert_eq!(events.lagged(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unanswered_requests_time_out_without_stopping_the_dispatcher() {
+        let mock = MockDiscord::start();
+        let ipc_config = IpcConfig::default().with_read_timeout(Duration::from_millis(100));
+        let mut client = TokioDiscordIpcClient::new_with_config_and_ipc_config(
+            "client",
+            Some(mock.pipe_config()),
+            ipc_config,
+        )
+        .await
+        .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        mock.set_silent(true);
+        match handle.clear_activity().await {
+            Err(DiscordIpcError::RequestTimeout { operation, nonce }) => {
+                assert_eq!(operation, "SET_ACTIVITY");
+                assert!(nonce.is_some());
+            }
+            other => panic!("expected a timeout, got {other:?}"),
+        }
+
+        mock.set_silent(false);
+        handle.clear_activity().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropping_the_last_handle_sends_a_close_frame() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        drop(handle.clone());
+        drop(handle);
+        let frame = tokio::task::spawn_blocking(move || mock.next_frame())
+            .await
+            .unwrap()
+            .expect("close frame");
+        assert!(frame.get("close").is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn state_receiver_survives_reconnect_and_handle() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        let mut states = client.state_receiver();
+        assert_eq!(states.get(), ConnectionState::Connecting);
+
+        client.connect().await.unwrap();
+        assert_eq!(
+            states.changed().await.unwrap().to_string(),
+            "Connected as mock"
+        );
+
+        client.reconnect().await.unwrap();
+        assert!(states.has_changed());
+        assert!(states.changed().await.unwrap().is_connected());
+
+        let handle = client.into_handle();
+        mock.drop_connections();
+        assert_eq!(
+            states.changed().await,
+            Some(ConnectionState::Closed { reason: None })
+        );
+        assert_eq!(
+            handle.connection_state(),
+            ConnectionState::Closed { reason: None }
+        );
+        assert_eq!(states.changed().await, None);
+    }
+}
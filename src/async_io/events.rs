@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! `Stream` adapter over the events of an async client
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+
+use super::client::AsyncDiscordIpcClient;
+use super::traits::{AsyncRead, AsyncWrite};
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::{EventData, EventKind};
+
+impl<T> AsyncDiscordIpcClient<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Stream incoming events
+    ///
+    /// The stream ends when Discord closes the socket. Other errors are yielded;
+    /// after a connection error the stream ends as well.
+    pub fn events(&mut self) -> EventStream<'_> {
+        let events = stream::unfold(Some(self), |client| async move {
+            let client = client?;
+            match client.next_event().await {
+                Ok(event) => Some((Ok(event), Some(client))),
+                Err(DiscordIpcError::SocketClosed) => None,
+                Err(err) if err.is_connection_error() => Some((Err(err), None)),
+                Err(err) => Some((Err(err), Some(client))),
+            }
+        });
+
+        EventStream {
+            events: events.boxed(),
+            kinds: Vec::new(),
+        }
+    }
+}
+
+/// Stream returned by `events()` on the async clients
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "tokio-runtime")]
+/// # async fn example() -> presenceforge::Result {
+/// use futures::StreamExt;
+/// use presenceforge::async_io::tokio::TokioDiscordIpcClient;
+/// use presenceforge::{EventData, EventKind};
+///
+/// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+/// client.connect().await?;
+/// client.subscribe("ACTIVITY_JOIN", serde_json::json!({})).await?;
+///
+/// let mut joins = client.events().of_kind(EventKind::ActivityJoin);
+/// while let Some(event) = joins.next().await {
+///     if let EventData::ActivityJoin(join) = event? {
+///         println!("Joining with secret {}", join.secret);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventStream<'a> {
+    events: BoxStream<'a, Result<EventData>>,
+    kinds: Vec<EventKind>,
+}
+
+impl EventStream<'_> {
+    /// Only yield events of `kind`; call repeatedly to accept several kinds
+    pub fn of_kind(mut self, kind: EventKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+}
+
+impl Stream for EventStream<'_> {
+    type Item = Result<EventData>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.events.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(event)))
+                    if !self.kinds.is_empty() && !self.kinds.contains(&event.kind()) => {}
+                other => return other,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+    use crate::async_io::tokio::TokioDiscordIpcClient;
+    use crate::test_support::{encode_frame, MockDiscord};
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn filters_by_kind_and_ends_when_the_socket_closes() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+
+        for (evt, data) in [
+            ("ACTIVITY_JOIN", json!({ "secret": "one" })),
+            ("GUILD_STATUS", json!({})),
+            ("ACTIVITY_JOIN", json!({ "secret": "two" })),
+        ] {
+            mock.send_raw(&encode_frame(
+                1,
+                &json!({ "cmd": "DISPATCH", "evt": evt, "data": data, "nonce": null }),
+            ));
+        }
+        mock.drop_connections();
+
+        let secrets: Vec<String> = client
+            .events()
+            .of_kind(EventKind::ActivityJoin)
+            .map(|event| match event.unwrap() {
+                EventData::ActivityJoin(join) => join.secret,
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect()
+            .await;
+        assert_eq!(secrets, ["one", "two"]);
+    }
+}
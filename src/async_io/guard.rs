@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! RAII guard that clears the presence of an async client
+
+use std::ops::{Deref, DerefMut};
+
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::error::Result;
+
+/// Owns an async client and clears its activity when dropped
+///
+/// `Drop` cannot wait, so dropping the guard spawns a short task on runtime `R` that
+/// clears the activity. Call [`release`](Self::release) where you can await to clear
+/// deterministically, e.g. before returning from `main`.
+///
+/// Panic hooks and signal handlers are available on the synchronous
+/// [`PresenceGuard`](crate::sync::PresenceGuard) only; they cannot await.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "tokio-runtime")]
+/// # async fn example() -> presenceforge::Result {
+/// use presenceforge::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+/// use presenceforge::async_io::AsyncPresenceGuard;
+/// use presenceforge::ActivityBuilder;
+///
+/// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+/// client.connect().await?;
+///
+/// let mut guard = AsyncPresenceGuard::<TokioRuntime>::new(client);
+/// guard
+///     .set_activity(&ActivityBuilder::new().state("Playing").build())
+///     .await?;
+///
+/// guard.release().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncPresenceGuard<R: ManagerRuntime> {
+    client: Option<R::Client>,
+}
+
+impl<R: ManagerRuntime> AsyncPresenceGuard<R> {
+    /// Guard a connected client
+    pub fn new(client: R::Client) -> Self {
+        Self {
+            client: Some(client),
+        }
+    }
+
+    /// Clear the activity now and drop the client
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `clear_activity`.
+    pub async fn release(mut self) -> Result {
+        match self.client.take() {
+            Some(mut client) => R::clear_activity(&mut client).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Disarm the guard and take back the client without clearing
+    pub fn into_inner(mut self) -> R::Client {
+        self.client.take().expect("client is present until drop")
+    }
+}
+
+impl<R: ManagerRuntime> Deref for AsyncPresenceGuard<R> {
+    type Target = R::Client;
+
+    fn deref(&self) -> &Self::Target {
+        self.client.as_ref().expect("client is present until drop")
+    }
+}
+
+impl<R: ManagerRuntime> DerefMut for AsyncPresenceGuard<R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.client.as_mut().expect("client is present until drop")
+    }
+}
+
+impl<R: ManagerRuntime> Drop for AsyncPresenceGuard<R> {
+    fn drop(&mut self) {
+        if let Some(mut client) = self.client.take() {
+            // Outside a runtime the socket simply closes with the client
+            R::try_spawn(async move {
+                let _ = R::clear_activity(&mut client).await;
+            });
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    use crate::test_support::MockDiscord;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn clears_when_dropped_inside_runtime() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+
+        let mut guard = AsyncPresenceGuard::<TokioRuntime>::new(client);
+        guard
+            .set_activity(&ActivityBuilder::new().state("Playing").build())
+            .await
+            .unwrap();
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "Playing"
+        );
+
+        drop(guard);
+        assert!(mock.next_frame().unwrap()["args"]["activity"].is_null());
+    }
+}
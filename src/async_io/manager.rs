@@ -0,0 +1,569 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Background presence manager for the async clients
+//!
+//! The async counterpart of [`crate::sync::PresenceManager`]. A spawned task owns the
+//! runtime-specific client, reconnects through the runtime's `with_retry_async`
+//! function and replays the last activity after every reconnect.
+//!
+//! Use the runtime-specific alias (`TokioPresenceManager`, `AsyncStdPresenceManager`
+//! or `SmolPresenceManager`), which the crate root re-exports.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
+use futures::{SinkExt, StreamExt};
+
+use crate::activity::Activity;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::protocol::IpcConfig;
+use crate::ipc::{PipeConfig, ReadyEvent};
+use crate::retry::RetryConfig;
+use crate::sync::manager::{ManagerCommand, IDLE_CHECK_INTERVAL};
+pub use crate::sync::manager::{PresenceManagerConfig, PresenceStatus};
+
+/// Number of updates that can be queued before `set_activity` waits for the task
+const COMMAND_BUFFER: usize = 16;
+
+/// Runtime integration for the manager task
+pub mod runtime {
+    use super::*;
+
+    /// Runtime hooks used by the manager task
+    ///
+    /// Implemented by `TokioRuntime`, `AsyncStdRuntime` and `SmolRuntime`.
+    pub trait ManagerRuntime: Send + Sync + 'static {
+        /// The runtime-specific client
+        type Client: Send + 'static;
+
+        /// Create a client and complete the handshake
+        fn connect(
+            client_id: String,
+            pipe_config: Option<PipeConfig>,
+            ipc_config: IpcConfig,
+        ) -> impl Future<Output = Result<(Self::Client, Option<ReadyEvent>)>> + Send;
+
+        /// Send `SET_ACTIVITY`
+        fn set_activity<'a>(
+            client: &'a mut Self::Client,
+            activity: &'a Activity,
+        ) -> impl Future<Output = Result> + Send + 'a;
+
+        /// Clear the activity
+        fn clear_activity(client: &mut Self::Client) -> impl Future<Output = Result> + Send + '_;
+
+        /// Send Discord a close frame before the client is dropped
+        fn close(client: &mut Self::Client) -> impl Future<Output = ()> + Send + '_;
+
+        /// When the update held back by `IpcConfig::rate_limit` can be sent, if there is one
+        fn pending_update_at(client: &Self::Client) -> Option<Instant>;
+
+        /// Send the held-back update; returns `true` if one was sent
+        fn flush_pending_update(
+            client: &mut Self::Client,
+        ) -> impl Future<Output = Result<bool>> + Send + '_;
+
+        /// Fail with a connection error if Discord has closed the connection, without waiting
+        ///
+        /// Called while idle so a Discord restart is noticed before the next update. The
+        /// default never fails; runtimes whose reads cannot be abandoned safely keep it.
+        fn check_connection(client: &mut Self::Client) -> Result {
+            let _ = client;
+            Ok(())
+        }
+
+        /// Retry with the runtime's `with_retry_async` implementation
+        fn retry<T, F, Fut>(
+            config: RetryConfig,
+            operation: F,
+        ) -> impl Future<Output = Result<T>> + Send
+        where
+            T: Send + 'static,
+            F: FnMut() -> Fut + Send + 'static,
+            Fut: Future<Output = Result<T>> + Send + 'static;
+
+        /// Sleep without blocking the executor
+        fn sleep(duration: Duration) -> impl Future<Output = ()> + Send;
+
+        /// Run the manager task in the background
+        fn spawn(task: impl Future<Output = ()> + Send + 'static);
+
+        /// Run a task in the background if a runtime is available; returns `false` if not
+        fn try_spawn(task: impl Future<Output = ()> + Send + 'static) -> bool {
+            Self::spawn(task);
+            true
+        }
+    }
+}
+
+use runtime::ManagerRuntime;
+
+/// Cheap, cloneable handle for sending updates to an async presence manager
+#[derive(Debug, Clone)]
+pub struct AsyncPresenceHandle {
+    commands: mpsc::Sender<ManagerCommand>,
+}
+
+impl AsyncPresenceHandle {
+    /// Set the activity, replacing any pending update
+    ///
+    /// The activity is validated immediately; delivery happens on the manager task
+    /// and is reported through the status stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::InvalidActivity` for invalid activities and
+    /// `DiscordIpcError::ManagerShutdown` if the manager has stopped.
+    pub async fn set_activity(&self, activity: Activity) -> Result {
+        activity.validate()?;
+        self.send(ManagerCommand::Set(Box::new(activity))).await
+    }
+
+    /// Clear the activity
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ManagerShutdown` if the manager has stopped.
+    pub async fn clear(&self) -> Result {
+        self.send(ManagerCommand::Clear).await
+    }
+
+    /// Ask the task to clear the activity (if configured), close and exit
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ManagerShutdown` if the manager has already stopped.
+    pub async fn shutdown(&self) -> Result {
+        self.send(ManagerCommand::Shutdown).await
+    }
+
+    async fn send(&self, command: ManagerCommand) -> Result {
+        self.commands
+            .clone()
+            .send(command)
+            .await
+            .map_err(|_| DiscordIpcError::ManagerShutdown)
+    }
+}
+
+/// Self-healing presence service running as a background task
+///
+/// Dropping the manager without calling [`shutdown`](Self::shutdown) still stops the
+/// task once every handle is gone, but does not wait for it.
+pub struct AsyncPresenceManager<R: ManagerRuntime> {
+    handle: AsyncPresenceHandle,
+    status: mpsc::UnboundedReceiver<PresenceStatus>,
+    done: Option<oneshot::Receiver<()>>,
+    _runtime: PhantomData<R>,
+}
+
+impl<R: ManagerRuntime> AsyncPresenceManager<R> {
+    /// Spawn the manager task on the current runtime
+    ///
+    /// The task connects lazily: this returns immediately even if Discord is not running.
+    pub fn spawn(client_id: impl Into<String>, config: PresenceManagerConfig) -> Self {
+        let (command_tx, command_rx) = mpsc::channel(COMMAND_BUFFER);
+        let (status_tx, status_rx) = mpsc::unbounded();
+        let (done_tx, done_rx) = oneshot::channel();
+
+        let task = Task::<R> {
+            client_id: client_id.into(),
+            config,
+            commands: command_rx,
+            status: status_tx,
+            client: None,
+            desired: None,
+            flush_failed: false,
+        };
+        R::spawn(async move {
+            task.run().await;
+            let _ = done_tx.send(());
+        });
+
+        Self {
+            handle: AsyncPresenceHandle {
+                commands: command_tx,
+            },
+            status: status_rx,
+            done: Some(done_rx),
+            _runtime: PhantomData,
+        }
+    }
+
+    /// Get a cloneable handle for sending updates from other tasks
+    pub fn handle(&self) -> AsyncPresenceHandle {
+        self.handle.clone()
+    }
+
+    /// Set the activity. See [`AsyncPresenceHandle::set_activity`].
+    pub async fn set_activity(&self, activity: Activity) -> Result {
+        self.handle.set_activity(activity).await
+    }
+
+    /// Clear the activity. See [`AsyncPresenceHandle::clear`].
+    pub async fn clear(&self) -> Result {
+        self.handle.clear().await
+    }
+
+    /// Wait for the next state change; `None` once the task has exited
+    pub async fn next_status(&mut self) -> Option<PresenceStatus> {
+        self.status.next().await
+    }
+
+    /// Return a state change if one is already queued
+    pub fn try_next_status(&mut self) -> Option<PresenceStatus> {
+        self.status.try_next().ok().flatten()
+    }
+
+    /// Stop the task and wait for it to clear the activity and exit
+    pub async fn shutdown(mut self) {
+        let _ = self.handle.shutdown().await;
+        if let Some(done) = self.done.take() {
+            let _ = done.await;
+        }
+    }
+}
+
+struct Task<R: ManagerRuntime> {
+    client_id: String,
+    config: PresenceManagerConfig,
+    commands: mpsc::Receiver<ManagerCommand>,
+    status: mpsc::UnboundedSender<PresenceStatus>,
+    client: Option<R::Client>,
+    desired: Option<Activity>,
+    flush_failed: bool,
+}
+
+impl<R: ManagerRuntime> Task<R> {
+    async fn run(mut self) {
+        let mut reconnecting = false;
+        let mut waiting_reported = false;
+
+        loop {
+            if self.client.is_none() {
+                match self.connect(reconnecting).await {
+                    Ok((client, ready)) => {
+                        self.client = Some(client);
+                        reconnecting = false;
+                        waiting_reported = false;
+                        self.emit(PresenceStatus::Connected {
+                            user: ready.and_then(|ready| ready.user),
+                        });
+
+                        // A fresh connection starts without an activity, so only replay a set one
+                        if self.desired.is_some() && !self.sync_presence().await {
+                            reconnecting = true;
+                            continue;
+                        }
+                    }
+                    Err(err) => {
+                        debug_println!("Presence manager connection attempt failed: {}", err);
+                        reconnecting = false;
+                        if !waiting_reported {
+                            waiting_reported = true;
+                            self.emit(PresenceStatus::WaitingForDiscord);
+                        }
+
+                        let sleep = Box::pin(R::sleep(self.config.discovery_interval));
+                        match future::select(self.commands.next(), sleep).await {
+                            Either::Left((Some(command), _)) => {
+                                let command = self.latest(command);
+                                if !self.record(command) {
+                                    break;
+                                }
+                            }
+                            Either::Left((None, _)) => break,
+                            Either::Right(_) => {}
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            let wait = match self.pending_update_at() {
+                Some(at) => at
+                    .saturating_duration_since(Instant::now())
+                    .min(IDLE_CHECK_INTERVAL),
+                None => IDLE_CHECK_INTERVAL,
+            };
+            let next = match future::select(self.commands.next(), Box::pin(R::sleep(wait))).await {
+                Either::Left((command, _)) => Some(command),
+                Either::Right(_) => None,
+            };
+            let Some(command) = next else {
+                if !self.flush_pending().await || !self.check_connection() {
+                    reconnecting = true;
+                }
+                continue;
+            };
+            let Some(command) = command else {
+                break;
+            };
+            let command = self.latest(command);
+            if !self.record(command) {
+                break;
+            }
+            if !self.sync_presence().await {
+                reconnecting = true;
+            }
+        }
+
+        self.stop().await;
+    }
+
+    fn connect(
+        &self,
+        reconnecting: bool,
+    ) -> impl Future<Output = Result<(R::Client, Option<ReadyEvent>)>> + Send + 'static {
+        let client_id = self.client_id.clone();
+        let pipe_config = self.config.pipe_config.clone();
+        let ipc_config = self.config.ipc_config.clone();
+        let retry_config = self.config.retry_config.clone();
+        let status = self.status.clone();
+
+        async move {
+            if !reconnecting {
+                return R::connect(client_id, pipe_config, ipc_config).await;
+            }
+
+            let mut attempt = 0;
+            R::retry(retry_config, move || {
+                attempt += 1;
+                let _ = status.unbounded_send(PresenceStatus::Reconnecting { attempt });
+                R::connect(client_id.clone(), pipe_config.clone(), ipc_config.clone())
+            })
+            .await
+        }
+    }
+
+    /// Skip straight to the most recent queued command; only the final state matters.
+    fn latest(&mut self, mut command: ManagerCommand) -> ManagerCommand {
+        while !matches!(command, ManagerCommand::Shutdown) {
+            match self.commands.try_next() {
+                Ok(Some(next)) => command = next,
+                _ => break,
+            }
+        }
+        command
+    }
+
+    /// When the update held back by the client's rate limiter can be sent
+    fn pending_update_at(&self) -> Option<Instant> {
+        if self.flush_failed {
+            return None;
+        }
+        self.client.as_ref().and_then(R::pending_update_at)
+    }
+
+    /// Send the held-back update; returns `false` if the connection was lost.
+    async fn flush_pending(&mut self) -> bool {
+        let Some(client) = self.client.as_mut() else {
+            return true;
+        };
+        match R::flush_pending_update(client).await {
+            Ok(true) if self.desired.is_some() => self.emit(PresenceStatus::ActivityUpdated),
+            Ok(true) => self.emit(PresenceStatus::ActivityCleared),
+            Ok(false) => {}
+            Err(err) => {
+                // Discord rejected it; leave it until the next command replaces it
+                self.flush_failed = !err.is_connection_error();
+                return self.failed(err);
+            }
+        }
+        true
+    }
+
+    /// Look for a Discord restart while idle; returns `false` if the connection was lost.
+    fn check_connection(&mut self) -> bool {
+        let Some(client) = self.client.as_mut() else {
+            return true;
+        };
+        match R::check_connection(client) {
+            Ok(()) => true,
+            Err(err) => self.failed(err),
+        }
+    }
+
+    /// Update the desired presence; returns `false` on shutdown.
+    fn record(&mut self, command: ManagerCommand) -> bool {
+        self.flush_failed = false;
+        match command {
+            ManagerCommand::Set(activity) => self.desired = Some(*activity),
+            ManagerCommand::Clear => self.desired = None,
+            ManagerCommand::Shutdown => return false,
+        }
+        true
+    }
+
+    /// Push the desired presence to Discord; returns `false` if the connection was lost.
+    async fn sync_presence(&mut self) -> bool {
+        let Some(client) = self.client.as_mut() else {
+            return true;
+        };
+
+        let result = match &self.desired {
+            Some(activity) => R::set_activity(client, activity)
+                .await
+                .map(|_| PresenceStatus::ActivityUpdated),
+            None => R::clear_activity(client)
+                .await
+                .map(|_| PresenceStatus::ActivityCleared),
+        };
+
+        match result {
+            Ok(status) => {
+                self.emit(status);
+                true
+            }
+            Err(err) => self.failed(err),
+        }
+    }
+
+    /// Report a failed update; returns `false` if the connection was lost.
+    fn failed(&mut self, err: DiscordIpcError) -> bool {
+        if err.is_connection_error() {
+            self.client = None;
+            self.emit(PresenceStatus::Disconnected {
+                reason: err.to_string(),
+            });
+            return false;
+        }
+        self.emit(PresenceStatus::UpdateFailed {
+            reason: err.to_string(),
+        });
+        true
+    }
+
+    async fn stop(&mut self) {
+        if let Some(mut client) = self.client.take() {
+            if self.config.clear_on_shutdown && self.desired.is_some() {
+                let _ = R::clear_activity(&mut client).await;
+            }
+            R::close(&mut client).await;
+        }
+        self.emit(PresenceStatus::Stopped);
+    }
+
+    fn emit(&self, status: PresenceStatus) {
+        // Nobody listening is fine; statuses are informational
+        let _ = self.status.unbounded_send(status);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::MockDiscord;
+
+    async fn replays_after_reconnect<R: ManagerRuntime>() {
+        let mock = MockDiscord::start();
+        let config = PresenceManagerConfig::default()
+            .with_pipe_config(mock.pipe_config())
+            .with_retry_config(RetryConfig::new(3, 10, 50, 2.0))
+            .with_discovery_interval(Duration::from_millis(20));
+        let mut manager = AsyncPresenceManager::<R>::spawn("client", config);
+        let presence = manager.handle();
+
+        presence
+            .set_activity(ActivityBuilder::new().state("first").build())
+            .await
+            .unwrap();
+        loop {
+            match manager.next_status().await {
+                Some(PresenceStatus::ActivityUpdated) => break,
+                Some(_) => {}
+                None => panic!("manager exited early"),
+            }
+        }
+        let frame = mock.next_frame().expect("first activity");
+        assert_eq!(frame["args"]["activity"]["state"], "first");
+
+        // The idle check notices the restart and replays without being sent anything
+        mock.drop_connections();
+        let frame = mock.next_frame().expect("replayed activity");
+        assert_eq!(frame["args"]["activity"]["state"], "first");
+
+        presence
+            .set_activity(ActivityBuilder::new().state("second").build())
+            .await
+            .unwrap();
+        let frame = mock.next_frame().expect("second activity");
+        assert_eq!(frame["args"]["activity"]["state"], "second");
+
+        let invalid = ActivityBuilder::new().state("x".repeat(200)).build();
+        assert!(presence.set_activity(invalid).await.is_err());
+
+        manager.shutdown().await;
+        let frame = mock.next_frame().expect("clear on shutdown");
+        assert!(frame["args"]["activity"].is_null());
+        let frame = mock.next_frame().expect("close on shutdown");
+        assert!(frame.get("close").is_some());
+        assert!(matches!(
+            presence.clear().await,
+            Err(DiscordIpcError::ManagerShutdown)
+        ));
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tokio_manager_flushes_held_back_updates() {
+        let mock = MockDiscord::start();
+        let ipc_config = IpcConfig::default().with_rate_limit(crate::ipc::RateLimitConfig::new(
+            1,
+            Duration::from_millis(200),
+        ));
+        let config = PresenceManagerConfig::default()
+            .with_pipe_config(mock.pipe_config())
+            .with_ipc_config(ipc_config);
+        let mut manager =
+            AsyncPresenceManager::<crate::async_io::tokio::TokioRuntime>::spawn("client", config);
+
+        manager
+            .set_activity(ActivityBuilder::new().state("first").build())
+            .await
+            .unwrap();
+        while !matches!(
+            manager.next_status().await,
+            Some(PresenceStatus::ActivityUpdated)
+        ) {}
+        manager
+            .set_activity(ActivityBuilder::new().state("final").build())
+            .await
+            .unwrap();
+
+        let frames = tokio::task::spawn_blocking(move || {
+            let first = mock.next_frame().expect("first activity");
+            let last = mock.next_frame().expect("held back activity");
+            (first, last)
+        })
+        .await
+        .unwrap();
+        assert_eq!(frames.0["args"]["activity"]["state"], "first");
+        assert_eq!(frames.1["args"]["activity"]["state"], "final");
+        manager.shutdown().await;
+    }
+
+    #[cfg(feature = "tokio-runtime")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tokio_manager_replays_after_reconnect() {
+        replays_after_reconnect::<crate::async_io::tokio::TokioRuntime>().await;
+    }
+
+    #[cfg(feature = "async-std-runtime")]
+    #[async_std::test]
+    async fn async_std_manager_replays_after_reconnect() {
+        replays_after_reconnect::<crate::async_io::async_std::AsyncStdRuntime>().await;
+    }
+
+    #[cfg(feature = "smol-runtime")]
+    #[test]
+    fn smol_manager_replays_after_reconnect() {
+        smol::block_on(replays_after_reconnect::<crate::async_io::smol::SmolRuntime>());
+    }
+}
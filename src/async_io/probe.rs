@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Concurrent probe handshakes for the runtime clients
+
+use std::future::Future;
+use std::time::Instant;
+
+use futures::future::{self, Either};
+
+use crate::async_io::client::AsyncDiscordIpcClient;
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::async_io::traits::{AsyncRead, AsyncWrite};
+use crate::debug_println;
+use crate::error::Result;
+use crate::ipc::discovery;
+use crate::ipc::probe::{probe_timeout, ProbedPipe};
+use crate::ipc::protocol::IpcConfig;
+
+/// Handshake with every candidate socket at once, opening each with `open`
+pub(crate) async fn probe_pipes<R, T, F, Fut>(
+    client_id: &str,
+    ipc_config: &IpcConfig,
+    open: F,
+) -> Vec<ProbedPipe>
+where
+    R: ManagerRuntime,
+    T: AsyncRead + AsyncWrite + Unpin,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let open = &open;
+    let probes = discovery::candidate_paths(ipc_config)
+        .into_iter()
+        .map(|path| async move {
+            let connection = open(path.clone()).await.ok()?;
+            let mut client = AsyncDiscordIpcClient::new_with_ipc_config(
+                client_id,
+                connection,
+                ipc_config.clone(),
+            );
+
+            let started = Instant::now();
+            let handshake = Box::pin(client.handshake());
+            let timeout = Box::pin(R::sleep(probe_timeout(ipc_config)));
+            let answer = future::select(handshake, timeout).await;
+            match answer {
+                Either::Left((Ok((_, ready)), _)) => {
+                    Some(ProbedPipe::new(path, ready, started.elapsed()))
+                }
+                Either::Left((Err(err), _)) => {
+                    debug_println!("Probe of {} failed: {}", path, err);
+                    None
+                }
+                Either::Right(_) => {
+                    debug_println!("Probe of {} timed out", path);
+                    None
+                }
+            }
+        });
+
+    future::join_all(probes)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
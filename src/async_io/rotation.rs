@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Driving an [`ActivityRotation`] from a background task
+
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
+use futures::StreamExt;
+
+use crate::activity::rotation::RotationCommand;
+use crate::activity::ActivityRotation;
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+
+/// Controls a rotation running as a background task
+///
+/// Dropping the handle stops the rotation and drops the client.
+#[derive(Debug)]
+pub struct AsyncRotationHandle<R: ManagerRuntime> {
+    commands: mpsc::UnboundedSender<RotationCommand>,
+    done: oneshot::Receiver<Result<R::Client>>,
+    _runtime: PhantomData<R>,
+}
+
+impl ActivityRotation {
+    /// Drive a connected async client from a task on runtime `R`
+    ///
+    /// `R` is the runtime marker, e.g. `presenceforge::async_io::tokio::TokioRuntime`.
+    /// Behaves like [`ActivityRotation::spawn`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # async fn example() -> presenceforge::Result {
+    /// use presenceforge::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    /// use presenceforge::{ActivityBuilder, ActivityRotation};
+    /// use std::time::Duration;
+    ///
+    /// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+    /// client.connect().await?;
+    ///
+    /// let rotation = ActivityRotation::new()
+    ///     .add(ActivityBuilder::new().state("Lobby").build(), Duration::from_secs(10))
+    ///     .add(ActivityBuilder::new().state("discord.gg/example").build(), Duration::from_secs(10))
+    ///     .spawn_async::<TokioRuntime>(client);
+    ///
+    /// rotation.pause();
+    /// rotation.resume();
+    /// let client = rotation.stop().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_async<R: ManagerRuntime>(self, client: R::Client) -> AsyncRotationHandle<R> {
+        let (commands, receiver) = mpsc::unbounded();
+        let (done_tx, done) = oneshot::channel();
+
+        R::spawn(async move {
+            let result = run::<R>(self, client, receiver).await;
+            let _ = done_tx.send(result);
+        });
+
+        AsyncRotationHandle {
+            commands,
+            done,
+            _runtime: PhantomData,
+        }
+    }
+}
+
+impl<R: ManagerRuntime> AsyncRotationHandle<R> {
+    /// Keep the current activity until [`resume`](Self::resume) is called
+    pub fn pause(&self) {
+        let _ = self.commands.unbounded_send(RotationCommand::Pause);
+    }
+
+    /// Continue the rotation; the current entry gets the rest of its time
+    pub fn resume(&self) {
+        let _ = self.commands.unbounded_send(RotationCommand::Resume);
+    }
+
+    /// Stop the rotation and take back the client
+    ///
+    /// # Errors
+    ///
+    /// Returns the connection error that ended the rotation early.
+    pub async fn stop(self) -> Result<R::Client> {
+        let _ = self.commands.unbounded_send(RotationCommand::Stop);
+        self.done
+            .await
+            .unwrap_or(Err(DiscordIpcError::ManagerShutdown))
+    }
+}
+
+async fn run<R: ManagerRuntime>(
+    mut rotation: ActivityRotation,
+    mut client: R::Client,
+    mut commands: mpsc::UnboundedReceiver<RotationCommand>,
+) -> Result<R::Client> {
+    loop {
+        let Some((activity, duration)) = rotation.next_entry() else {
+            // Nothing to show; just wait to be stopped
+            while !matches!(commands.next().await, Some(RotationCommand::Stop) | None) {}
+            return Ok(client);
+        };
+
+        match R::set_activity(&mut client, &activity).await {
+            Err(err) if err.is_connection_error() => return Err(err),
+            Err(err) => debug_println!("Rotation entry rejected: {}", err),
+            Ok(()) => {}
+        }
+
+        let mut deadline = Instant::now() + duration;
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            let sleep = Box::pin(R::sleep(wait));
+            match future::select(commands.next(), sleep).await {
+                Either::Right(_) => break,
+                Either::Left((Some(RotationCommand::Resume), _)) => {}
+                Either::Left((Some(RotationCommand::Pause), _)) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    loop {
+                        match commands.next().await {
+                            Some(RotationCommand::Resume) => break,
+                            Some(RotationCommand::Pause) => {}
+                            Some(RotationCommand::Stop) | None => return Ok(client),
+                        }
+                    }
+                    deadline = Instant::now() + remaining;
+                }
+                Either::Left((Some(RotationCommand::Stop) | None, _)) => return Ok(client),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    use crate::ipc::RateLimitConfig;
+    use crate::test_support::MockDiscord;
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rotates_until_stopped() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+
+        let rotation = ActivityRotation::new()
+            .with_rate_limit(RateLimitConfig::new(100, Duration::from_millis(100)))
+            .add(
+                ActivityBuilder::new().state("one").build(),
+                Duration::from_millis(20),
+            )
+            .add(
+                ActivityBuilder::new().state("two").build(),
+                Duration::from_millis(20),
+            )
+            .spawn_async::<TokioRuntime>(client);
+
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "one"
+        );
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "two"
+        );
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "one"
+        );
+
+        let mut client = rotation.stop().await.unwrap();
+        client.clear_activity().await.unwrap();
+    }
+}
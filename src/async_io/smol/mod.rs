@@ -6,6 +6,7 @@
 use std::future::Future;
 use std::io;
 use std::pin::Pin;
+use std::time::Duration;
 
 #[cfg(unix)]
 use smol::net::unix::UnixStream;
@@ -14,6 +15,8 @@ use smol::net::unix::UnixStream;
 use smol::fs::File;
 
 use crate::async_io::traits::{AsyncRead, AsyncWrite};
+use crate::async_io::wait;
+#[cfg(windows)]
 use crate::debug_println;
 use crate::error::{DiscordIpcError, Result};
 use crate::ipc::protocol::IpcConfig;
@@ -40,6 +43,16 @@ impl SmolConnection {
         config: Option<PipeConfig>,
         ipc_config: IpcConfig,
     ) -> Result<Self> {
+        Self::open(config, ipc_config)
+            .await
+            .map(|(connection, _)| connection)
+    }
+
+    /// Connect like [`Self::new_with_config_and_ipc_config`], also returning the socket path
+    pub(crate) async fn open(
+        config: Option<PipeConfig>,
+        ipc_config: IpcConfig,
+    ) -> Result<(Self, String)> {
         let config = config.unwrap_or_default();
 
         #[cfg(unix)]
@@ -68,71 +81,63 @@ impl SmolConnection {
         timeout_ms: u64,
         ipc_config: IpcConfig,
     ) -> Result<Self> {
-        use std::time::{Duration, Instant};
-
-        #[cfg(windows)]
-        debug_println!(
-            "Attempting to connect to Discord IPC with timeout {} ms (Windows)",
-            timeout_ms
-        );
-
-        #[cfg(unix)]
-        debug_println!(
-            "Attempting to connect to Discord IPC with timeout {} ms (Unix)",
-            timeout_ms
-        );
-
-        let start = Instant::now();
-        let timeout = Duration::from_millis(timeout_ms);
-        let config = config.unwrap_or_default();
-        let mut last_error_message = None;
-
-        while start.elapsed() < timeout {
-            match Self::new_with_config_and_ipc_config(Some(config.clone()), ipc_config.clone())
-                .await
-            {
-                Ok(connection) => return Ok(connection),
-                Err(DiscordIpcError::NoValidSocket) => {
-                    last_error_message = Some("No valid Discord socket found".to_string());
-                }
-                Err(DiscordIpcError::ConnectionFailed(ref source)) => {
-                    last_error_message = Some(source.to_string());
-                }
-                Err(err) => return Err(err),
-            }
+        Self::wait_for_socket(config, Duration::from_millis(timeout_ms), ipc_config).await
+    }
 
-            smol::Timer::after(Duration::from_millis(ipc_config.retry_interval_ms)).await;
-        }
+    /// Wait up to `timeout` for Discord to start, then connect
+    ///
+    /// See [`IpcConnection::wait_for_socket`](crate::ipc::IpcConnection::wait_for_socket).
+    pub async fn wait_for_socket(
+        config: Option<PipeConfig>,
+        timeout: Duration,
+        ipc_config: IpcConfig,
+    ) -> Result<Self> {
+        Self::open_within(config, timeout, ipc_config)
+            .await
+            .map(|(connection, _)| connection)
+    }
 
-        Err(DiscordIpcError::connection_timeout(
-            timeout_ms,
-            last_error_message,
-        ))
+    /// Wait like [`Self::wait_for_socket`], also returning the socket path
+    pub(crate) async fn open_within(
+        config: Option<PipeConfig>,
+        timeout: Duration,
+        ipc_config: IpcConfig,
+    ) -> Result<(Self, String)> {
+        wait::wait_for_socket::<manager::SmolRuntime, _, _, _>(
+            config,
+            timeout,
+            ipc_config,
+            Self::open,
+        )
+        .await
     }
 
     #[cfg(unix)]
     /// Connect to Discord IPC socket on Unix systems with configuration
-    async fn connect_unix_with_config(config: &PipeConfig, ipc_config: &IpcConfig) -> Result<Self> {
+    async fn connect_unix_with_config(
+        config: &PipeConfig,
+        ipc_config: &IpcConfig,
+    ) -> Result<(Self, String)> {
         match config {
-            PipeConfig::Auto => Self::connect_unix_auto(ipc_config).await,
+            PipeConfig::Auto | PipeConfig::ReleaseChannel(_) | PipeConfig::UserId(_) => {
+                Self::connect_unix_auto(ipc_config).await
+            }
             PipeConfig::CustomPath(path) => UnixStream::connect(path)
                 .await
-                .map(Self::Unix)
+                .map(|stream| (Self::Unix(stream), path.clone()))
                 .map_err(DiscordIpcError::ConnectionFailed),
         }
     }
 
     #[cfg(unix)]
     /// Connect to Discord IPC socket using auto-discovery
-    async fn connect_unix_auto(ipc_config: &IpcConfig) -> Result<Self> {
+    async fn connect_unix_auto(ipc_config: &IpcConfig) -> Result<(Self, String)> {
         let mut last_error = None;
 
-        for socket_path in
-            crate::ipc::discovery::get_socket_paths_with_limit(ipc_config.max_sockets)
-        {
+        for socket_path in crate::ipc::discovery::get_socket_paths_with_config(ipc_config) {
             match UnixStream::connect(&socket_path).await {
                 Ok(stream) => {
-                    return Ok(Self::Unix(stream));
+                    return Ok((Self::Unix(stream), socket_path));
                 }
                 Err(err) => {
                     last_error = Some(err);
@@ -162,9 +167,11 @@ impl SmolConnection {
     async fn connect_windows_with_config(
         config: &PipeConfig,
         ipc_config: &IpcConfig,
-    ) -> Result<Self> {
+    ) -> Result<(Self, String)> {
         match config {
-            PipeConfig::Auto => Self::connect_windows_auto(ipc_config).await,
+            PipeConfig::Auto | PipeConfig::ReleaseChannel(_) | PipeConfig::UserId(_) => {
+                Self::connect_windows_auto(ipc_config).await
+            }
             PipeConfig::CustomPath(path) => {
                 use std::fs::OpenOptions;
                 use std::os::windows::fs::OpenOptionsExt;
@@ -181,21 +188,21 @@ impl SmolConnection {
                 .await
                 .map_err(DiscordIpcError::ConnectionFailed)?;
 
-                Ok(Self::Windows(File::from(file)))
+                Ok((Self::Windows(File::from(file)), path.clone()))
             }
         }
     }
 
     #[cfg(windows)]
     /// Connect to Discord IPC named pipe using auto-discovery
-    async fn connect_windows_auto(ipc_config: &IpcConfig) -> Result<Self> {
+    async fn connect_windows_auto(ipc_config: &IpcConfig) -> Result<(Self, String)> {
         use std::fs::OpenOptions;
         use std::os::windows::fs::OpenOptionsExt;
         const FILE_FLAG_OVERLAPPED: u32 = 0x40000000;
 
         let mut last_error = None;
 
-        for pipe_path in crate::ipc::discovery::get_pipe_paths_with_limit(ipc_config.max_sockets) {
+        for pipe_path in crate::ipc::discovery::get_pipe_paths_with_config(ipc_config) {
             debug_println!("Attempting to connect to Windows named pipe: {}", pipe_path);
 
             // Clone pipe_path for the closure
@@ -215,7 +222,7 @@ impl SmolConnection {
             match result {
                 Ok(file) => {
                     debug_println!("Successfully opened named pipe: {}", pipe_path);
-                    return Ok(Self::Windows(File::from(file)));
+                    return Ok((Self::Windows(File::from(file)), pipe_path));
                 }
                 Err(err) => {
                     debug_println!("Failed to connect to named pipe {}: {}", pipe_path, err);
@@ -304,13 +311,30 @@ impl AsyncWrite for SmolConnection {
             }
         })
     }
+
+    fn shutdown<'a>(&'a mut self) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            match self {
+                #[cfg(unix)]
+                Self::Unix(stream) => stream.shutdown(std::net::Shutdown::Both),
+
+                // Windows pipes close when they are dropped
+                #[cfg(windows)]
+                Self::Windows(_) => Ok(()),
+            }
+        })
+    }
 }
 
 /// smol specific implementation of AsyncDiscordIpcClient
 pub mod client {
     use super::SmolConnection;
     use crate::async_io::client::AsyncDiscordIpcClient;
+    use crate::async_io::probe;
+    use crate::async_io::target;
     use crate::error::{DiscordIpcError, Result};
+    use crate::ipc::connection::SocketTarget;
+    use crate::ipc::probe::ProbedPipe;
     use crate::ipc::protocol::IpcConfig;
     use crate::ipc::PipeConfig;
     use serde_json::Value;
@@ -322,10 +346,11 @@ pub mod client {
     /// allowing you to reconnect after connection loss.
     pub struct SmolDiscordIpcClient {
         inner: AsyncDiscordIpcClient<SmolConnection>,
-        client_id: String,
         pipe_config: Option<PipeConfig>,
         timeout_ms: Option<u64>,
         ipc_config: IpcConfig,
+        /// Path of the socket the client is connected to
+        path: String,
     }
 
     impl SmolDiscordIpcClient {
@@ -337,38 +362,100 @@ pub mod client {
             ipc_config: IpcConfig,
         ) -> Result<Self> {
             let client_id = client_id.into();
-
-            let connection = if let Some(timeout) = timeout_ms {
-                SmolConnection::new_with_config_timeout_and_ipc_config(
-                    pipe_config.clone(),
-                    timeout,
-                    ipc_config.clone(),
-                )
-                .await?
-            } else {
-                SmolConnection::new_with_config_and_ipc_config(
-                    pipe_config.clone(),
-                    ipc_config.clone(),
-                )
-                .await?
-            };
+            let (connection, path) = Self::open(&pipe_config, timeout_ms, &ipc_config).await?;
 
             Ok(Self {
                 inner: AsyncDiscordIpcClient::new_with_ipc_config(
-                    client_id.clone(),
+                    client_id,
                     connection,
                     ipc_config.clone(),
                 ),
-                client_id,
                 pipe_config,
                 timeout_ms,
                 ipc_config,
+                path,
             })
         }
 
+        /// Open a socket, waiting up to `timeout_ms` for one to appear
+        async fn open(
+            pipe_config: &Option<PipeConfig>,
+            timeout_ms: Option<u64>,
+            ipc_config: &IpcConfig,
+        ) -> Result<(SmolConnection, String)> {
+            match timeout_ms {
+                Some(timeout) => {
+                    SmolConnection::open_within(
+                        pipe_config.clone(),
+                        Duration::from_millis(timeout),
+                        ipc_config.clone(),
+                    )
+                    .await
+                }
+                None => SmolConnection::open(pipe_config.clone(), ipc_config.clone()).await,
+            }
+        }
+
         /// Performs handshake with Discord
+        ///
+        /// With [`PipeConfig::ReleaseChannel`] or [`PipeConfig::UserId`], each socket is
+        /// handshaked in turn until one belongs to the requested channel or user.
         pub async fn connect(&mut self) -> Result<Value> {
-            self.inner.connect().await
+            match self.pipe_config.as_ref().and_then(PipeConfig::target) {
+                Some(target) => self.connect_target(target).await,
+                None => self.inner.connect().await,
+            }
+        }
+
+        /// Handshake on the open socket, then on each other socket, within the connect
+        /// timeout or, without one, [`IpcConfig::handshake_timeout`]
+        async fn connect_target(&mut self, target: SocketTarget) -> Result<Value> {
+            let budget = self
+                .timeout_ms
+                .map(Duration::from_millis)
+                .or(self.ipc_config.handshake_timeout);
+            let ipc_config = &self.ipc_config;
+            target::connect_target::<super::manager::SmolRuntime, _, _, _>(
+                &mut self.inner,
+                &mut self.path,
+                target,
+                budget,
+                ipc_config,
+                |path| {
+                    SmolConnection::new_with_config_and_ipc_config(
+                        Some(PipeConfig::CustomPath(path)),
+                        ipc_config.clone(),
+                    )
+                },
+            )
+            .await
+        }
+
+        /// Handshake with every discovered socket at once and report who answered
+        ///
+        /// The async counterpart of
+        /// [`IpcConnection::probe_pipes`](crate::ipc::IpcConnection::probe_pipes).
+        pub async fn probe_pipes(client_id: &str) -> Vec<ProbedPipe> {
+            Self::probe_pipes_with_config(client_id, &IpcConfig::default()).await
+        }
+
+        /// Like [`Self::probe_pipes`], scanning `ipc_config.max_sockets` sockets and
+        /// waiting `ipc_config.handshake_timeout` (if set) for each answer
+        pub async fn probe_pipes_with_config(
+            client_id: &str,
+            ipc_config: &IpcConfig,
+        ) -> Vec<ProbedPipe> {
+            probe::probe_pipes::<super::manager::SmolRuntime, _, _, _>(
+                client_id,
+                ipc_config,
+                |path| {
+                    SmolConnection::new_with_config_and_ipc_config(
+                        Some(PipeConfig::CustomPath(path)),
+                        ipc_config.clone(),
+                    )
+                },
+            )
+            .await
         }
 
         /// Perform handshake and return the typed READY payload when available.
@@ -381,21 +468,95 @@ pub mod client {
             AsyncDiscordIpcClient::<SmolConnection>::ready_event_from_payload(payload)
         }
 
+        /// Send Discord a close frame if connected and mark the client closed
+        ///
+        /// See [`AsyncDiscordIpcClient::close`]; `reconnect()` opens a new connection.
+        pub async fn close(&mut self) {
+            self.inner.close().await
+        }
+
+        /// Fail with `SocketClosed` if Discord has closed the connection, without waiting
+        ///
+        /// Unix only, for the same reason as `into_handle()`.
+        #[cfg(unix)]
+        pub(crate) fn check_connection(&mut self) -> Result {
+            self.inner.check_connection()
+        }
+
         /// Returns `true` once a handshake has been successfully completed.
         pub fn is_connected(&self) -> bool {
             self.inner.is_connected()
         }
 
+        /// Which PID activities are sent with; see [`PidInfo`](crate::PidInfo)
+        pub fn pid_info(&self) -> crate::ipc::PidInfo {
+            self.inner.pid_info()
+        }
+
+        /// The current [`ConnectionState`](crate::ConnectionState)
+        pub fn connection_state(&self) -> crate::ipc::ConnectionState {
+            self.inner.connection_state()
+        }
+
+        /// Counters for messages queued while waiting for something else
+        pub fn pending_stats(&mut self) -> crate::ipc::PendingStats {
+            self.inner.pending_stats()
+        }
+
+        /// Watch [`ConnectionState`](crate::ConnectionState) changes from another task
+        pub fn state_receiver(&self) -> crate::ipc::ConnectionStateReceiver {
+            self.inner.state_receiver()
+        }
+
         /// Sets Discord Rich Presence activity
         pub async fn set_activity(&mut self, activity: &crate::activity::Activity) -> Result<()> {
             self.inner.set_activity(activity).await
         }
 
+        /// Sets Discord Rich Presence activity and returns the activity Discord stored
+        ///
+        /// Returns `None` if the rate limiter held the activity back or Discord already shows it.
+        pub async fn set_activity_with_response(
+            &mut self,
+            activity: &crate::activity::Activity,
+        ) -> Result<Option<crate::activity::AppliedActivity>> {
+            self.inner.set_activity_with_response(activity).await
+        }
+
+        /// Sets the activity on behalf of process `pid`
+        pub async fn set_activity_for_pid(
+            &mut self,
+            pid: u32,
+            activity: &crate::activity::Activity,
+        ) -> Result<()> {
+            self.inner.set_activity_for_pid(pid, activity).await
+        }
+
+        /// Sends the update held back by the rate limiter, if the window has room for it
+        pub async fn flush_pending_update(&mut self) -> Result<bool> {
+            self.inner.flush_pending_update().await
+        }
+
+        /// Returns `true` if the rate limiter is holding back an activity update
+        pub fn has_pending_update(&self) -> bool {
+            self.inner.has_pending_update()
+        }
+
+        /// When the rate limiter will allow the next activity update
+        pub fn next_update_at(&self) -> Option<std::time::Instant> {
+            self.inner.next_update_at()
+        }
+
         /// Clears Discord Rich Presence activity
         pub async fn clear_activity(&mut self) -> Result<Value> {
             self.inner.clear_activity().await
         }
 
+        /// Clears the activity shown on behalf of process `pid`
+        pub async fn clear_activity_for_pid(&mut self, pid: u32) -> Result<Value> {
+            self.inner.clear_activity_for_pid(pid).await
+        }
+
         /// Subscribe to a Discord IPC event.
         pub async fn subscribe<S: Into<String>>(&mut self, event: S, args: Value) -> Result<()> {
             self.inner.subscribe(event, args).await
@@ -411,36 +572,31 @@ pub mod client {
             self.inner.next_event().await
         }
 
+        /// Stream incoming IPC events; ends when the socket closes.
+        pub fn events(&mut self) -> crate::async_io::EventStream<'_> {
+            self.inner.events()
+        }
+
         /// Reconnect to Discord IPC
         ///
         /// This method closes the existing connection and establishes a new one,
         /// then performs the handshake again.
         pub async fn reconnect(&mut self) -> Result<Value> {
+            self.inner.state_mut().reconnecting();
+
             // Create a new connection with the same configuration
-            let connection = if let Some(timeout) = self.timeout_ms {
-                SmolConnection::new_with_config_timeout_and_ipc_config(
-                    self.pipe_config.clone(),
-                    timeout,
-                    self.ipc_config.clone(),
-                )
-                .await?
-            } else {
-                SmolConnection::new_with_config_and_ipc_config(
-                    self.pipe_config.clone(),
-                    self.ipc_config.clone(),
-                )
-                .await?
-            };
+            let opened = Self::open(&self.pipe_config, self.timeout_ms, &self.ipc_config).await;
+            let (connection, path) = opened.map_err(|err| {
+                self.inner.state_mut().open_failed(&err);
+                err
+            })?;
 
-            // Replace the inner client with a new one
-            self.inner = AsyncDiscordIpcClient::new_with_ipc_config(
-                self.client_id.clone(),
-                connection,
-                self.ipc_config.clone(),
-            );
+            // Keep the rate limit window and state observers of the current client
+            self.inner.replace_connection(connection);
+            self.path = path;
 
             // Perform handshake
-            self.inner.connect().await
+            self.connect().await
         }
 
         /// Create a new smol-based Discord IPC client (uses auto-discovery)
@@ -481,6 +637,19 @@ pub mod client {
             Self::new_internal(client_id, None, Some(timeout_ms), IpcConfig::default()).await
         }
 
+        /// Wait up to `timeout` for Discord to start, then create a client on its socket
+        ///
+        /// On Linux the socket directories are watched with inotify, so the client is
+        /// created as soon as Discord's socket appears; elsewhere connecting is retried
+        /// every `retry_interval_ms`. Call `connect` afterwards for the handshake.
+        pub async fn wait_for_socket(
+            client_id: impl Into<String>,
+            timeout: Duration,
+        ) -> Result<Self> {
+            let timeout_ms = timeout.as_millis() as u64;
+            Self::new_internal(client_id, None, Some(timeout_ms), IpcConfig::default()).await
+        }
+
         /// Create a new smol-based Discord IPC client with timeout and protocol configuration.
         pub async fn new_with_timeout_and_ipc_config(
             client_id: impl Into<String>,
@@ -544,6 +713,24 @@ pub mod client {
         pub async fn recv_message(&mut self) -> Result<(crate::ipc::Opcode, Value)> {
             self.inner.recv_message().await
         }
+
+        /// Move the connected client onto a dispatcher task and return a shareable handle
+        ///
+        /// The handle can be cloned into many tasks that issue requests concurrently;
+        /// see [`AsyncClientHandle`](crate::async_io::AsyncClientHandle).
+        ///
+        /// Unix only: on Windows the pipe is a blocking file, and a read abandoned by
+        /// the dispatcher would hold it until Discord sends something, which it only
+        /// does after our write.
+        #[cfg(unix)]
+        pub fn into_handle(self) -> crate::async_io::AsyncClientHandle {
+            let (handle, dispatcher) = self.inner.into_dispatcher();
+            smol::spawn(dispatcher.run(|duration| async move {
+                smol::Timer::after(duration).await;
+            }))
+            .detach();
+            handle
+        }
     }
 
     /// Helper extension trait for smol-specific timeout operations
@@ -597,4 +784,109 @@ pub mod client {
     }
 }
 
+/// smol integration for the background presence manager
+pub mod manager {
+    use super::client::SmolDiscordIpcClient;
+    use crate::activity::Activity;
+    use crate::async_io::manager::runtime::ManagerRuntime;
+    use crate::async_io::manager::AsyncPresenceManager;
+    use crate::error::Result;
+    use crate::ipc::protocol::IpcConfig;
+    use crate::ipc::{PipeConfig, ReadyEvent};
+    use crate::retry::RetryConfig;
+    use std::future::Future;
+    use std::time::Duration;
+
+    /// Marker type running the presence manager on smol
+    #[derive(Debug, Clone, Copy)]
+    pub struct SmolRuntime;
+
+    /// Background presence manager running on smol
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use presenceforge::async_io::smol::SmolPresenceManager;
+    /// use presenceforge::sync::PresenceManagerConfig;
+    /// use presenceforge::ActivityBuilder;
+    ///
+    /// # smol::block_on(async {
+    /// let manager = SmolPresenceManager::spawn("client_id", PresenceManagerConfig::default());
+    /// manager
+    ///     .set_activity(ActivityBuilder::new().state("In the menu").build())
+    ///     .await?;
+    ///
+    /// manager.shutdown().await;
+    /// # Ok::<(), presenceforge::DiscordIpcError>(())
+    /// # });
+    /// ```
+    pub type SmolPresenceManager = AsyncPresenceManager<SmolRuntime>;
+
+    impl ManagerRuntime for SmolRuntime {
+        type Client = SmolDiscordIpcClient;
+
+        async fn connect(
+            client_id: String,
+            pipe_config: Option<PipeConfig>,
+            ipc_config: IpcConfig,
+        ) -> Result<(Self::Client, Option<ReadyEvent>)> {
+            let mut client = SmolDiscordIpcClient::new_with_config_and_ipc_config(
+                client_id,
+                pipe_config,
+                ipc_config,
+            )
+            .await?;
+            let ready = client.connect_with_ready().await?;
+            Ok((client, ready))
+        }
+
+        async fn set_activity<'a>(client: &'a mut Self::Client, activity: &'a Activity) -> Result {
+            client.set_activity(activity).await
+        }
+
+        async fn clear_activity(client: &mut Self::Client) -> Result {
+            client.clear_activity().await.map(|_| ())
+        }
+
+        async fn close(client: &mut Self::Client) {
+            client.close().await
+        }
+
+        fn pending_update_at(client: &Self::Client) -> Option<std::time::Instant> {
+            client.has_pending_update().then(|| {
+                client
+                    .next_update_at()
+                    .unwrap_or_else(std::time::Instant::now)
+            })
+        }
+
+        async fn flush_pending_update(client: &mut Self::Client) -> Result<bool> {
+            client.flush_pending_update().await
+        }
+
+        #[cfg(unix)]
+        fn check_connection(client: &mut Self::Client) -> Result {
+            client.check_connection()
+        }
+
+        async fn retry<T, F, Fut>(config: RetryConfig, operation: F) -> Result<T>
+        where
+            T: Send + 'static,
+            F: FnMut() -> Fut + Send + 'static,
+            Fut: Future<Output = Result<T>> + Send + 'static,
+        {
+            crate::retry::with_retry_async_smol(&config, operation).await
+        }
+
+        async fn sleep(duration: Duration) {
+            smol::Timer::after(duration).await;
+        }
+
+        fn spawn(task: impl Future<Output = ()> + Send + 'static) {
+            smol::spawn(task).detach();
+        }
+    }
+}
+
 pub use client::*;
+pub use manager::*;
@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Applying a [`PresenceStack`] with the async clients
+
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use futures::channel::{mpsc, oneshot};
+use futures::future::{self, Either};
+use futures::StreamExt;
+
+use crate::activity::stack::StackCommand;
+use crate::activity::{Activity, PresenceStack, PresenceUpdate};
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+
+/// Controls a stack applied from a background task
+///
+/// Dropping the handle stops the task and drops the client.
+#[derive(Debug)]
+pub struct AsyncStackHandle<R: ManagerRuntime> {
+    commands: mpsc::UnboundedSender<StackCommand>,
+    done: oneshot::Receiver<Result<R::Client>>,
+    _runtime: PhantomData<R>,
+}
+
+impl PresenceStack {
+    /// Send the visible activity to an async client if it changed since the last call
+    ///
+    /// `R` is the runtime marker, e.g. `presenceforge::async_io::tokio::TokioRuntime`.
+    /// Behaves like [`PresenceStack::apply`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the client error; the next call sends the update again.
+    pub async fn apply_async<R: ManagerRuntime>(&mut self, client: &mut R::Client) -> Result<bool> {
+        let Some(update) = self.take_update() else {
+            return Ok(false);
+        };
+
+        let result = match update {
+            PresenceUpdate::Set(activity) => R::set_activity(client, &activity).await,
+            PresenceUpdate::Clear => R::clear_activity(client).await,
+        };
+
+        if let Err(err) = result {
+            self.invalidate();
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    /// Apply the stack to a connected async client from a task on runtime `R`
+    ///
+    /// Behaves like [`PresenceStack::spawn`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # async fn example() -> presenceforge::Result {
+    /// use presenceforge::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    /// use presenceforge::{ActivityBuilder, PresenceStack};
+    /// use std::time::Duration;
+    ///
+    /// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+    /// client.connect().await?;
+    ///
+    /// let mut stack = PresenceStack::new();
+    /// stack.push("game", 0, ActivityBuilder::new().state("In a match").build());
+    /// let stack = stack.spawn_async::<TokioRuntime>(client);
+    ///
+    /// // Shown for eight seconds, then "In a match" again
+    /// stack.push_for(
+    ///     "achievement",
+    ///     10,
+    ///     ActivityBuilder::new().state("Achievement unlocked!").build(),
+    ///     Duration::from_secs(8),
+    /// );
+    /// let client = stack.stop().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_async<R: ManagerRuntime>(self, client: R::Client) -> AsyncStackHandle<R> {
+        let (commands, receiver) = mpsc::unbounded();
+        let (done_tx, done) = oneshot::channel();
+
+        R::spawn(async move {
+            let result = run::<R>(self, client, receiver).await;
+            let _ = done_tx.send(result);
+        });
+
+        AsyncStackHandle {
+            commands,
+            done,
+            _runtime: PhantomData,
+        }
+    }
+}
+
+impl<R: ManagerRuntime> AsyncStackHandle<R> {
+    /// Set a layer that stays until it is removed. See [`PresenceStack::push`].
+    pub fn push(&self, name: impl Into<String>, priority: i32, activity: Activity) {
+        self.send(StackCommand::Push {
+            name: name.into(),
+            priority,
+            activity: Box::new(activity),
+            ttl: None,
+        });
+    }
+
+    /// Set a layer that expires after `ttl`. See [`PresenceStack::push_for`].
+    pub fn push_for(
+        &self,
+        name: impl Into<String>,
+        priority: i32,
+        activity: Activity,
+        ttl: Duration,
+    ) {
+        self.send(StackCommand::Push {
+            name: name.into(),
+            priority,
+            activity: Box::new(activity),
+            ttl: Some(ttl),
+        });
+    }
+
+    /// Remove a layer
+    pub fn remove(&self, name: impl Into<String>) {
+        self.send(StackCommand::Remove(name.into()));
+    }
+
+    /// Stop the task and take back the client
+    ///
+    /// # Errors
+    ///
+    /// Returns the connection error that ended the task early.
+    pub async fn stop(self) -> Result<R::Client> {
+        self.send(StackCommand::Stop);
+        self.done
+            .await
+            .unwrap_or(Err(DiscordIpcError::ManagerShutdown))
+    }
+
+    fn send(&self, command: StackCommand) {
+        let _ = self.commands.unbounded_send(command);
+    }
+}
+
+async fn run<R: ManagerRuntime>(
+    mut stack: PresenceStack,
+    mut client: R::Client,
+    mut commands: mpsc::UnboundedReceiver<StackCommand>,
+) -> Result<R::Client> {
+    loop {
+        match stack.apply_async::<R>(&mut client).await {
+            Err(err) if err.is_connection_error() => return Err(err),
+            Err(err) => debug_println!("Stack update rejected: {}", err),
+            Ok(_) => {}
+        }
+
+        let command = match stack.next_expiry() {
+            Some(at) => {
+                let sleep = Box::pin(R::sleep(at.saturating_duration_since(Instant::now())));
+                match future::select(commands.next(), sleep).await {
+                    Either::Left((command, _)) => command,
+                    Either::Right(_) => continue,
+                }
+            }
+            None => commands.next().await,
+        };
+        match command {
+            Some(command) => {
+                if !stack.handle(command) {
+                    return Ok(client);
+                }
+            }
+            None => return Ok(client),
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    use crate::test_support::MockDiscord;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn task_restores_the_next_layer() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+
+        let mut stack = PresenceStack::new();
+        stack.push("game", 0, ActivityBuilder::new().state("game").build());
+        stack.push_for(
+            "banner",
+            1,
+            ActivityBuilder::new().state("banner").build(),
+            Duration::from_millis(50),
+        );
+        let stack = stack.spawn_async::<TokioRuntime>(client);
+
+        // The mock is handed back so it keeps serving until the stack stops
+        let (mock, frames) = tokio::task::spawn_blocking(move || {
+            let frames = [mock.next_frame().unwrap(), mock.next_frame().unwrap()];
+            (mock, frames)
+        })
+        .await
+        .unwrap();
+        assert_eq!(frames[0]["args"]["activity"]["state"], "banner");
+        assert_eq!(frames[1]["args"]["activity"]["state"], "game");
+        stack.stop().await.unwrap();
+        drop(mock);
+    }
+}
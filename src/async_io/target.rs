@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Finding the socket of a particular release channel or user for the runtime clients
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures::future::{self, Either};
+use serde_json::{json, Value};
+
+use crate::async_io::client::AsyncDiscordIpcClient;
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::async_io::traits::{AsyncRead, AsyncWrite};
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::connection::{SocketSearch, SocketTarget};
+use crate::ipc::discovery;
+use crate::ipc::protocol::IpcConfig;
+use crate::ipc::{Opcode, ReadyEvent};
+
+/// Handshake on `client`'s socket at `path`, then on each other candidate socket opened
+/// with `open`, until one matches `target` or `budget` is spent
+///
+/// `path` is updated to the socket the client ends up on.
+pub(crate) async fn connect_target<R, T, F, Fut>(
+    client: &mut AsyncDiscordIpcClient<T>,
+    path: &mut String,
+    target: SocketTarget,
+    budget: Option<Duration>,
+    ipc_config: &IpcConfig,
+    open: F,
+) -> Result<Value>
+where
+    R: ManagerRuntime,
+    T: AsyncRead + AsyncWrite + Unpin,
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut search = SocketSearch::new(target, budget);
+    let opened = path.clone();
+    let mut candidates = discovery::candidate_paths(ipc_config)
+        .into_iter()
+        .filter(|candidate| *candidate != opened);
+
+    loop {
+        search.opened();
+        let timeout = search.cap(ipc_config.handshake_timeout);
+        match handshake_within::<R, T>(client, timeout).await {
+            Ok((response, ready)) if search.accepts(path, &ready) => {
+                client.mark_connected(ready);
+                return Ok(response);
+            }
+            // Say goodbye rather than leave Discord with a dangling client
+            Ok(_) => {
+                let _ = client.send_message(Opcode::Close, &json!({})).await;
+            }
+            Err(err) => search.failed(path, err),
+        }
+        client.shutdown_connection().await;
+
+        let next = loop {
+            if search.expired() {
+                break None;
+            }
+            let Some(candidate) = candidates.next() else {
+                break None;
+            };
+            if let Ok(connection) = open(candidate.clone()).await {
+                break Some((connection, candidate));
+            }
+        };
+        let Some((connection, candidate)) = next else {
+            break;
+        };
+        client.replace_connection(connection);
+        *path = candidate;
+    }
+
+    let err = search.into_error();
+    client.state_mut().open_failed(&err);
+    Err(err)
+}
+
+/// Handshake, giving up after `timeout`
+async fn handshake_within<R, T>(
+    client: &mut AsyncDiscordIpcClient<T>,
+    timeout: Option<Duration>,
+) -> Result<(Value, ReadyEvent)>
+where
+    R: ManagerRuntime,
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let Some(timeout) = timeout else {
+        return client.handshake().await;
+    };
+    let handshake = Box::pin(client.handshake());
+    match future::select(handshake, Box::pin(R::sleep(timeout))).await {
+        Either::Left((answer, _)) => answer,
+        Either::Right(_) => Err(DiscordIpcError::request_timeout("handshake", None)),
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use crate::activity::ActivityBuilder;
+    use crate::async_io::tokio::TokioDiscordIpcClient;
+    use crate::ipc::protocol::IpcConfig;
+    use crate::ipc::PipeConfig;
+    use crate::test_support::MockDiscord;
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn user_search_starts_from_the_open_socket_and_closes_rejected_ones() {
+        let dir =
+            std::env::temp_dir().join(format!("presenceforge-search-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        let other = MockDiscord::start_at(dir.join("discord-ipc-0"), "1");
+        let wanted = MockDiscord::start_at(dir.join("discord-ipc-1"), "2");
+        let mut client = TokioDiscordIpcClient::new_with_config_and_ipc_config(
+            "client",
+            Some(PipeConfig::UserId("2".to_string())),
+            IpcConfig::default().with_search_dir(dir.to_string_lossy()),
+        )
+        .await
+        .unwrap();
+        client.connect().await.unwrap();
+
+        let activity = ActivityBuilder::new().state("found").build();
+        client.set_activity(&activity).await.unwrap();
+        let (other, wanted) = tokio::task::spawn_blocking(move || {
+            // The socket opened by the constructor is handshaked once and told goodbye
+            assert_eq!(other.next_frame(), Some(json!({ "close": {} })));
+            assert_eq!(
+                other.next_frame_within(std::time::Duration::from_millis(200)),
+                None
+            );
+            let frame = wanted.next_frame().unwrap();
+            assert_eq!(frame["args"]["activity"]["state"], "found");
+            (other, wanted)
+        })
+        .await
+        .unwrap();
+
+        drop((other, wanted));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
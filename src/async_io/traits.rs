@@ -81,6 +81,13 @@ pub trait AsyncWrite {
     ///
     /// A future that resolves when the flush is complete
     fn flush<'a>(&'a mut self) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'a>>;
+
+    /// Shut the connection down so that later reads and writes fail
+    ///
+    /// The default does nothing; the connection then closes when it is dropped.
+    fn shutdown<'a>(&'a mut self) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'a>> {
+        Box::pin(async { Ok(()) })
+    }
 }
 
 /// Default implementation of write_all using AsyncWrite
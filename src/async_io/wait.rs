@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Waiting for Discord to start without blocking the executor
+
+use std::future::Future;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use futures::channel::mpsc;
+use futures::future::{self, Either};
+use futures::StreamExt;
+
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::connection::not_listening_yet;
+use crate::ipc::protocol::IpcConfig;
+use crate::ipc::watch::SocketWatch;
+use crate::ipc::PipeConfig;
+
+/// How often the watcher thread checks whether the waiting future is gone
+const CANCEL_CHECK: Duration = Duration::from_millis(250);
+
+/// Run `watch` on a background thread, sending `()` whenever connecting is worth a try
+///
+/// The thread exits shortly after the receiver is dropped.
+fn spawn_watch(mut watch: SocketWatch) -> Option<mpsc::UnboundedReceiver<()>> {
+    let (tx, rx) = mpsc::unbounded();
+    let spawned = thread::Builder::new()
+        .name("presenceforge-socket-watch".to_string())
+        .spawn(move || {
+            while !tx.is_closed() {
+                if watch.wait(CANCEL_CHECK) && tx.unbounded_send(()).is_err() {
+                    break;
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => Some(rx),
+        Err(err) => {
+            debug_println!("Cannot spawn socket watcher ({}), polling", err);
+            None
+        }
+    }
+}
+
+/// Connect with `connect` as soon as a socket for `config` appears, for up to `timeout`
+pub(crate) async fn wait_for_socket<R, T, F, Fut>(
+    config: Option<PipeConfig>,
+    timeout: Duration,
+    ipc_config: IpcConfig,
+    connect: F,
+) -> Result<T>
+where
+    R: ManagerRuntime,
+    F: Fn(Option<PipeConfig>, IpcConfig) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    debug_println!("Waiting up to {:?} for a Discord socket", timeout);
+    // A timeout too large to represent waits forever
+    let deadline = Instant::now().checked_add(timeout);
+    let config = config.unwrap_or_default();
+    let retry = Duration::from_millis(ipc_config.retry_interval_ms);
+    let mut changes = spawn_watch(SocketWatch::new(&config, &ipc_config));
+
+    loop {
+        let last_error_message = match connect(Some(config.clone()), ipc_config.clone()).await {
+            Ok(connection) => return Ok(connection),
+            Err(err) => match not_listening_yet(&err) {
+                Some(message) => message,
+                None => return Err(err),
+            },
+        };
+
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => Duration::MAX,
+        };
+        if remaining.is_zero() {
+            return Err(DiscordIpcError::connection_timeout(
+                timeout.as_millis() as u64,
+                Some(last_error_message),
+            ));
+        }
+
+        match changes.as_mut() {
+            Some(rx) if deadline.is_none() => {
+                if rx.next().await.is_none() {
+                    // The watcher thread is gone; fall back to polling
+                    changes = None;
+                }
+            }
+            Some(rx) => {
+                let sleep = Box::pin(R::sleep(remaining));
+                if let Either::Left((None, _)) = future::select(rx.next(), sleep).await {
+                    changes = None;
+                }
+            }
+            None => R::sleep(remaining.min(retry)).await,
+        }
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use crate::async_io::tokio::TokioConnection;
+    use crate::error::DiscordIpcError;
+    use crate::ipc::protocol::IpcConfig;
+    use crate::ipc::PipeConfig;
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn connects_once_the_socket_appears() {
+        let path =
+            std::env::temp_dir().join(format!("presenceforge-wait-{}", uuid::Uuid::new_v4()));
+        let config = PipeConfig::CustomPath(path.to_string_lossy().into_owned());
+
+        let listener = std::thread::spawn({
+            let path = path.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(100));
+                let listener = std::os::unix::net::UnixListener::bind(path).unwrap();
+                listener.accept().unwrap()
+            }
+        });
+        let connection = TokioConnection::wait_for_socket(
+            Some(config.clone()),
+            // Too far out for an `Instant`, so there is no deadline
+            Duration::MAX,
+            IpcConfig::default(),
+        )
+        .await;
+        assert!(connection.is_ok());
+        drop(listener.join().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            TokioConnection::wait_for_socket(
+                Some(config),
+                Duration::from_millis(50),
+                IpcConfig::default(),
+            )
+            .await,
+            Err(DiscordIpcError::ConnectionTimeout { timeout_ms: 50, .. })
+        ));
+    }
+}
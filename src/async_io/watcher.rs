@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Clearing a child process's activity from an async task once it exits
+
+use std::fmt;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use futures::channel::oneshot;
+
+use crate::async_io::dispatcher::AsyncClientHandle;
+use crate::async_io::manager::runtime::ManagerRuntime;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::process;
+
+/// Clears a child process's activity once it exits
+///
+/// The async counterpart of [`ChildWatcher`](crate::sync::ChildWatcher). Dropping the
+/// watcher stops it without clearing anything.
+pub struct AsyncChildWatcher {
+    pid: u32,
+    // Dropping it ends the waiting thread
+    _stop: Sender<()>,
+    done: oneshot::Receiver<Result>,
+}
+
+impl fmt::Debug for AsyncChildWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncChildWatcher")
+            .field("pid", &self.pid)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncChildWatcher {
+    /// The watched process
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Wait until the process exits and its activity has been cleared
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `clear_activity_for_pid` if clearing failed, or
+    /// `DiscordIpcError::ManagerShutdown` if the runtime dropped the task.
+    pub async fn wait(mut self) -> Result {
+        (&mut self.done)
+            .await
+            .unwrap_or(Err(DiscordIpcError::ManagerShutdown))
+    }
+}
+
+impl AsyncClientHandle {
+    /// Clear the activity of process `pid` from a task on runtime `R` once it exits
+    ///
+    /// Meant for launchers that set presence on behalf of a game with
+    /// `set_activity_for_pid`. The process is waited on from a background thread, like
+    /// [`ChildWatcher`](crate::sync::ChildWatcher) does, so the executor never blocks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::SocketClosed` if the dispatcher has already stopped, or
+    /// `DiscordIpcError::ConnectionFailed` if the thread cannot be spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "tokio-runtime")]
+    /// # async fn example() -> presenceforge::Result {
+    /// use presenceforge::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    /// use presenceforge::ActivityBuilder;
+    ///
+    /// let game = std::process::Command::new("./game").spawn()?;
+    /// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+    /// client.connect().await?;
+    /// let handle = client.into_handle();
+    ///
+    /// let activity = ActivityBuilder::new().state("In game").build();
+    /// handle.set_activity_for_pid(game.id(), &activity).await?;
+    /// let watcher = handle.watch_child::<TokioRuntime>(game.id())?;
+    /// // Returns once the game has exited and its activity was cleared
+    /// watcher.wait().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_child<R: ManagerRuntime>(&self, pid: u32) -> Result<AsyncChildWatcher> {
+        if self.is_closed() {
+            return Err(DiscordIpcError::SocketClosed);
+        }
+
+        let (stop, stop_rx) = mpsc::channel();
+        let (exited_tx, exited) = oneshot::channel();
+        thread::Builder::new()
+            .name("presenceforge-child".to_string())
+            .spawn(move || {
+                let _ = exited_tx.send(process::wait_for_exit(pid, &stop_rx));
+            })?;
+
+        let (done_tx, done) = oneshot::channel();
+        let handle = self.clone();
+        R::spawn(async move {
+            // The exit may still be reported after the watcher was dropped
+            if exited.await != Ok(true) || done_tx.is_canceled() {
+                return;
+            }
+            debug_println!("Process {} exited, clearing its activity", pid);
+            let result = handle.clear_activity_for_pid(pid).await.map(|_| ());
+            if let Err(err) = &result {
+                debug_println!("Failed to clear activity of process {}: {}", pid, err);
+            }
+            let _ = done_tx.send(result);
+        });
+
+        Ok(AsyncChildWatcher {
+            pid,
+            _stop: stop,
+            done,
+        })
+    }
+}
+
+#[cfg(all(test, unix, feature = "tokio-runtime"))]
+mod tests {
+    use crate::activity::ActivityBuilder;
+    use crate::async_io::tokio::{TokioDiscordIpcClient, TokioRuntime};
+    use crate::ipc::process;
+    use crate::test_support::MockDiscord;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn clears_the_activity_when_the_child_exits() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("0.2")
+            .spawn()
+            .unwrap();
+        handle
+            .set_activity_for_pid(child.id(), &ActivityBuilder::new().state("Child").build())
+            .await
+            .unwrap();
+        let sent = process::resolve_pid(child.id()).sent;
+        assert_eq!(mock.next_frame().unwrap()["args"]["pid"], sent);
+
+        let pid = child.id();
+        let watcher = handle.watch_child::<TokioRuntime>(pid).unwrap();
+        tokio::task::spawn_blocking(move || child.wait().unwrap())
+            .await
+            .unwrap();
+        watcher.wait().await.unwrap();
+
+        let clear = tokio::task::spawn_blocking(move || mock.next_frame())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(clear["args"]["pid"], sent);
+        assert!(clear["args"]["activity"].is_null());
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dropping_the_watcher_leaves_the_activity() {
+        let mock = MockDiscord::start();
+        let mut client = TokioDiscordIpcClient::new_with_config("client", Some(mock.pipe_config()))
+            .await
+            .unwrap();
+        client.connect().await.unwrap();
+        let handle = client.into_handle();
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("0.2")
+            .spawn()
+            .unwrap();
+        handle
+            .set_activity_for_pid(child.id(), &ActivityBuilder::new().state("Child").build())
+            .await
+            .unwrap();
+
+        drop(handle.watch_child::<TokioRuntime>(child.id()).unwrap());
+        let cleared = tokio::task::spawn_blocking(move || {
+            mock.next_frame().unwrap();
+            child.wait().unwrap();
+            mock.next_frame_within(std::time::Duration::from_millis(500))
+        })
+        .await
+        .unwrap();
+        assert_eq!(cleared, None);
+    }
+}
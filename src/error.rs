@@ -81,6 +81,7 @@ pub enum InvalidResponseKind {
     NonceMismatch,
     PayloadTooLarge,
     MissingEventData,
+    MissingResponseData,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -154,6 +155,7 @@ impl Display for ErrorCategory {
 ///
 /// See the `examples/connection_retry.rs` file for more comprehensive examples.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum DiscordIpcError {
     /// Failed to connect to Discord IPC socket or pipe
     #[error("Failed to connect to Discord IPC socket: {0}")]
@@ -174,10 +176,37 @@ pub enum DiscordIpcError {
         last_error: Option<String>,
     },
 
+    /// Discord did not answer a request in time; the connection must be re-established
+    #[error("Timed out waiting for Discord to answer {operation}")]
+    RequestTimeout {
+        /// The operation that timed out, e.g. `handshake` or `SET_ACTIVITY`
+        operation: &'static str,
+        /// Nonce of the unanswered request, if it had one
+        nonce: Option<String>,
+    },
+
     /// Failed to find a valid Discord IPC socket or pipe
     #[error("No Discord IPC socket found. Is Discord running?")]
     NoValidSocket,
 
+    /// No running Discord serves the requested release channel
+    #[error("No Discord {wanted} socket found{}", found_list(found))]
+    ReleaseChannelNotFound {
+        /// The channel asked for with `PipeConfig::ReleaseChannel`
+        wanted: crate::ipc::ReleaseChannel,
+        /// Channels of the sockets that answered instead
+        found: Vec<crate::ipc::ReleaseChannel>,
+    },
+
+    /// No running Discord is logged in as the requested user
+    #[error("No Discord socket logged in as user {user_id}{}", found_list(found))]
+    UserNotFound {
+        /// The user ID asked for with `PipeConfig::UserId`
+        user_id: String,
+        /// User IDs of the sockets that answered instead
+        found: Vec<String>,
+    },
+
     /// Failed to serialize JSON payload
     #[error("Failed to serialize JSON payload: {0}")]
     SerializationFailed(#[source] serde_json::Error),
@@ -227,9 +256,48 @@ pub enum DiscordIpcError {
     #[error("Invalid activity: {0}")]
     InvalidActivity(#[from] crate::activity::ActivityValidationError),
 
+    /// Failed to parse or write an activity configuration file
+    #[error("Invalid {format} activity: {message}")]
+    ActivityFormat {
+        /// The file format, e.g. `TOML` or `YAML`
+        format: &'static str,
+        /// The parser or serializer message
+        message: String,
+    },
+
     /// System time error (e.g., time before UNIX epoch)
     #[error("System time error: {0}")]
     SystemTimeError(#[source] StdSystemTimeError),
+
+    /// The background presence manager is no longer running
+    #[error("Presence manager has shut down")]
+    ManagerShutdown,
+
+    /// An event subscriber fell behind and its oldest events were dropped
+    #[error("Event subscriber lagged behind; {skipped} events were dropped")]
+    EventsLagged {
+        /// Number of events dropped since the last successful receive
+        skipped: u64,
+    },
+
+    /// A `PresenceHub` has no connection for this application
+    #[error("No connection for application {client_id}")]
+    UnknownApplication {
+        /// The application (client) ID that was asked for
+        client_id: String,
+    },
+
+    /// A `PresenceHub` was asked to update the active application before one was activated
+    #[error("No application has been activated")]
+    NoActiveApplication,
+}
+
+fn found_list<T: Display>(found: &[T]) -> String {
+    if found.is_empty() {
+        return String::new();
+    }
+    let found: Vec<String> = found.iter().map(ToString::to_string).collect();
+    format!(" (found {})", found.join(", "))
 }
 
 impl DiscordIpcError {
@@ -238,12 +306,15 @@ impl DiscordIpcError {
             Self::ConnectionFailed(_)
             | Self::SocketDiscoveryFailed { .. }
             | Self::ConnectionTimeout { .. }
+            | Self::RequestTimeout { .. }
             | Self::NoValidSocket
+            | Self::ReleaseChannelNotFound { .. }
+            | Self::UserNotFound { .. }
             | Self::SocketClosed => ErrorCategory::Connection,
 
-            Self::SerializationFailed(_) | Self::DeserializationFailed(_) => {
-                ErrorCategory::Serialization
-            }
+            Self::SerializationFailed(_)
+            | Self::DeserializationFailed(_)
+            | Self::ActivityFormat { .. } => ErrorCategory::Serialization,
 
             Self::InvalidResponse { .. }
             | Self::HandshakeFailed { .. }
@@ -252,7 +323,12 @@ impl DiscordIpcError {
 
             Self::DiscordError { .. } => ErrorCategory::Application,
 
-            Self::InvalidActivity(_) | Self::SystemTimeError(_) => ErrorCategory::Other,
+            Self::InvalidActivity(_)
+            | Self::SystemTimeError(_)
+            | Self::ManagerShutdown
+            | Self::EventsLagged { .. }
+            | Self::UnknownApplication { .. }
+            | Self::NoActiveApplication => ErrorCategory::Other,
         }
     }
 
@@ -264,9 +340,11 @@ impl DiscordIpcError {
         matches!(
             self,
             Self::ConnectionTimeout { .. }
+                | Self::RequestTimeout { .. }
                 | Self::SocketClosed
                 | Self::InvalidResponse { .. }
                 | Self::SocketDiscoveryFailed { .. }
+                | Self::EventsLagged { .. }
         )
     }
 
@@ -291,6 +369,14 @@ impl DiscordIpcError {
         }
     }
 
+    /// Create an ActivityFormat error for the given file format
+    pub fn activity_format(format: &'static str, message: impl Into<String>) -> Self {
+        Self::ActivityFormat {
+            format,
+            message: message.into(),
+        }
+    }
+
     /// Create a SocketDiscoveryFailed error with the attempted paths
     pub fn socket_discovery_failed(source: io::Error, attempted_paths: Vec<String>) -> Self {
         Self::SocketDiscoveryFailed {
@@ -307,6 +393,11 @@ impl DiscordIpcError {
         }
     }
 
+    /// Create a RequestTimeout error for an operation and its nonce
+    pub fn request_timeout(operation: &'static str, nonce: Option<String>) -> Self {
+        Self::RequestTimeout { operation, nonce }
+    }
+
     /// Create a ProtocolViolation error with message and context
     pub fn protocol_violation(
         kind: ProtocolViolationKind,
@@ -0,0 +1,349 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Fan-out of events to several independent consumers
+//!
+//! Each [`EventSubscription`] has its own bounded queue. When a slow consumer's queue
+//! is full the oldest event is dropped, and the next receive reports how many were
+//! lost with `DiscordIpcError::EventsLagged` before continuing with the rest.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::poll_fn;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Weak};
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::protocol::EventData;
+
+/// Queue size used when a subscription is created with a capacity of `0`
+pub const DEFAULT_SUBSCRIPTION_CAPACITY: usize = 64;
+
+/// Publishing side shared by a reader and everything that hands out subscriptions
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventBroadcaster {
+    inner: Arc<Mutex<BroadcastState>>,
+}
+
+#[derive(Debug, Default)]
+struct BroadcastState {
+    subscribers: Vec<Weak<Shared>>,
+    closed: bool,
+}
+
+impl EventBroadcaster {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new subscriber; it only sees events published from now on
+    pub(crate) fn subscribe(&self, capacity: usize) -> EventSubscription {
+        let capacity = if capacity == 0 {
+            DEFAULT_SUBSCRIPTION_CAPACITY
+        } else {
+            capacity
+        };
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                events: VecDeque::with_capacity(capacity.min(DEFAULT_SUBSCRIPTION_CAPACITY)),
+                capacity,
+                skipped: 0,
+                total_skipped: 0,
+                closed: false,
+                waker: None,
+            }),
+            ready: Condvar::new(),
+        });
+
+        let mut state = lock(&self.inner);
+        if state.closed {
+            lock(&shared.queue).closed = true;
+        } else {
+            state.subscribers.push(Arc::downgrade(&shared));
+        }
+        EventSubscription { shared }
+    }
+
+    /// Hand a copy of `event` to every live subscriber
+    pub(crate) fn publish(&self, event: &EventData) {
+        let mut state = lock(&self.inner);
+        state
+            .subscribers
+            .retain(|subscriber| match subscriber.upgrade() {
+                Some(shared) => {
+                    shared.push(event.clone());
+                    true
+                }
+                None => false,
+            });
+    }
+
+    /// End every subscription once its queued events have been received
+    pub(crate) fn close(&self) {
+        let mut state = lock(&self.inner);
+        state.closed = true;
+        for shared in state
+            .subscribers
+            .drain(..)
+            .filter_map(|weak| weak.upgrade())
+        {
+            shared.close();
+        }
+    }
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    ready: Condvar,
+}
+
+struct Queue {
+    events: VecDeque<EventData>,
+    capacity: usize,
+    skipped: u64,
+    total_skipped: u64,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl Shared {
+    fn push(&self, event: EventData) {
+        let mut queue = lock(&self.queue);
+        if queue.events.len() >= queue.capacity {
+            queue.events.pop_front();
+            queue.skipped += 1;
+            queue.total_skipped += 1;
+        }
+        queue.events.push_back(event);
+        Self::wake(queue);
+        self.ready.notify_all();
+    }
+
+    fn close(&self) {
+        let mut queue = lock(&self.queue);
+        queue.closed = true;
+        Self::wake(queue);
+        self.ready.notify_all();
+    }
+
+    fn wake(mut queue: MutexGuard<'_, Queue>) {
+        if let Some(waker) = queue.waker.take() {
+            drop(queue);
+            waker.wake();
+        }
+    }
+}
+
+impl Queue {
+    /// `None` means nothing is queued yet and the source is still open
+    fn take(&mut self) -> Option<Result<EventData>> {
+        if self.skipped > 0 {
+            let skipped = std::mem::take(&mut self.skipped);
+            return Some(Err(DiscordIpcError::EventsLagged { skipped }));
+        }
+        match self.events.pop_front() {
+            Some(event) => Some(Ok(event)),
+            None if self.closed => Some(Err(DiscordIpcError::SocketClosed)),
+            None => None,
+        }
+    }
+}
+
+/// One consumer's view of a broadcast event feed
+///
+/// Obtained from `AsyncClientHandle::event_subscription` or
+/// `EventReader::event_subscription`. Receives fail with
+/// `DiscordIpcError::EventsLagged` once after events were dropped because this
+/// subscriber fell behind, and with `DiscordIpcError::SocketClosed` after the
+/// source stopped and the queue is empty.
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::{DiscordIpcClient, DiscordIpcError};
+///
+/// let mut client = DiscordIpcClient::new("client_id")?;
+/// client.connect()?;
+/// client.subscribe("ACTIVITY_JOIN", serde_json::json!({}))?;
+/// let reader = client.spawn_event_reader()?;
+///
+/// let mut ui = reader.event_subscription(32);
+/// std::thread::spawn(move || loop {
+///     match ui.recv() {
+///         Ok(event) => println!("{event:?}"),
+///         Err(DiscordIpcError::EventsLagged { skipped }) => eprintln!("missed {skipped}"),
+///         Err(_) => break,
+///     }
+/// });
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+pub struct EventSubscription {
+    shared: Arc<Shared>,
+}
+
+impl fmt::Debug for EventSubscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let queue = lock(&self.shared.queue);
+        f.debug_struct("EventSubscription")
+            .field("queued", &queue.events.len())
+            .field("capacity", &queue.capacity)
+            .field("lagged", &queue.total_skipped)
+            .field("closed", &queue.closed)
+            .finish()
+    }
+}
+
+impl EventSubscription {
+    /// Block until the next event arrives
+    ///
+    /// # Errors
+    ///
+    /// Returns `EventsLagged` after events were dropped and `SocketClosed` once the
+    /// source has stopped.
+    pub fn recv(&mut self) -> Result<EventData> {
+        let mut queue = lock(&self.shared.queue);
+        loop {
+            if let Some(result) = queue.take() {
+                return result;
+            }
+            queue = self
+                .shared
+                .ready
+                .wait(queue)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Block for at most `timeout`; `Ok(None)` if nothing arrived in time
+    ///
+    /// # Errors
+    ///
+    /// Same as [`recv`](Self::recv).
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<EventData>> {
+        // A timeout too large to represent never runs out
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return self.recv().map(Some);
+        };
+        let mut queue = lock(&self.shared.queue);
+        loop {
+            if let Some(result) = queue.take() {
+                return result.map(Some);
+            }
+            let wait = deadline.saturating_duration_since(Instant::now());
+            if wait.is_zero() {
+                return Ok(None);
+            }
+            queue = self
+                .shared
+                .ready
+                .wait_timeout(queue, wait)
+                .map(|(queue, _)| queue)
+                .unwrap_or_else(|poisoned| poisoned.into_inner().0);
+        }
+    }
+
+    /// Return the next queued event without blocking
+    ///
+    /// # Errors
+    ///
+    /// Same as [`recv`](Self::recv).
+    pub fn try_recv(&mut self) -> Result<Option<EventData>> {
+        lock(&self.shared.queue).take().transpose()
+    }
+
+    /// Wait for the next event without blocking the executor
+    ///
+    /// # Errors
+    ///
+    /// Same as [`recv`](Self::recv).
+    pub async fn recv_async(&mut self) -> Result<EventData> {
+        poll_fn(|cx| {
+            let mut queue = lock(&self.shared.queue);
+            match queue.take() {
+                Some(result) => Poll::Ready(result),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Number of events waiting to be received
+    pub fn len(&self) -> usize {
+        lock(&self.shared.queue).events.len()
+    }
+
+    /// Returns `true` if no events are waiting
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total number of events dropped for this subscriber so far
+    pub fn lagged(&self) -> u64 {
+        lock(&self.shared.queue).total_skipped
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::protocol::ActivityJoinEvent;
+
+    fn join(secret: &str) -> EventData {
+        EventData::ActivityJoin(ActivityJoinEvent {
+            secret: secret.to_string(),
+        })
+    }
+
+    fn secret(event: EventData) -> String {
+        match event {
+            EventData::ActivityJoin(join) => join.secret,
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn slow_subscriber_reports_lag_without_affecting_others() {
+        let broadcaster = EventBroadcaster::new();
+        let mut slow = broadcaster.subscribe(2);
+        let mut fast = broadcaster.subscribe(8);
+
+        for i in 0..5 {
+            broadcaster.publish(&join(&i.to_string()));
+        }
+        broadcaster.close();
+
+        assert!(matches!(
+            slow.recv(),
+            Err(DiscordIpcError::EventsLagged { skipped: 3 })
+        ));
+        assert_eq!(secret(slow.recv().unwrap()), "3");
+        assert_eq!(secret(slow.recv().unwrap()), "4");
+        assert!(matches!(slow.recv(), Err(DiscordIpcError::SocketClosed)));
+        assert_eq!(slow.lagged(), 3);
+
+        let received: Vec<String> = (0..5).map(|_| secret(fast.recv().unwrap())).collect();
+        assert_eq!(received, ["0", "1", "2", "3", "4"]);
+        assert_eq!(fast.lagged(), 0);
+    }
+
+    #[test]
+    fn subscribing_after_close_ends_immediately() {
+        let broadcaster = EventBroadcaster::new();
+        broadcaster.close();
+        let mut late = broadcaster.subscribe(0);
+        assert!(matches!(
+            late.try_recv(),
+            Err(DiscordIpcError::SocketClosed)
+        ));
+    }
+}
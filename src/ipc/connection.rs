@@ -4,7 +4,9 @@
 use byteorder::{LittleEndian, ReadBytesExt};
 use bytes::{BufMut, BytesMut};
 use serde_json::Value;
-use std::io::Read;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
 
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
@@ -12,13 +14,18 @@ use std::os::unix::net::UnixStream;
 #[cfg(windows)]
 use std::fs::OpenOptions;
 #[cfg(windows)]
-use std::io::{BufReader, BufWriter};
+use std::io::{BufRead, BufReader, BufWriter};
+#[cfg(windows)]
+use std::os::windows::io::AsRawHandle;
 
+use crate::debug_println;
 use crate::error::{DiscordIpcError, ProtocolContext, ProtocolViolationKind, Result};
-use crate::ipc::protocol::{constants, IpcConfig, Opcode};
+use crate::ipc::protocol::{constants, IpcConfig, Opcode, ReadyConfig, ReadyEvent};
+use crate::ipc::watch::SocketWatch;
 
 /// Configuration for selecting which Discord IPC pipe to connect to
 #[derive(Debug, Clone, Default)]
+#[non_exhaustive]
 pub enum PipeConfig {
     /// Automatically discover and connect to the first available pipe (default behavior)
     #[default]
@@ -31,6 +38,196 @@ pub enum PipeConfig {
     ///
     /// Windows: `\\.\pipe\discord-ipc-0`
     CustomPath(String),
+    /// Connect to the first socket served by this release channel of Discord
+    ///
+    /// Every socket is handshaked in turn and identified from the READY payload's
+    /// `config`, so this only takes effect when a client connects; the connection
+    /// layer alone treats it like [`PipeConfig::Auto`].
+    ReleaseChannel(ReleaseChannel),
+    /// Connect to the first socket whose Discord is logged in as this user ID
+    ///
+    /// For people running several accounts side by side. Like
+    /// [`PipeConfig::ReleaseChannel`], sockets are handshaked in turn and matched on the
+    /// READY payload's user; [`IpcConnection::probe_pipes`] lists who is on each socket.
+    UserId(String),
+}
+
+impl PipeConfig {
+    /// What a client has to handshake its way to, if this config picks a socket by READY
+    pub(crate) fn target(&self) -> Option<SocketTarget> {
+        match self {
+            Self::ReleaseChannel(channel) => Some(SocketTarget::Channel(*channel)),
+            Self::UserId(user_id) => Some(SocketTarget::User(user_id.clone())),
+            Self::Auto | Self::CustomPath(_) => None,
+        }
+    }
+}
+
+/// A Discord release channel, for [`PipeConfig::ReleaseChannel`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReleaseChannel {
+    /// The regular Discord client
+    Stable,
+    /// Public Test Build
+    Ptb,
+    /// Canary
+    Canary,
+    /// Any channel; the first socket that completes a handshake is used
+    Any,
+}
+
+impl ReleaseChannel {
+    /// Identify the channel from a READY payload's `config`
+    ///
+    /// Returns `None` if neither the API endpoint nor the CDN host names a known build.
+    pub fn from_config(config: &ReadyConfig) -> Option<Self> {
+        let hosts = [config.api_endpoint.as_deref(), config.cdn_host.as_deref()];
+        for host in hosts.into_iter().flatten() {
+            let host = host.to_ascii_lowercase();
+            if host.contains("canary.discord") {
+                return Some(Self::Canary);
+            }
+            if host.contains("ptb.discord") {
+                return Some(Self::Ptb);
+            }
+        }
+        match config.api_endpoint.as_deref() {
+            Some(endpoint)
+                if endpoint.contains("discord.com") || endpoint.contains("discordapp.com") =>
+            {
+                Some(Self::Stable)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if a socket identified as `found` satisfies this channel
+    pub fn accepts(self, found: Option<Self>) -> bool {
+        self == Self::Any || found == Some(self)
+    }
+}
+
+/// The socket a [`SocketSearch`] is looking for
+#[derive(Debug, Clone)]
+pub(crate) enum SocketTarget {
+    Channel(ReleaseChannel),
+    User(String),
+}
+
+/// Bookkeeping while handshaking socket after socket for a [`SocketTarget`]
+pub(crate) struct SocketSearch {
+    target: SocketTarget,
+    opened: bool,
+    channels: Vec<ReleaseChannel>,
+    users: Vec<String>,
+    last_error: Option<DiscordIpcError>,
+    budget: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+impl SocketSearch {
+    /// Start a search that gives up once `budget` has passed
+    pub(crate) fn new(target: SocketTarget, budget: Option<Duration>) -> Self {
+        Self {
+            target,
+            opened: false,
+            channels: Vec::new(),
+            users: Vec::new(),
+            last_error: None,
+            budget,
+            deadline: budget.and_then(|budget| Instant::now().checked_add(budget)),
+        }
+    }
+
+    /// Returns `true` once the budget is spent
+    pub(crate) fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Cap `timeout` for the next handshake at what is left of the budget
+    pub(crate) fn cap(&self, timeout: Option<Duration>) -> Option<Duration> {
+        let Some(deadline) = self.deadline else {
+            return timeout;
+        };
+        // A zero read timeout is rejected, so always leave a millisecond
+        let remaining = deadline
+            .saturating_duration_since(Instant::now())
+            .max(Duration::from_millis(1));
+        Some(timeout.map_or(remaining, |timeout| timeout.min(remaining)))
+    }
+
+    /// A socket at `path` was opened and is about to be handshaked
+    pub(crate) fn opened(&mut self) {
+        self.opened = true;
+    }
+
+    /// Returns `true` if the socket at `path` that answered with `ready` is the one
+    pub(crate) fn accepts(&mut self, path: &str, ready: &ReadyEvent) -> bool {
+        match &self.target {
+            SocketTarget::Channel(wanted) => {
+                let answered = ready.release_channel();
+                if wanted.accepts(answered) {
+                    return true;
+                }
+                debug_println!("{} is {:?}, not {}", path, answered, wanted);
+                self.channels.extend(answered);
+            }
+            SocketTarget::User(wanted) => {
+                let answered = ready.user.as_ref().and_then(|user| user.id.as_deref());
+                if answered == Some(wanted.as_str()) {
+                    return true;
+                }
+                debug_println!("{} is user {:?}, not {}", path, answered, wanted);
+                self.users.extend(answered.map(str::to_string));
+            }
+        }
+        false
+    }
+
+    /// The handshake on the socket at `path` failed
+    pub(crate) fn failed(&mut self, path: &str, err: DiscordIpcError) {
+        debug_println!("Handshake on {} failed: {}", path, err);
+        self.last_error = Some(err);
+    }
+
+    /// Why no socket was accepted
+    pub(crate) fn into_error(mut self) -> DiscordIpcError {
+        if let (true, Some(budget)) = (self.expired(), self.budget.take()) {
+            let timeout_ms = u64::try_from(budget.as_millis()).unwrap_or(u64::MAX);
+            return DiscordIpcError::connection_timeout(
+                timeout_ms,
+                Some(self.into_error().to_string()),
+            );
+        }
+        let identified = !self.channels.is_empty() || !self.users.is_empty();
+        match self.last_error {
+            _ if !self.opened => DiscordIpcError::NoValidSocket,
+            Some(err) if !identified => err,
+            _ => match self.target {
+                SocketTarget::Channel(wanted) => DiscordIpcError::ReleaseChannelNotFound {
+                    wanted,
+                    found: self.channels,
+                },
+                SocketTarget::User(user_id) => DiscordIpcError::UserNotFound {
+                    user_id,
+                    found: self.users,
+                },
+            },
+        }
+    }
+}
+
+impl fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Stable => "Stable",
+            Self::Ptb => "PTB",
+            Self::Canary => "Canary",
+            Self::Any => "any channel",
+        })
+    }
 }
 
 /// Information about a discovered Discord IPC pipe
@@ -45,24 +242,34 @@ pub struct DiscoveredPipe {
 #[cfg(unix)]
 pub struct IpcConnection {
     stream: UnixStream,
+    path: String,
     read_buf: BytesMut,
     write_buf: BytesMut,
     ipc_config: IpcConfig,
+    usable: bool,
 }
 
 #[cfg(windows)]
 pub struct IpcConnection {
     reader: BufReader<std::fs::File>,
     writer: BufWriter<std::fs::File>,
+    path: String,
     read_buf: BytesMut,
     write_buf: BytesMut,
     ipc_config: IpcConfig,
+    usable: bool,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    canceller: cancel::Canceller,
 }
 
 impl IpcConnection {
     /// Initial capacity for read and write buffers (4KB)
     const INITIAL_BUFFER_CAPACITY: usize = 4096;
 
+    /// Bytes read from the socket per call
+    const READ_CHUNK_SIZE: usize = 4096;
+
     /// Discover all available Discord IPC pipes
     ///
     /// Returns a list of all Discord IPC pipes that are currently accessible
@@ -93,19 +300,14 @@ impl IpcConnection {
     fn discover_pipes_unix() -> Vec<DiscoveredPipe> {
         let mut pipes = Vec::new();
 
-        // Try each directory with each socket number
-        for dir in crate::ipc::discovery::candidate_ipc_directories() {
-            for i in 0..constants::MAX_IPC_SOCKETS {
-                let socket_path = format!("{}/{}{}", dir, constants::IPC_SOCKET_PREFIX, i);
-
-                // Check if we can connect to this socket
-                if let Ok(stream) = UnixStream::connect(&socket_path) {
-                    drop(stream); // Close the test connection
-                    pipes.push(DiscoveredPipe {
-                        pipe_number: i,
-                        path: socket_path,
-                    });
-                }
+        for path in crate::ipc::discovery::get_socket_paths() {
+            // Check if we can connect to this socket
+            if let Ok(stream) = UnixStream::connect(&path) {
+                drop(stream); // Close the test connection
+                pipes.push(DiscoveredPipe {
+                    pipe_number: crate::ipc::discovery::pipe_number(&path),
+                    path,
+                });
             }
         }
 
@@ -119,11 +321,7 @@ impl IpcConnection {
         for path in crate::ipc::discovery::get_pipe_paths() {
             // Try to open the named pipe to check if it exists
             if let Ok(file) = OpenOptions::new().read(true).write(true).open(&path) {
-                let pipe_number = path
-                    .chars()
-                    .last()
-                    .and_then(|c| c.to_digit(10))
-                    .unwrap_or(0) as u8;
+                let pipe_number = crate::ipc::discovery::pipe_number(&path);
 
                 drop(file); // Close the test connection
                 pipes.push(DiscoveredPipe { pipe_number, path });
@@ -149,31 +347,7 @@ impl IpcConnection {
 
     /// Create a new IPC connection with pipe and protocol configuration.
     pub fn new_with_configs(config: Option<PipeConfig>, ipc_config: IpcConfig) -> Result<Self> {
-        let config = config.unwrap_or_default();
-
-        #[cfg(unix)]
-        {
-            let stream = Self::connect_to_discord_unix_with_config(&config, &ipc_config)?;
-            Ok(Self {
-                stream,
-                read_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
-                write_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
-                ipc_config,
-            })
-        }
-
-        #[cfg(windows)]
-        {
-            let (reader, writer) =
-                Self::connect_to_discord_windows_with_config(&config, &ipc_config)?;
-            Ok(Self {
-                reader,
-                writer,
-                read_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
-                write_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
-                ipc_config,
-            })
-        }
+        Self::try_connect_with_config(&config.unwrap_or_default(), &ipc_config)
     }
 
     /// Create a new IPC connection (uses auto-discovery)
@@ -205,71 +379,106 @@ impl IpcConnection {
     }
 
     /// Create a new IPC connection with pipe configuration, timeout, and protocol configuration.
+    ///
+    /// Waits for Discord like [`IpcConnection::wait_for_socket_with_configs`].
     pub fn new_with_configs_and_timeout(
         config: Option<PipeConfig>,
         timeout_ms: u64,
         ipc_config: IpcConfig,
     ) -> Result<Self> {
-        use std::time::{Duration, Instant};
+        Self::wait_for_socket_with_configs(config, Duration::from_millis(timeout_ms), ipc_config)
+    }
 
-        let start = Instant::now();
-        let timeout = Duration::from_millis(timeout_ms);
-        let config = config.unwrap_or_default();
+    /// Wait up to `timeout` for Discord to start, then connect
+    ///
+    /// On Linux the socket directories are watched with inotify and the connection
+    /// is made as soon as a `discord-ipc-N` socket appears. Other platforms try again
+    /// every `retry_interval_ms`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionTimeout` if no socket could be connected
+    /// in time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use presenceforge::ipc::IpcConnection;
+    ///
+    /// let connection = IpcConnection::wait_for_socket(Duration::from_secs(30))?;
+    /// # Ok::<(), presenceforge::DiscordIpcError>(())
+    /// ```
+    pub fn wait_for_socket(timeout: Duration) -> Result<Self> {
+        Self::wait_for_socket_with_configs(None, timeout, IpcConfig::default())
+    }
 
-        let mut last_error_message = None;
+    /// Like [`IpcConnection::wait_for_socket`], with pipe and protocol configuration
+    ///
+    /// With [`PipeConfig::CustomPath`], only that socket is waited for.
+    pub fn wait_for_socket_with_configs(
+        config: Option<PipeConfig>,
+        timeout: Duration,
+        ipc_config: IpcConfig,
+    ) -> Result<Self> {
+        // A timeout too large to represent waits forever
+        let deadline = Instant::now().checked_add(timeout);
+        let config = config.unwrap_or_default();
+        let mut watch = SocketWatch::new(&config, &ipc_config);
 
-        // Keep trying to connect until we succeed or timeout
-        while start.elapsed() < timeout {
-            match Self::try_connect_with_config(&config, &ipc_config) {
+        loop {
+            let last_error_message = match Self::try_connect_with_config(&config, &ipc_config) {
                 Ok(connection) => return Ok(connection),
-                Err(DiscordIpcError::NoValidSocket) => {
-                    last_error_message = Some("No valid Discord socket found".to_string());
-                    // Wait a bit before trying again
-                    std::thread::sleep(Duration::from_millis(ipc_config.retry_interval_ms));
-                    continue;
-                }
-                Err(DiscordIpcError::SocketDiscoveryFailed { ref source, .. }) => {
-                    last_error_message = Some(format!("Socket discovery failed: {}", source));
-                    // Wait a bit before trying again
-                    std::thread::sleep(Duration::from_millis(ipc_config.retry_interval_ms));
-                    continue;
-                }
-                Err(e) => {
-                    // Non-recoverable error
-                    return Err(e);
-                }
+                Err(err) => match not_listening_yet(&err) {
+                    Some(message) => message,
+                    None => return Err(err),
+                },
+            };
+
+            let remaining = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+                None => Duration::MAX,
+            };
+            if remaining.is_zero() {
+                return Err(DiscordIpcError::connection_timeout(
+                    timeout.as_millis() as u64,
+                    Some(last_error_message),
+                ));
             }
+            watch.wait(remaining);
         }
-
-        Err(DiscordIpcError::connection_timeout(
-            timeout_ms,
-            last_error_message,
-        ))
     }
 
     /// Try to connect to Discord with configuration
     fn try_connect_with_config(config: &PipeConfig, ipc_config: &IpcConfig) -> Result<Self> {
         #[cfg(unix)]
         {
-            let stream = Self::connect_to_discord_unix_with_config(config, ipc_config)?;
+            let (stream, path) = Self::connect_to_discord_unix_with_config(config, ipc_config)?;
             Ok(Self {
                 stream,
+                path,
                 read_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
                 write_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
                 ipc_config: ipc_config.clone(),
+                usable: true,
             })
         }
 
         #[cfg(windows)]
         {
-            let (reader, writer) =
+            let (reader, writer, path) =
                 Self::connect_to_discord_windows_with_config(config, ipc_config)?;
             Ok(Self {
                 reader,
                 writer,
+                path,
                 read_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
                 write_buf: BytesMut::with_capacity(Self::INITIAL_BUFFER_CAPACITY),
                 ipc_config: ipc_config.clone(),
+                usable: true,
+                read_timeout: None,
+                write_timeout: None,
+                canceller: cancel::Canceller::default(),
             })
         }
     }
@@ -279,50 +488,52 @@ impl IpcConnection {
     fn connect_to_discord_unix_with_config(
         config: &PipeConfig,
         ipc_config: &IpcConfig,
-    ) -> Result<UnixStream> {
-        match config {
-            PipeConfig::Auto => {
+    ) -> Result<(UnixStream, String)> {
+        let (stream, path) = match config {
+            PipeConfig::Auto | PipeConfig::ReleaseChannel(_) | PipeConfig::UserId(_) => {
                 // Auto-discovery: try all possible pipes
-                Self::connect_to_discord_unix_auto(ipc_config)
+                Self::connect_to_discord_unix_auto(ipc_config)?
             }
             PipeConfig::CustomPath(path) => {
                 // Connect to custom path
-                UnixStream::connect(path)
+                let stream = UnixStream::connect(path)
                     .and_then(|stream| {
                         stream.set_nonblocking(false)?;
                         Ok(stream)
                     })
-                    .map_err(DiscordIpcError::ConnectionFailed)
+                    .map_err(DiscordIpcError::ConnectionFailed)?;
+                (stream, path.clone())
             }
-        }
+        };
+
+        stream.set_read_timeout(ipc_config.read_timeout)?;
+        stream.set_write_timeout(ipc_config.write_timeout)?;
+        Ok((stream, path))
     }
 
     #[cfg(unix)]
     /// Connect to Discord IPC socket using auto-discovery
-    fn connect_to_discord_unix_auto(ipc_config: &IpcConfig) -> Result<UnixStream> {
+    fn connect_to_discord_unix_auto(ipc_config: &IpcConfig) -> Result<(UnixStream, String)> {
         // Try each directory with each socket number
         let mut last_error = None;
         let mut attempted_paths = Vec::new();
 
-        for dir in crate::ipc::discovery::candidate_ipc_directories() {
-            for i in 0..ipc_config.max_sockets {
-                let socket_path = format!("{}/{}{}", dir, constants::IPC_SOCKET_PREFIX, i);
-                attempted_paths.push(socket_path.clone());
-
-                match UnixStream::connect(&socket_path) {
-                    Ok(stream) => {
-                        // Configure socket
-                        if let Err(err) = stream.set_nonblocking(false) {
-                            last_error = Some(err);
-                            continue;
-                        }
+        for socket_path in crate::ipc::discovery::get_socket_paths_with_config(ipc_config) {
+            attempted_paths.push(socket_path.clone());
 
-                        return Ok(stream);
-                    }
-                    Err(err) => {
+            match UnixStream::connect(&socket_path) {
+                Ok(stream) => {
+                    // Configure socket
+                    if let Err(err) = stream.set_nonblocking(false) {
                         last_error = Some(err);
                         continue;
                     }
+
+                    return Ok((stream, socket_path));
+                }
+                Err(err) => {
+                    last_error = Some(err);
+                    continue;
                 }
             }
         }
@@ -344,9 +555,9 @@ impl IpcConnection {
     fn connect_to_discord_windows_with_config(
         config: &PipeConfig,
         ipc_config: &IpcConfig,
-    ) -> Result<(BufReader<std::fs::File>, BufWriter<std::fs::File>)> {
+    ) -> Result<(BufReader<std::fs::File>, BufWriter<std::fs::File>, String)> {
         match config {
-            PipeConfig::Auto => {
+            PipeConfig::Auto | PipeConfig::ReleaseChannel(_) | PipeConfig::UserId(_) => {
                 // Auto-discovery: try all possible pipes
                 Self::connect_to_discord_windows_auto(ipc_config)
             }
@@ -358,7 +569,11 @@ impl IpcConnection {
                     .open(path)
                     .and_then(|file| {
                         let reader_file = file.try_clone()?;
-                        Ok((BufReader::new(reader_file), BufWriter::new(file)))
+                        Ok((
+                            BufReader::new(reader_file),
+                            BufWriter::new(file),
+                            path.clone(),
+                        ))
                     })
                     .map_err(DiscordIpcError::ConnectionFailed)
             }
@@ -369,11 +584,11 @@ impl IpcConnection {
     /// Connect to Discord IPC named pipe on Windows using auto-discovery
     fn connect_to_discord_windows_auto(
         ipc_config: &IpcConfig,
-    ) -> Result<(BufReader<std::fs::File>, BufWriter<std::fs::File>)> {
+    ) -> Result<(BufReader<std::fs::File>, BufWriter<std::fs::File>, String)> {
         let mut last_error = None;
         let mut attempted_paths = Vec::new();
 
-        for path in crate::ipc::discovery::get_pipe_paths_with_limit(ipc_config.max_sockets) {
+        for path in crate::ipc::discovery::get_pipe_paths_with_config(ipc_config) {
             attempted_paths.push(path.clone());
 
             // Try to open the named pipe
@@ -383,7 +598,11 @@ impl IpcConnection {
                     match file.try_clone() {
                         Ok(reader_file) => {
                             let writer_file = file;
-                            return Ok((BufReader::new(reader_file), BufWriter::new(writer_file)));
+                            return Ok((
+                                BufReader::new(reader_file),
+                                BufWriter::new(writer_file),
+                                path,
+                            ));
                         }
                         Err(err) => {
                             last_error = Some(err);
@@ -410,8 +629,17 @@ impl IpcConnection {
         }
     }
 
+    /// The socket or pipe path this connection was opened on
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
     /// Send data with opcode
     pub fn send(&mut self, opcode: Opcode, payload: &Value) -> Result<()> {
+        if !self.usable {
+            return Err(DiscordIpcError::SocketClosed);
+        }
+
         let raw = serde_json::to_vec(payload)?;
         // Clear and prepare write buffer
         self.write_buf.clear();
@@ -423,44 +651,142 @@ impl IpcConnection {
         self.write_buf.extend_from_slice(&raw);
 
         #[cfg(unix)]
-        {
-            use std::io::Write;
-            self.stream.write_all(&self.write_buf)?;
-        }
+        let written = self.stream.write_all(&self.write_buf);
 
         #[cfg(windows)]
-        {
-            use std::io::Write;
-            self.writer.write_all(&self.write_buf)?;
-            self.writer.flush()?;
-        }
+        let written = {
+            let (writer, buf) = (&mut self.writer, &self.write_buf);
+            let handle = writer.get_ref().as_raw_handle();
+            self.canceller.run(handle, self.write_timeout, || {
+                writer.write_all(buf).and_then(|_| writer.flush())
+            })
+        };
 
-        Ok(())
+        written.map_err(|err| {
+            if timed_out(&err) {
+                self.mark_timed_out("write")
+            } else {
+                DiscordIpcError::ConnectionFailed(err)
+            }
+        })
     }
 
     /// Receive data and return opcode and payload
     pub fn recv(&mut self) -> Result<(Opcode, Value)> {
-        // Read header into buffer
-        self.read_buf.clear();
-        self.read_buf.reserve(8);
+        if !self.usable {
+            return Err(DiscordIpcError::SocketClosed);
+        }
+
+        loop {
+            if let Some(frame) = self.take_frame()? {
+                return Ok(frame);
+            }
 
-        let mut header = [0u8; 8];
+            let mut chunk = [0u8; Self::READ_CHUNK_SIZE];
 
-        #[cfg(unix)]
-        {
-            self.stream
-                .read_exact(&mut header)
-                .map_err(|_| DiscordIpcError::SocketClosed)?;
+            #[cfg(unix)]
+            let read = self.stream.read(&mut chunk);
+
+            #[cfg(windows)]
+            let read = {
+                let reader = &mut self.reader;
+                let handle = reader.get_ref().as_raw_handle();
+                self.canceller
+                    .run(handle, self.read_timeout, || reader.read(&mut chunk))
+            };
+
+            match read {
+                Ok(0) => return Err(DiscordIpcError::SocketClosed),
+                Ok(n) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(self.read_error(&err)),
+            }
+        }
+    }
+
+    /// Receive a frame if one has fully arrived, without blocking
+    ///
+    /// Reads whatever bytes are available and keeps partial frames buffered for the
+    /// next call (and for `recv`). On Windows the pipe is asked how many bytes it
+    /// holds, and exactly those are read.
+    pub fn try_recv(&mut self) -> Result<Option<(Opcode, Value)>> {
+        if !self.usable {
+            return Err(DiscordIpcError::SocketClosed);
+        }
+
+        if let Some(frame) = self.take_frame()? {
+            return Ok(Some(frame));
         }
 
+        #[cfg(unix)]
+        let closed = {
+            self.stream.set_nonblocking(true)?;
+            let drained = self.read_available();
+            self.stream.set_nonblocking(false)?;
+            drained?
+        };
+
         #[cfg(windows)]
-        {
-            self.reader
-                .read_exact(&mut header)
-                .map_err(|_| DiscordIpcError::SocketClosed)?;
+        let closed = self.read_available()?;
+
+        let frame = self.take_frame()?;
+        if frame.is_none() && closed {
+            return Err(DiscordIpcError::SocketClosed);
         }
+        Ok(frame)
+    }
+
+    /// Read everything the socket has ready; returns `true` if the peer closed it
+    #[cfg(unix)]
+    fn read_available(&mut self) -> Result<bool> {
+        let mut chunk = [0u8; Self::READ_CHUNK_SIZE];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Ok(true),
+                Ok(n) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => return Err(DiscordIpcError::SocketClosed),
+            }
+        }
+    }
+
+    /// Read everything the pipe has ready; returns `true` if the peer closed it
+    #[cfg(windows)]
+    fn read_available(&mut self) -> Result<bool> {
+        // Bytes an earlier `recv` pulled into the reader's buffer come first
+        let buffered = self.reader.buffer().len();
+        self.read_buf.extend_from_slice(self.reader.buffer());
+        self.reader.consume(buffered);
+
+        let pipe = self.reader.get_mut();
+        let mut chunk = [0u8; Self::READ_CHUNK_SIZE];
+        loop {
+            let available = match pipe::available(pipe.as_raw_handle()) {
+                Ok(0) => return Ok(false),
+                Ok(available) => available,
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(true),
+                Err(_) => return Err(DiscordIpcError::SocketClosed),
+            };
+
+            // Never blocks: the pipe already holds at least this many bytes
+            let wanted = available.min(chunk.len());
+            match pipe.read(&mut chunk[..wanted]) {
+                Ok(0) => return Ok(true),
+                Ok(n) => self.read_buf.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => return Err(DiscordIpcError::SocketClosed),
+            }
+        }
+    }
 
-        let mut header_reader = &header[..];
+    /// Split the next complete frame off the read buffer
+    fn take_frame(&mut self) -> Result<Option<(Opcode, Value)>> {
+        if self.read_buf.len() < constants::IPC_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let mut header_reader = &self.read_buf[..constants::IPC_HEADER_SIZE];
         let opcode_raw = header_reader.read_u32::<LittleEndian>()?;
         let length = header_reader.read_u32::<LittleEndian>()?;
 
@@ -479,26 +805,66 @@ impl IpcConnection {
 
         let opcode = Opcode::try_from(opcode_raw)?;
 
-        // Reuse read buffer for payload
-        self.read_buf.clear();
-        self.read_buf.resize(length as usize, 0);
+        let frame_len = constants::IPC_HEADER_SIZE + length as usize;
+        if self.read_buf.len() < frame_len {
+            self.read_buf.reserve(frame_len - self.read_buf.len());
+            return Ok(None);
+        }
+
+        let frame = self.read_buf.split_to(frame_len);
+        let value: Value = serde_json::from_slice(&frame[constants::IPC_HEADER_SIZE..])?;
+        Ok(Some((opcode, value)))
+    }
 
+    /// Set how long `recv` waits for data; `None` blocks until data arrives
+    ///
+    /// On Windows a helper thread cancels a read that runs past the timeout.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
         #[cfg(unix)]
+        self.stream.set_read_timeout(timeout)?;
+
+        #[cfg(windows)]
         {
-            self.stream
-                .read_exact(&mut self.read_buf[..])
-                .map_err(|_| DiscordIpcError::SocketClosed)?;
+            self.read_timeout = timeout;
         }
 
+        Ok(())
+    }
+
+    /// Set how long `send` may block; `None` blocks until the write completes
+    ///
+    /// On Windows a helper thread cancels a write that runs past the timeout.
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        #[cfg(unix)]
+        self.stream.set_write_timeout(timeout)?;
+
         #[cfg(windows)]
         {
-            self.reader
-                .read_exact(&mut self.read_buf[..])
-                .map_err(|_| DiscordIpcError::SocketClosed)?;
+            self.write_timeout = timeout;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `false` once a timeout has left the stream in an unknown state
+    ///
+    /// A timeout can interrupt a frame halfway, so every later `send` and `recv`
+    /// fails with `DiscordIpcError::SocketClosed` until a new connection is made.
+    pub fn is_usable(&self) -> bool {
+        self.usable
+    }
+
+    fn read_error(&mut self, err: &io::Error) -> DiscordIpcError {
+        if timed_out(err) {
+            self.mark_timed_out("read")
+        } else {
+            DiscordIpcError::SocketClosed
         }
+    }
 
-        let value: Value = serde_json::from_slice(&self.read_buf)?;
-        Ok((opcode, value))
+    fn mark_timed_out(&mut self, operation: &'static str) -> DiscordIpcError {
+        self.usable = false;
+        DiscordIpcError::request_timeout(operation, None)
     }
 
     /// Close the connection
@@ -515,3 +881,397 @@ impl IpcConnection {
         }
     }
 }
+
+/// Why to keep waiting, if `err` only means that Discord is not listening yet
+///
+/// Shared by the sync and async `wait_for_socket`.
+pub(crate) fn not_listening_yet(err: &DiscordIpcError) -> Option<String> {
+    match err {
+        DiscordIpcError::NoValidSocket => Some("No valid Discord socket found".to_string()),
+        DiscordIpcError::SocketDiscoveryFailed { source, .. } => {
+            Some(format!("Socket discovery failed: {}", source))
+        }
+        // A custom path that does not exist or accept connections yet
+        DiscordIpcError::ConnectionFailed(source)
+            if matches!(
+                source.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            Some(source.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Socket timeouts surface as `WouldBlock` on Unix and `TimedOut` elsewhere
+fn timed_out(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// Non-blocking queries on Windows named pipes
+#[cfg(windows)]
+mod pipe {
+    use std::ffi::c_void;
+    use std::io;
+    use std::os::windows::io::RawHandle;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn PeekNamedPipe(
+            pipe: *mut c_void,
+            buffer: *mut c_void,
+            buffer_size: u32,
+            bytes_read: *mut u32,
+            total_bytes_available: *mut u32,
+            bytes_left_this_message: *mut u32,
+        ) -> i32;
+    }
+
+    /// Bytes that can be read from `handle` without blocking
+    ///
+    /// A pipe closed by the other end fails with `io::ErrorKind::BrokenPipe`.
+    pub(super) fn available(handle: RawHandle) -> io::Result<usize> {
+        let mut available = 0u32;
+        // SAFETY: no buffer is passed, and `available` is valid for the whole call
+        let ok = unsafe {
+            PeekNamedPipe(
+                handle,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+                &mut available,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(available as usize)
+    }
+}
+
+/// Timeouts for the blocking pipe handles on Windows, which have none of their own
+#[cfg(windows)]
+mod cancel {
+    use std::ffi::c_void;
+    use std::io;
+    use std::os::windows::io::RawHandle;
+    use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CancelIoEx(handle: *mut c_void, overlapped: *mut c_void) -> i32;
+    }
+
+    /// The operation the canceller thread is timing
+    #[derive(Default)]
+    struct State {
+        /// Handle and deadline of the pending operation; raw handles are not `Send`
+        armed: Option<(usize, Instant)>,
+        cancelled: bool,
+        shutdown: bool,
+    }
+
+    #[derive(Default)]
+    struct Shared {
+        state: Mutex<State>,
+        changed: Condvar,
+    }
+
+    impl Shared {
+        fn lock(&self) -> MutexGuard<'_, State> {
+            self.state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+    }
+
+    /// Cancels a connection's blocking reads and writes once they run past their timeout
+    ///
+    /// One thread per connection does the waiting. It starts with the first operation
+    /// that has a timeout and stops when the canceller is dropped.
+    #[derive(Default)]
+    pub(super) struct Canceller {
+        shared: Arc<Shared>,
+        worker: Option<JoinHandle<()>>,
+    }
+
+    impl Canceller {
+        /// Run a blocking read or write on `handle`, cancelling it once `timeout` passes
+        ///
+        /// A cancelled operation fails with `io::ErrorKind::TimedOut`.
+        pub(super) fn run<T>(
+            &mut self,
+            handle: RawHandle,
+            timeout: Option<Duration>,
+            operation: impl FnOnce() -> io::Result<T>,
+        ) -> io::Result<T> {
+            // A timeout too large for an `Instant` never expires
+            let Some(deadline) = timeout.and_then(|timeout| Instant::now().checked_add(timeout))
+            else {
+                return operation();
+            };
+            if self.worker.is_none() {
+                let shared = Arc::clone(&self.shared);
+                self.worker = Some(
+                    thread::Builder::new()
+                        .name("presenceforge-cancel".to_string())
+                        .spawn(move || watch(&shared))?,
+                );
+            }
+
+            {
+                let mut state = self.shared.lock();
+                state.armed = Some((handle as usize, deadline));
+                state.cancelled = false;
+            }
+            self.shared.changed.notify_one();
+
+            let result = operation();
+            // Disarming under the lock means a cancel never hits a later operation
+            let cancelled = {
+                let mut state = self.shared.lock();
+                state.armed = None;
+                state.cancelled
+            };
+            match result {
+                Err(err) if cancelled => Err(io::Error::new(io::ErrorKind::TimedOut, err)),
+                result => result,
+            }
+        }
+    }
+
+    impl Drop for Canceller {
+        fn drop(&mut self) {
+            if let Some(worker) = self.worker.take() {
+                self.shared.lock().shutdown = true;
+                self.shared.changed.notify_one();
+                let _ = worker.join();
+            }
+        }
+    }
+
+    /// Cancel each armed operation that is still pending at its deadline
+    fn watch(shared: &Shared) {
+        let mut state = shared.lock();
+        while !state.shutdown {
+            let Some((handle, deadline)) = state.armed else {
+                state = shared
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                continue;
+            };
+            let now = Instant::now();
+            if now < deadline {
+                state = shared
+                    .changed
+                    .wait_timeout(state, deadline - now)
+                    .map(|(state, _)| state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner().0);
+                continue;
+            }
+            // SAFETY: the handle stays open while armed, since the connection owning it
+            // is blocked in the operation; cancelling when nothing is pending is a no-op
+            unsafe { CancelIoEx(handle as *mut c_void, std::ptr::null_mut()) };
+            state.armed = None;
+            state.cancelled = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ready(api_endpoint: &str) -> ReadyEvent {
+        ReadyEvent {
+            user: None,
+            config: Some(ReadyConfig {
+                cdn_host: Some("cdn.discordapp.com".to_string()),
+                api_endpoint: Some(api_endpoint.to_string()),
+                environment: Some("production".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn release_channel_is_read_from_the_api_endpoint() {
+        assert_eq!(
+            ready("//discord.com/api").release_channel(),
+            Some(ReleaseChannel::Stable)
+        );
+        assert_eq!(
+            ready("//canary.discord.com/api").release_channel(),
+            Some(ReleaseChannel::Canary)
+        );
+        assert_eq!(
+            ready("//ptb.discord.com/api").release_channel(),
+            Some(ReleaseChannel::Ptb)
+        );
+        assert_eq!(ready("//localhost:1337/api").release_channel(), None);
+        assert!(ReleaseChannel::Any.accepts(None));
+        assert!(!ReleaseChannel::Stable.accepts(None));
+    }
+
+    #[test]
+    fn socket_search_reports_what_it_found_instead() {
+        let mut search = SocketSearch::new(SocketTarget::Channel(ReleaseChannel::Canary), None);
+        search.opened();
+        assert!(!search.accepts("discord-ipc-0", &ready("//discord.com/api")));
+        search.opened();
+        search.failed("discord-ipc-1", DiscordIpcError::SocketClosed);
+        assert!(matches!(
+            search.into_error(),
+            DiscordIpcError::ReleaseChannelNotFound { wanted: ReleaseChannel::Canary, found }
+                if found == [ReleaseChannel::Stable]
+        ));
+
+        assert!(matches!(
+            SocketSearch::new(SocketTarget::Channel(ReleaseChannel::Stable), None).into_error(),
+            DiscordIpcError::NoValidSocket
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wait_for_socket_connects_once_the_socket_appears() {
+        let path =
+            std::env::temp_dir().join(format!("presenceforge-wait-{}", uuid::Uuid::new_v4()));
+        let config = PipeConfig::CustomPath(path.to_string_lossy().into_owned());
+
+        let listener = std::thread::spawn({
+            let path = path.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(100));
+                let listener = std::os::unix::net::UnixListener::bind(path).unwrap();
+                listener.accept().unwrap()
+            }
+        });
+        let connection = IpcConnection::wait_for_socket_with_configs(
+            Some(config.clone()),
+            // Too far out for an `Instant`, so there is no deadline
+            Duration::MAX,
+            IpcConfig::default(),
+        );
+        assert!(connection.is_ok());
+        drop(listener.join().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            IpcConnection::wait_for_socket_with_configs(
+                Some(config),
+                Duration::from_millis(50),
+                IpcConfig::default(),
+            ),
+            Err(DiscordIpcError::ConnectionTimeout { timeout_ms: 50, .. })
+        ));
+    }
+
+    /// The server end of a new named pipe and a connection to it
+    #[cfg(windows)]
+    fn pipe_pair() -> (std::fs::File, IpcConnection) {
+        use std::ffi::c_void;
+        use std::os::windows::ffi::OsStrExt;
+        use std::os::windows::io::FromRawHandle;
+
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn CreateNamedPipeW(
+                name: *const u16,
+                open_mode: u32,
+                pipe_mode: u32,
+                max_instances: u32,
+                out_buffer_size: u32,
+                in_buffer_size: u32,
+                default_timeout: u32,
+                security_attributes: *mut c_void,
+            ) -> *mut c_void;
+        }
+        const PIPE_ACCESS_DUPLEX: u32 = 3;
+
+        let path = format!(r"\\.\pipe\presenceforge-test-{}", uuid::Uuid::new_v4());
+        let name: Vec<u16> = std::ffi::OsStr::new(&path)
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+        // SAFETY: `name` is NUL-terminated and outlives the call; byte mode is 0
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                0,
+                1,
+                4096,
+                4096,
+                0,
+                std::ptr::null_mut(),
+            )
+        };
+        assert_ne!(handle as isize, -1, "CreateNamedPipeW failed");
+        // SAFETY: CreateNamedPipeW returned a new handle that nothing else owns
+        let server = unsafe { std::fs::File::from_raw_handle(handle) };
+
+        // A client that opens the pipe is connected without ConnectNamedPipe
+        let connection = IpcConnection::new_with_configs(
+            Some(PipeConfig::CustomPath(path)),
+            IpcConfig::default(),
+        )
+        .unwrap();
+        (server, connection)
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn try_recv_buffers_partial_frames_on_windows() {
+        let (mut server, mut connection) = pipe_pair();
+        let payload = serde_json::to_vec(&serde_json::json!({ "evt": "ACTIVITY_JOIN" })).unwrap();
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&1u32.to_le_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+
+        assert!(connection.try_recv().unwrap().is_none());
+        // Half a header, then the rest of it with half the payload
+        for part in [&frame[..4], &frame[4..12]] {
+            server.write_all(part).unwrap();
+            assert!(connection.try_recv().unwrap().is_none());
+        }
+        server.write_all(&frame[12..]).unwrap();
+        let (opcode, value) = connection.try_recv().unwrap().expect("complete frame");
+        assert_eq!(opcode, Opcode::Frame);
+        assert_eq!(value["evt"], "ACTIVITY_JOIN");
+
+        drop(server);
+        assert!(matches!(
+            connection.try_recv(),
+            Err(DiscordIpcError::SocketClosed)
+        ));
+    }
+
+    #[test]
+    fn socket_search_matches_the_ready_user() {
+        let mut ready = ready("//discord.com/api");
+        ready.user = serde_json::from_value(serde_json::json!({"id": "42"})).unwrap();
+
+        let mut search = SocketSearch::new(SocketTarget::User("42".to_string()), None);
+        search.opened();
+        assert!(search.accepts("discord-ipc-0", &ready));
+
+        let mut search = SocketSearch::new(SocketTarget::User("7".to_string()), None);
+        search.opened();
+        assert!(!search.accepts("discord-ipc-0", &ready));
+        assert!(matches!(
+            search.into_error(),
+            DiscordIpcError::UserNotFound { user_id, found } if user_id == "7" && found == ["42"]
+        ));
+        assert!(PipeConfig::UserId("7".to_string()).target().is_some());
+        assert!(PipeConfig::Auto.target().is_none());
+    }
+}
@@ -1,7 +1,114 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // Copyright (c) 2025-2026 Sreehari Anil and project contributors
 
-use crate::ipc::protocol::constants;
+use std::borrow::Cow;
+
+use crate::ipc::protocol::{constants, IpcConfig};
+
+/// Environment variables that override socket discovery, in order of precedence
+///
+/// The value is either a socket path (its file name starts with `discord-ipc-`) or a
+/// directory to look for `discord-ipc-N` sockets in. When set, the built-in locations
+/// are not searched; directories added with `IpcConfig::with_search_dir` still are.
+/// On Windows the value is used as the pipe path.
+pub const IPC_PATH_ENV_VARS: [&str; 2] = ["PRESENCEFORGE_IPC_PATH", "DISCORD_IPC_PATH"];
+
+/// Where a Discord build, fork or sandboxed package keeps its IPC socket
+///
+/// The directory is relative to the runtime directory (`XDG_RUNTIME_DIR`, and
+/// `/run/user/<uid>`). Only used on Unix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownLocation {
+    /// Human-readable name, for diagnostics
+    pub name: Cow<'static, str>,
+    /// Directory of the socket relative to the runtime directory
+    pub runtime_subdir: Cow<'static, str>,
+}
+
+impl KnownLocation {
+    /// A location for [`IpcConfig::with_known_location`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use presenceforge::ipc::KnownLocation;
+    /// use presenceforge::IpcConfig;
+    ///
+    /// let config = IpcConfig::default()
+    ///     .with_known_location(KnownLocation::new("My fork (Flatpak)", "app/org.example.Fork"));
+    /// ```
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        runtime_subdir: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            runtime_subdir: runtime_subdir.into(),
+        }
+    }
+
+    const fn builtin(name: &'static str, runtime_subdir: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            runtime_subdir: Cow::Borrowed(runtime_subdir),
+        }
+    }
+}
+
+/// Built-in fork and sandbox locations, the default of `IpcConfig::known_locations`
+///
+/// Snap packages (`snap.discord*`) are found by scanning the runtime directory and
+/// need no entry here.
+pub const KNOWN_LOCATIONS: &[KnownLocation] = &[
+    KnownLocation::builtin("Discord (Flatpak)", "app/com.discordapp.Discord"),
+    KnownLocation::builtin(
+        "Discord Canary (Flatpak)",
+        "app/com.discordapp.DiscordCanary",
+    ),
+    KnownLocation::builtin("Discord PTB (Flatpak)", "app/com.discordapp.DiscordPTB"),
+    KnownLocation::builtin("Vesktop (Flatpak)", "app/dev.vencord.Vesktop"),
+    KnownLocation::builtin(
+        "Vesktop (Flatpak xdg-run)",
+        ".flatpak/dev.vencord.Vesktop/xdg-run",
+    ),
+];
+
+/// The value of the first non-empty variable in [`IPC_PATH_ENV_VARS`]
+pub fn ipc_path_override() -> Option<String> {
+    IPC_PATH_ENV_VARS
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// What an override from [`IPC_PATH_ENV_VARS`] points at
+#[cfg(unix)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IpcOverride {
+    Socket(String),
+    Dir(String),
+}
+
+#[cfg(unix)]
+impl IpcOverride {
+    pub(crate) fn from_env() -> Option<Self> {
+        ipc_path_override().map(Self::parse)
+    }
+
+    fn parse(value: String) -> Self {
+        let is_socket = std::path::Path::new(&value)
+            .file_name()
+            .is_some_and(|name| {
+                name.to_string_lossy()
+                    .starts_with(constants::IPC_SOCKET_PREFIX)
+            });
+        if is_socket {
+            Self::Socket(value)
+        } else {
+            Self::Dir(value.trim_end_matches('/').to_string())
+        }
+    }
+}
 
 /// Returns the current user's UID on Unix-based systems.
 #[cfg(unix)]
@@ -9,70 +116,88 @@ fn current_uid() -> u32 {
     unsafe { libc::getuid() }
 }
 
+#[cfg(unix)]
+fn push_unique(directories: &mut Vec<String>, dir: String) {
+    if !directories.contains(&dir) {
+        directories.push(dir);
+    }
+}
+
+/// Add the known locations and Snap directories under a runtime directory
+#[cfg(unix)]
+fn push_runtime_locations(
+    directories: &mut Vec<String>,
+    runtime_dir: &str,
+    ipc_config: &IpcConfig,
+) {
+    for location in &ipc_config.known_locations {
+        push_unique(
+            directories,
+            format!("{}/{}", runtime_dir, location.runtime_subdir),
+        );
+    }
+
+    // Snap (can be snap.discord, snap.discord-canary, etc.)
+    if let Ok(entries) = std::fs::read_dir(runtime_dir) {
+        for entry in entries.flatten() {
+            if let Ok(name) = entry.file_name().into_string() {
+                if name.starts_with("snap.discord")
+                    && entry.metadata().is_ok_and(|metadata| metadata.is_dir())
+                {
+                    push_unique(directories, format!("{}/{}", runtime_dir, name));
+                }
+            }
+        }
+    }
+}
+
 /// Discovers potential base directories where Discord IPC sockets may exist on Unix systems.
 #[cfg(unix)]
 pub fn candidate_ipc_directories() -> Vec<String> {
-    let env_keys = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP", "tmp"];
+    candidate_ipc_directories_with_config(&IpcConfig::default())
+}
+
+/// Like [`candidate_ipc_directories`], with the search directories and known
+/// locations from `ipc_config`
+#[cfg(unix)]
+pub fn candidate_ipc_directories_with_config(ipc_config: &IpcConfig) -> Vec<String> {
+    directories(ipc_config, IpcOverride::from_env())
+}
+
+#[cfg(unix)]
+fn directories(ipc_config: &IpcConfig, ipc_override: Option<IpcOverride>) -> Vec<String> {
     let mut directories = Vec::new();
+    for dir in &ipc_config.search_dirs {
+        push_unique(&mut directories, dir.clone());
+    }
+    match ipc_override {
+        Some(IpcOverride::Dir(dir)) => {
+            push_unique(&mut directories, dir);
+            return directories;
+        }
+        Some(IpcOverride::Socket(_)) => return directories,
+        None => {}
+    }
 
+    let env_keys = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP", "tmp"];
     for key in &env_keys {
         if let Ok(dir) = std::env::var(key) {
-            directories.push(dir.clone());
+            push_unique(&mut directories, dir.clone());
 
             // Check specialized paths if XDG_RUNTIME_DIR is set
             if key == &"XDG_RUNTIME_DIR" {
-                // Flatpak
-                directories.push(format!("{}/app/com.discordapp.Discord", dir));
-
-                // Snap (can be snap.discord, snap.discord-canary, etc.)
-                if let Ok(entries) = std::fs::read_dir(&dir) {
-                    for entry in entries.flatten() {
-                        if let Ok(name) = entry.file_name().into_string() {
-                            if name.starts_with("snap.discord") {
-                                if let Ok(metadata) = entry.metadata() {
-                                    if metadata.is_dir() {
-                                        directories.push(format!("{}/{}", dir, name));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+                push_runtime_locations(&mut directories, &dir, ipc_config);
             }
         }
     }
 
     // Fallback to /run/user/{uid} if directories list is still thin or to ensure coverage
-    let uid = current_uid();
-    let run_user_dir = format!("/run/user/{}", uid);
-    if !directories.contains(&run_user_dir) {
-        directories.push(run_user_dir.clone());
-    }
+    let run_user_dir = format!("/run/user/{}", current_uid());
+    push_unique(&mut directories, run_user_dir.clone());
+    push_runtime_locations(&mut directories, &run_user_dir, ipc_config);
 
-    // Fallback specialized paths for /run/user/{uid}
-    let flatpak_fallback = format!("/run/user/{}/app/com.discordapp.Discord", uid);
-    if !directories.contains(&flatpak_fallback) {
-        directories.push(flatpak_fallback);
-    }
-
-    // Snap fallback scan
-    let base_run_user = format!("/run/user/{}", uid);
-    if let Ok(entries) = std::fs::read_dir(&base_run_user) {
-        for entry in entries.flatten() {
-            if let Ok(name) = entry.file_name().into_string() {
-                if name.starts_with("snap.discord") {
-                    if let Ok(metadata) = entry.metadata() {
-                        if metadata.is_dir() {
-                            let path = format!("{}/{}", base_run_user, name);
-                            if !directories.contains(&path) {
-                                directories.push(path);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    // arRPC and other IPC servers fall back to /tmp when no temporary directory is set
+    push_unique(&mut directories, "/tmp".to_string());
 
     directories
 }
@@ -86,15 +211,50 @@ pub fn get_socket_paths() -> Vec<String> {
 /// Returns a list of all potential Discord IPC socket paths to check with a custom scan limit.
 #[cfg(unix)]
 pub fn get_socket_paths_with_limit(max_sockets: u8) -> Vec<String> {
+    get_socket_paths_with_config(&IpcConfig::default().with_max_sockets(max_sockets))
+}
+
+/// Returns every socket path auto-discovery tries with `ipc_config`, in order
+///
+/// A socket named by an [`IPC_PATH_ENV_VARS`] override comes first.
+#[cfg(unix)]
+pub fn get_socket_paths_with_config(ipc_config: &IpcConfig) -> Vec<String> {
+    socket_paths(ipc_config, IpcOverride::from_env())
+}
+
+#[cfg(unix)]
+fn socket_paths(ipc_config: &IpcConfig, ipc_override: Option<IpcOverride>) -> Vec<String> {
     let mut paths = Vec::new();
-    for dir in candidate_ipc_directories() {
-        for i in 0..max_sockets {
+    if let Some(IpcOverride::Socket(path)) = &ipc_override {
+        paths.push(path.clone());
+    }
+    for dir in directories(ipc_config, ipc_override) {
+        for i in 0..ipc_config.max_sockets {
             paths.push(format!("{}/{}{}", dir, constants::IPC_SOCKET_PREFIX, i));
         }
     }
     paths
 }
 
+/// Every socket or pipe path auto-discovery would try, in order
+pub(crate) fn candidate_paths(ipc_config: &IpcConfig) -> Vec<String> {
+    #[cfg(unix)]
+    {
+        get_socket_paths_with_config(ipc_config)
+    }
+
+    #[cfg(windows)]
+    {
+        get_pipe_paths_with_config(ipc_config)
+    }
+}
+
+/// The number at the end of a socket or pipe path, e.g. 0 for `discord-ipc-0`
+pub(crate) fn pipe_number(path: &str) -> u8 {
+    let digits = path.len() - path.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    path[path.len() - digits..].parse().unwrap_or(0)
+}
+
 #[cfg(windows)]
 pub fn get_pipe_paths() -> Vec<String> {
     get_pipe_paths_with_limit(constants::MAX_IPC_SOCKETS)
@@ -103,9 +263,72 @@ pub fn get_pipe_paths() -> Vec<String> {
 /// Returns a list of all potential Discord IPC pipe paths to check with a custom scan limit.
 #[cfg(windows)]
 pub fn get_pipe_paths_with_limit(max_sockets: u8) -> Vec<String> {
+    get_pipe_paths_with_config(&IpcConfig::default().with_max_sockets(max_sockets))
+}
+
+/// Returns every pipe path auto-discovery tries with `ipc_config`, in order
+///
+/// An [`IPC_PATH_ENV_VARS`] override replaces the numbered pipes.
+#[cfg(windows)]
+pub fn get_pipe_paths_with_config(ipc_config: &IpcConfig) -> Vec<String> {
+    if let Some(path) = ipc_path_override() {
+        return vec![path];
+    }
     let mut paths = Vec::new();
-    for i in 0..max_sockets {
+    for i in 0..ipc_config.max_sockets {
         paths.push(format!(r"\\.\pipe\discord-ipc-{}", i));
     }
     paths
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_dirs_and_known_locations_are_searched() {
+        let config = IpcConfig::default()
+            .with_search_dir("/opt/custom")
+            .with_known_location(KnownLocation::new("Fork", "app/org.example.Fork"));
+        let dirs = directories(&config, None);
+        assert_eq!(dirs[0], "/opt/custom");
+
+        let run_user_dir = format!("/run/user/{}", current_uid());
+        for location in KNOWN_LOCATIONS {
+            let dir = format!("{}/{}", run_user_dir, location.runtime_subdir);
+            assert!(dirs.contains(&dir), "{} missing", dir);
+        }
+        assert!(dirs.contains(&format!("{}/app/org.example.Fork", run_user_dir)));
+        assert!(dirs.contains(&"/tmp".to_string()));
+    }
+
+    #[test]
+    fn overrides_replace_the_built_in_locations() {
+        let config = IpcConfig::default().with_max_sockets(2);
+
+        let ipc_override = IpcOverride::parse("/srv/ipc/".to_string());
+        assert_eq!(ipc_override, IpcOverride::Dir("/srv/ipc".to_string()));
+        assert_eq!(
+            socket_paths(&config, Some(ipc_override)),
+            ["/srv/ipc/discord-ipc-0", "/srv/ipc/discord-ipc-1"]
+        );
+
+        let ipc_override = IpcOverride::parse("/srv/ipc/discord-ipc-3".to_string());
+        let config = config.with_search_dir("/opt/custom");
+        assert_eq!(
+            socket_paths(&config, Some(ipc_override)),
+            [
+                "/srv/ipc/discord-ipc-3",
+                "/opt/custom/discord-ipc-0",
+                "/opt/custom/discord-ipc-1"
+            ]
+        );
+    }
+
+    #[test]
+    fn pipe_numbers_are_read_from_the_path() {
+        assert_eq!(pipe_number("/run/user/1000/discord-ipc-7"), 7);
+        assert_eq!(pipe_number(r"\\.\pipe\discord-ipc-0"), 0);
+        assert_eq!(pipe_number("/tmp/custom.sock"), 0);
+    }
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Callback registry in the style of the discord-rpc C library
+//!
+//! Register the callbacks you need on an [`EventHandlers`], then pump it with
+//! `DiscordIpcClient::run_callbacks` (sync) or `EventHandlers::spawn_async` (async).
+
+use std::fmt;
+
+use crate::error::DiscordIpcError;
+use crate::ipc::protocol::{
+    ActivityJoinEvent, ActivityJoinRequestEvent, ActivitySpectateEvent, ErrorEvent, EventData,
+    ReadyEvent,
+};
+
+type Handler<T> = Option<Box<dyn FnMut(&T) + Send>>;
+
+/// Typed callbacks for Discord events
+///
+/// Every callback is optional; events without a callback are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::{DiscordIpcClient, EventHandlers};
+///
+/// let mut client = DiscordIpcClient::new("client_id")?;
+/// client.connect()?;
+/// client.subscribe("ACTIVITY_JOIN", serde_json::json!({}))?;
+///
+/// let mut handlers = EventHandlers::new()
+///     .on_ready(|ready| println!("Connected as {:?}", ready.user))
+///     .on_join(|join| println!("Joining with secret {}", join.secret))
+///     .on_disconnected(|err| eprintln!("Lost Discord: {err}"));
+///
+/// loop {
+///     client.run_callbacks(&mut handlers)?;
+///     // ... render a frame ...
+/// #   break;
+/// }
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+#[derive(Default)]
+pub struct EventHandlers {
+    ready: Handler<ReadyEvent>,
+    join: Handler<ActivityJoinEvent>,
+    spectate: Handler<ActivitySpectateEvent>,
+    join_request: Handler<ActivityJoinRequestEvent>,
+    error: Handler<ErrorEvent>,
+    disconnected: Handler<DiscordIpcError>,
+}
+
+impl fmt::Debug for EventHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventHandlers")
+            .field("ready", &self.ready.is_some())
+            .field("join", &self.join.is_some())
+            .field("spectate", &self.spectate.is_some())
+            .field("join_request", &self.join_request.is_some())
+            .field("error", &self.error.is_some())
+            .field("disconnected", &self.disconnected.is_some())
+            .finish()
+    }
+}
+
+impl EventHandlers {
+    /// Create a registry without callbacks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called after each successful handshake
+    pub fn on_ready(mut self, handler: impl FnMut(&ReadyEvent) + Send + 'static) -> Self {
+        self.ready = Some(Box::new(handler));
+        self
+    }
+
+    /// Called for `ACTIVITY_JOIN`: the user accepted an invite
+    pub fn on_join(mut self, handler: impl FnMut(&ActivityJoinEvent) + Send + 'static) -> Self {
+        self.join = Some(Box::new(handler));
+        self
+    }
+
+    /// Called for `ACTIVITY_SPECTATE`: the user chose to spectate
+    pub fn on_spectate(
+        mut self,
+        handler: impl FnMut(&ActivitySpectateEvent) + Send + 'static,
+    ) -> Self {
+        self.spectate = Some(Box::new(handler));
+        self
+    }
+
+    /// Called for `ACTIVITY_JOIN_REQUEST`: another user asked to join
+    pub fn on_join_request(
+        mut self,
+        handler: impl FnMut(&ActivityJoinRequestEvent) + Send + 'static,
+    ) -> Self {
+        self.join_request = Some(Box::new(handler));
+        self
+    }
+
+    /// Called for `ERROR` events sent by Discord
+    pub fn on_error(mut self, handler: impl FnMut(&ErrorEvent) + Send + 'static) -> Self {
+        self.error = Some(Box::new(handler));
+        self
+    }
+
+    /// Called once when the connection to Discord is lost
+    pub fn on_disconnected(
+        mut self,
+        handler: impl FnMut(&DiscordIpcError) + Send + 'static,
+    ) -> Self {
+        self.disconnected = Some(Box::new(handler));
+        self
+    }
+
+    /// Run the callback registered for `event`, if any
+    ///
+    /// Useful when consuming events yourself, e.g. from `events()`.
+    pub fn dispatch(&mut self, event: &EventData) {
+        match event {
+            EventData::Ready(ready) => call(&mut self.ready, ready),
+            EventData::ActivityJoin(join) => call(&mut self.join, join),
+            EventData::ActivitySpectate(spectate) => call(&mut self.spectate, spectate),
+            EventData::ActivityJoinRequest(request) => call(&mut self.join_request, request),
+            EventData::Error(error) => call(&mut self.error, error),
+            EventData::Unknown { .. } => {}
+        }
+    }
+
+    /// Run the disconnect callback, if any
+    pub fn dispatch_disconnected(&mut self, err: &DiscordIpcError) {
+        call(&mut self.disconnected, err);
+    }
+}
+
+fn call<T>(handler: &mut Handler<T>, value: &T) {
+    if let Some(handler) = handler.as_mut() {
+        handler(value);
+    }
+}
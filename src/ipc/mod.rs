@@ -1,10 +1,25 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // Copyright (c) 2025-2026 Sreehari Anil and project contributors
 
+pub mod broadcast;
 pub mod connection;
 pub mod discovery;
+pub mod handlers;
+pub mod pending;
+pub mod probe;
+pub mod process;
 pub mod protocol;
+pub mod rate_limit;
+pub mod state;
+pub(crate) mod watch;
 
+pub use broadcast::EventSubscription;
 pub use connection::*;
 pub use discovery::*;
+pub use handlers::EventHandlers;
+pub use pending::PendingStats;
+pub use probe::ProbedPipe;
+pub use process::{resolve_pid, PidInfo, PidSource, Sandbox};
 pub use protocol::*;
+pub use rate_limit::RateLimitConfig;
+pub use state::{ConnectionState, ConnectionStateReceiver};
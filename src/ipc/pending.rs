@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Bounded queue of messages read while the client was waiting for something else
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::debug_println;
+use crate::ipc::protocol::{IpcConfig, Opcode};
+
+/// Counters for the unread-message queue of a client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PendingStats {
+    /// Messages currently queued
+    pub queued: usize,
+    /// Events dropped because the queue was full
+    pub dropped_overflow: u64,
+    /// Messages dropped because they were older than the maximum age
+    pub dropped_expired: u64,
+}
+
+#[derive(Debug)]
+struct PendingMessage {
+    opcode: Opcode,
+    payload: Value,
+    received_at: Instant,
+}
+
+impl PendingMessage {
+    /// Unsolicited events may be dropped; replies carry the nonce of a request
+    fn is_event(&self) -> bool {
+        self.payload.get("evt").is_some_and(Value::is_string)
+            && !self.payload.get("nonce").is_some_and(Value::is_string)
+    }
+}
+
+/// Messages that arrived while the client was waiting for something else
+#[derive(Debug)]
+pub(crate) struct PendingQueue {
+    messages: VecDeque<PendingMessage>,
+    capacity: Option<usize>,
+    max_age: Option<Duration>,
+    dropped_overflow: u64,
+    dropped_expired: u64,
+}
+
+impl PendingQueue {
+    pub(crate) fn new(config: &IpcConfig) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            capacity: config.pending_capacity,
+            max_age: config.pending_max_age,
+            dropped_overflow: 0,
+            dropped_expired: 0,
+        }
+    }
+
+    /// Queue a message, evicting expired events and the oldest event when full
+    pub(crate) fn push(&mut self, opcode: Opcode, payload: Value) {
+        self.expire();
+        let message = PendingMessage {
+            opcode,
+            payload,
+            received_at: Instant::now(),
+        };
+
+        if let Some(capacity) = self.capacity {
+            if self.messages.len() >= capacity {
+                match self.messages.iter().position(PendingMessage::is_event) {
+                    Some(index) => {
+                        self.messages.remove(index);
+                    }
+                    // Only replies are queued; they are never dropped, so drop the newcomer
+                    None if message.is_event() => {
+                        self.dropped_overflow += 1;
+                        debug_println!("Pending queue full of replies, dropping event");
+                        return;
+                    }
+                    None => {}
+                }
+                if self.messages.len() < capacity {
+                    self.dropped_overflow += 1;
+                    debug_println!("Pending queue full, dropped the oldest event");
+                }
+            }
+        }
+
+        self.messages.push_back(message);
+    }
+
+    pub(crate) fn pop_front(&mut self) -> Option<(Opcode, Value)> {
+        self.expire();
+        self.messages
+            .pop_front()
+            .map(|message| (message.opcode, message.payload))
+    }
+
+    /// Remove and return the first message matching `predicate`
+    pub(crate) fn take(&mut self, predicate: impl Fn(&Value) -> bool) -> Option<(Opcode, Value)> {
+        self.expire();
+        let index = self
+            .messages
+            .iter()
+            .position(|message| predicate(&message.payload))?;
+        self.messages
+            .remove(index)
+            .map(|message| (message.opcode, message.payload))
+    }
+
+    /// Drop every message older than `max_age`, replies included
+    pub(crate) fn cleanup(&mut self, max_age: Duration) -> usize {
+        let original_len = self.messages.len();
+        if max_age.is_zero() {
+            self.messages.clear();
+        } else {
+            let now = Instant::now();
+            self.messages
+                .retain(|message| now.saturating_duration_since(message.received_at) <= max_age);
+        }
+        let dropped = original_len - self.messages.len();
+        self.dropped_expired += dropped as u64;
+        dropped
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    pub(crate) fn stats(&mut self) -> PendingStats {
+        self.expire();
+        PendingStats {
+            queued: self.messages.len(),
+            dropped_overflow: self.dropped_overflow,
+            dropped_expired: self.dropped_expired,
+        }
+    }
+
+    fn expire(&mut self) {
+        let Some(max_age) = self.max_age else {
+            return;
+        };
+        let now = Instant::now();
+        let original_len = self.messages.len();
+        self.messages.retain(|message| {
+            !message.is_event() || now.saturating_duration_since(message.received_at) <= max_age
+        });
+        self.dropped_expired += (original_len - self.messages.len()) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(secret: &str) -> Value {
+        json!({ "cmd": "DISPATCH", "evt": "ACTIVITY_JOIN", "data": { "secret": secret }, "nonce": null })
+    }
+
+    fn reply(nonce: &str) -> Value {
+        json!({ "cmd": "SET_ACTIVITY", "evt": null, "data": {}, "nonce": nonce })
+    }
+
+    #[test]
+    fn overflow_drops_oldest_events_but_never_replies() {
+        let mut queue = PendingQueue::new(&IpcConfig::default().with_pending_capacity(3));
+        queue.push(Opcode::Frame, reply("r1"));
+        queue.push(Opcode::Frame, event("a"));
+        queue.push(Opcode::Frame, event("b"));
+        queue.push(Opcode::Frame, event("c"));
+        queue.push(Opcode::Frame, reply("r2"));
+        queue.push(Opcode::Frame, reply("r3"));
+
+        let stats = queue.stats();
+        assert_eq!(stats.dropped_overflow, 3);
+        assert_eq!(stats.queued, 3);
+        assert!(queue.take(|value| value["nonce"] == "r1").is_some());
+        assert!(queue.take(|value| value["nonce"] == "r3").is_some());
+        let (_, last) = queue.pop_front().unwrap();
+        assert_eq!(last["nonce"], "r2");
+    }
+
+    #[test]
+    fn events_expire_after_max_age() {
+        let mut queue = PendingQueue::new(
+            &IpcConfig::default().with_pending_max_age(Duration::from_millis(20)),
+        );
+        queue.push(Opcode::Frame, event("old"));
+        queue.push(Opcode::Frame, reply("kept"));
+        std::thread::sleep(Duration::from_millis(40));
+        queue.push(Opcode::Frame, event("new"));
+
+        let stats = queue.stats();
+        assert_eq!(stats.dropped_expired, 1);
+        assert_eq!(stats.queued, 2);
+        assert_eq!(queue.pop_front().unwrap().1["nonce"], "kept");
+    }
+}
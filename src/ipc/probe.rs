@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Handshaking with every discovered socket to see who is on the other end
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::connection::{IpcConnection, PipeConfig, ReleaseChannel};
+use crate::ipc::discovery;
+use crate::ipc::protocol::{
+    validate_handshake_response, HandshakePayload, IpcConfig, Opcode, ReadyEvent,
+};
+
+/// How long a probe waits for READY when `IpcConfig::handshake_timeout` is unset
+pub(crate) const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A socket that answered a probe handshake
+///
+/// Returned by [`IpcConnection::probe_pipes`] and the async clients' `probe_pipes`.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ProbedPipe {
+    /// The pipe number (0-9)
+    pub pipe_number: u8,
+    /// The full path to the pipe
+    pub path: String,
+    /// The READY payload Discord answered with
+    pub ready: ReadyEvent,
+    /// Time from sending the handshake to receiving READY
+    pub latency: Duration,
+}
+
+impl ProbedPipe {
+    pub(crate) fn new(path: String, ready: ReadyEvent, latency: Duration) -> Self {
+        let pipe_number = discovery::pipe_number(&path);
+        Self {
+            pipe_number,
+            path,
+            ready,
+            latency,
+        }
+    }
+
+    /// ID of the user logged in to this Discord
+    pub fn user_id(&self) -> Option<&str> {
+        self.ready.user.as_ref()?.id.as_deref()
+    }
+
+    /// Username of the user logged in to this Discord
+    pub fn username(&self) -> Option<&str> {
+        self.ready.user.as_ref()?.username.as_deref()
+    }
+
+    /// The release channel of this Discord, if it can be told
+    pub fn release_channel(&self) -> Option<ReleaseChannel> {
+        self.ready.release_channel()
+    }
+
+    /// A [`PipeConfig`] that connects to exactly this socket
+    pub fn pipe_config(&self) -> PipeConfig {
+        PipeConfig::CustomPath(self.path.clone())
+    }
+}
+
+/// The handshake timeout a probe uses
+pub(crate) fn probe_timeout(ipc_config: &IpcConfig) -> Duration {
+    ipc_config
+        .handshake_timeout
+        .unwrap_or(DEFAULT_PROBE_TIMEOUT)
+}
+
+impl IpcConnection {
+    /// Handshake with every discovered socket at once and report who answered
+    ///
+    /// Unlike [`IpcConnection::discover_pipes`], which only checks that a socket
+    /// accepts connections, each socket gets a full handshake as `client_id`, so the
+    /// result says which account and release channel is behind it. Sockets that fail
+    /// the handshake or do not answer within two seconds are left out; the rest are
+    /// returned in discovery order. Each probe connection is closed again, so nothing
+    /// shows up in Discord.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use presenceforge::ipc::IpcConnection;
+    ///
+    /// for pipe in IpcConnection::probe_pipes("client_id") {
+    ///     println!(
+    ///         "{}: {} ({:?}) in {:?}",
+    ///         pipe.path,
+    ///         pipe.username().unwrap_or("unknown"),
+    ///         pipe.release_channel(),
+    ///         pipe.latency
+    ///     );
+    /// }
+    /// ```
+    pub fn probe_pipes(client_id: &str) -> Vec<ProbedPipe> {
+        Self::probe_pipes_with_config(client_id, &IpcConfig::default())
+    }
+
+    /// Like [`IpcConnection::probe_pipes`], scanning `ipc_config.max_sockets` sockets
+    /// and waiting `ipc_config.handshake_timeout` (if set) for each answer
+    pub fn probe_pipes_with_config(client_id: &str, ipc_config: &IpcConfig) -> Vec<ProbedPipe> {
+        let connections: Vec<_> = discovery::candidate_paths(ipc_config)
+            .into_iter()
+            .filter_map(|path| {
+                let connection = IpcConnection::new_with_configs(
+                    Some(PipeConfig::CustomPath(path.clone())),
+                    ipc_config.clone(),
+                )
+                .ok()?;
+                Some((path, connection))
+            })
+            .collect();
+
+        thread::scope(|scope| {
+            let probes: Vec<_> = connections
+                .into_iter()
+                .map(|(path, mut connection)| {
+                    scope.spawn(
+                        move || match probe(&mut connection, client_id, ipc_config) {
+                            Ok((ready, latency)) => Some(ProbedPipe::new(path, ready, latency)),
+                            Err(err) => {
+                                debug_println!("Probe of {} failed: {}", path, err);
+                                None
+                            }
+                        },
+                    )
+                })
+                .collect();
+
+            probes
+                .into_iter()
+                .filter_map(|probe| probe.join().ok().flatten())
+                .collect()
+        })
+    }
+}
+
+fn probe(
+    connection: &mut IpcConnection,
+    client_id: &str,
+    ipc_config: &IpcConfig,
+) -> Result<(ReadyEvent, Duration)> {
+    let payload = serde_json::to_value(HandshakePayload {
+        v: ipc_config.ipc_version,
+        client_id: client_id.to_string(),
+    })
+    .map_err(DiscordIpcError::SerializationFailed)?;
+
+    // Both timeouts are real deadlines on every platform (Windows cancels the pipe
+    // I/O), so a silent socket cannot hold up the scope in `probe_pipes_with_config`
+    let timeout = probe_timeout(ipc_config);
+    connection.set_read_timeout(Some(timeout))?;
+    connection.set_write_timeout(Some(timeout))?;
+    let started = Instant::now();
+    connection.send(Opcode::Handshake, &payload)?;
+    let (opcode, response) = connection.recv()?;
+    let ready = validate_handshake_response(opcode, &response)?;
+    Ok((ready, started.elapsed()))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::MockDiscord;
+
+    #[test]
+    fn probed_pipes_carry_the_ready_user() {
+        let mock = MockDiscord::start();
+        let path = mock.path();
+        let mut connection =
+            IpcConnection::new_with_configs(Some(mock.pipe_config()), IpcConfig::default())
+                .unwrap();
+
+        let (ready, latency) = probe(&mut connection, "client", &IpcConfig::default()).unwrap();
+        let pipe = ProbedPipe::new(path.clone(), ready, latency);
+        assert_eq!(pipe.user_id(), Some("1"));
+        assert_eq!(pipe.username(), Some("mock"));
+        assert_eq!(pipe.release_channel(), None);
+        assert!(latency < DEFAULT_PROBE_TIMEOUT);
+        assert!(matches!(pipe.pipe_config(), PipeConfig::CustomPath(p) if p == path));
+    }
+
+    #[test]
+    fn silent_sockets_time_out() {
+        let mock = MockDiscord::start();
+        mock.set_silent(true);
+        let ipc_config = IpcConfig::default().with_handshake_timeout(Duration::from_millis(100));
+        let mut connection =
+            IpcConnection::new_with_configs(Some(mock.pipe_config()), ipc_config.clone()).unwrap();
+
+        let started = Instant::now();
+        assert!(probe(&mut connection, "client", &ipc_config).is_err());
+        assert!(started.elapsed() < DEFAULT_PROBE_TIMEOUT);
+    }
+
+    #[test]
+    fn pipe_number_is_read_from_the_path() {
+        let ready: ReadyEvent = serde_json::from_value(serde_json::json!({})).unwrap();
+        let pipe = ProbedPipe::new("/run/user/1000/discord-ipc-7".into(), ready, Duration::ZERO);
+        assert_eq!(pipe.pipe_number, 7);
+    }
+}
@@ -0,0 +1,415 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Process IDs sent with activities and liveness checks for watched processes
+//!
+//! Inside a Flatpak or container, `std::process::id()` is a PID in a separate
+//! namespace that means nothing to Discord on the host. Before the PID of this
+//! process is sent it is translated to the outermost PID listed under `NSpid` in
+//! `/proc/self/status`; [`resolve_pid`] reports which PID that was and how it was found.
+
+use std::fmt;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::debug_println;
+
+/// A sandbox or container the process appears to run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Sandbox {
+    /// Flatpak (`/.flatpak-info` exists)
+    Flatpak,
+    /// Snap (`SNAP` is set); snaps share the host PID namespace
+    Snap,
+    /// Docker (`/.dockerenv` exists)
+    Docker,
+    /// Podman or another OCI runtime (`/run/.containerenv` exists)
+    Podman,
+}
+
+impl fmt::Display for Sandbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Flatpak => "Flatpak",
+            Self::Snap => "Snap",
+            Self::Docker => "Docker",
+            Self::Podman => "Podman",
+        })
+    }
+}
+
+/// How the PID sent to Discord was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PidSource {
+    /// The process is not in a separate PID namespace; its own PID was sent
+    Local,
+    /// Translated from a namespaced PID using `NSpid`
+    Translated,
+    /// The process runs in a sandbox that hides the host PID; its own PID was sent
+    ///
+    /// Discord cannot tie the activity to the process, so it is not cleared when the
+    /// process dies. Clear it explicitly or share the host PID namespace.
+    Unresolved,
+}
+
+/// Which PID is sent to Discord for a process, from [`resolve_pid`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PidInfo {
+    /// The PID as seen from inside this process's namespace
+    pub local: u32,
+    /// The PID sent with `SET_ACTIVITY`
+    pub sent: u32,
+    /// How `sent` was chosen
+    pub source: PidSource,
+    /// The detected sandbox, if any
+    pub sandbox: Option<Sandbox>,
+}
+
+impl fmt::Display for PidInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.source {
+            PidSource::Local => write!(f, "PID {}", self.sent)?,
+            PidSource::Translated => write!(
+                f,
+                "host PID {} (PID {} in the sandbox)",
+                self.sent, self.local
+            )?,
+            PidSource::Unresolved => write!(f, "PID {} (host PID unknown)", self.sent)?,
+        }
+        if let Some(sandbox) = self.sandbox {
+            write!(f, " inside {sandbox}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Work out which PID to send to Discord for `pid`
+///
+/// `pid` is interpreted in this process's PID namespace. Only the PID of this
+/// process can be translated; any other PID inside a namespace is reported as
+/// [`PidSource::Unresolved`]. Only Linux has PID namespaces; elsewhere `pid` is
+/// returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use presenceforge::{resolve_pid, PidSource};
+///
+/// let info = resolve_pid(std::process::id());
+/// if info.source == PidSource::Unresolved {
+///     eprintln!("Discord will not see {info}; presence is not cleared if we crash");
+/// }
+/// ```
+pub fn resolve_pid(pid: u32) -> PidInfo {
+    let (own_pids, sandbox) = own_process();
+    resolve(pid, std::process::id(), own_pids.as_deref(), *sandbox)
+}
+
+/// Our `NSpid` entries and sandbox, looked up once: a process keeps both for life
+fn own_process() -> &'static (Option<Vec<u32>>, Option<Sandbox>) {
+    static OWN_PROCESS: OnceLock<(Option<Vec<u32>>, Option<Sandbox>)> = OnceLock::new();
+    OWN_PROCESS.get_or_init(|| (own_namespaced_pids(), detect_sandbox()))
+}
+
+/// Decide which PID to send, given the `NSpid` entries of the process `own_pid`
+fn resolve(pid: u32, own_pid: u32, own_pids: Option<&[u32]>, sandbox: Option<Sandbox>) -> PidInfo {
+    let own_pids = own_pids.unwrap_or_default();
+    // The last entry is the PID in our own namespace; anything else means /proc
+    // belongs to another namespace and its entries cannot be trusted
+    let in_namespace = own_pids.len() > 1 && own_pids.last() == Some(&own_pid);
+    let (sent, source) = if in_namespace && pid == own_pid {
+        (own_pids[0], PidSource::Translated)
+    } else if in_namespace
+        || matches!(
+            sandbox,
+            Some(Sandbox::Flatpak | Sandbox::Docker | Sandbox::Podman)
+        )
+    {
+        (pid, PidSource::Unresolved)
+    } else {
+        (pid, PidSource::Local)
+    };
+    PidInfo {
+        local: pid,
+        sent,
+        source,
+        sandbox,
+    }
+}
+
+/// The PID to put in a `SET_ACTIVITY` request for `pid`
+pub(crate) fn host_pid(pid: u32) -> u32 {
+    let info = resolve_pid(pid);
+    if info.source != PidSource::Local {
+        debug_println!("Sending {}", info);
+    }
+    info.sent
+}
+
+/// The `NSpid` line of `/proc/self/status`, outermost namespace first
+#[cfg(target_os = "linux")]
+fn own_namespaced_pids() -> Option<Vec<u32>> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_nspid(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn own_namespaced_pids() -> Option<Vec<u32>> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nspid(status: &str) -> Option<Vec<u32>> {
+    let line = status
+        .lines()
+        .find_map(|line| line.strip_prefix("NSpid:"))?;
+    line.split_whitespace()
+        .map(|pid| pid.parse().ok())
+        .collect::<Option<Vec<u32>>>()
+        .filter(|pids| !pids.is_empty())
+}
+
+fn detect_sandbox() -> Option<Sandbox> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let exists = |path: &str| std::path::Path::new(path).exists();
+    if exists("/.flatpak-info") {
+        Some(Sandbox::Flatpak)
+    } else if std::env::var_os("SNAP").is_some() {
+        Some(Sandbox::Snap)
+    } else if exists("/.dockerenv") {
+        Some(Sandbox::Docker)
+    } else if exists("/run/.containerenv") {
+        Some(Sandbox::Podman)
+    } else {
+        None
+    }
+}
+
+/// How often a watched process is checked when it cannot be waited on directly
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Returns `true` while `pid` refers to a running process
+///
+/// A zombie (exited but not yet reaped by its parent) counts as exited on Linux.
+pub(crate) fn is_alive(pid: u32) -> bool {
+    imp::is_alive(pid)
+}
+
+/// Block until `pid` exits or a message arrives on `stop`
+///
+/// Returns `true` if the process exited.
+pub(crate) fn wait_for_exit(pid: u32, stop: &Receiver<()>) -> bool {
+    #[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+    if let Some(exited) = pidfd::wait_for_exit(pid, stop) {
+        return exited;
+    }
+
+    loop {
+        if !is_alive(pid) {
+            return true;
+        }
+        match stop.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => {}
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return false,
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", any(target_env = "gnu", target_env = "musl")))]
+mod pidfd {
+    use std::sync::mpsc::{Receiver, TryRecvError};
+
+    use super::POLL_INTERVAL;
+    use crate::debug_println;
+
+    /// Wait on a pidfd; `None` if the kernel does not support them (before 5.3)
+    pub(super) fn wait_for_exit(pid: u32, stop: &Receiver<()>) -> Option<bool> {
+        let pid = libc::pid_t::try_from(pid).ok()?;
+        // SAFETY: pidfd_open takes a pid and flags and returns a new descriptor or -1
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+        if fd < 0 {
+            let err = std::io::Error::last_os_error();
+            return match err.raw_os_error() {
+                // The process is already gone
+                Some(libc::ESRCH) => Some(true),
+                _ => {
+                    debug_println!("pidfd_open unavailable ({}), polling /proc", err);
+                    None
+                }
+            };
+        }
+        let fd = fd as libc::c_int;
+
+        let exited = loop {
+            let mut poll = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: `poll` points to one valid pollfd for the duration of the call
+            let ready =
+                unsafe { libc::poll(&mut poll, 1, POLL_INTERVAL.as_millis() as libc::c_int) };
+            if ready > 0 {
+                break true;
+            }
+            if ready < 0
+                && std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted
+            {
+                break !super::is_alive(pid as u32);
+            }
+            match stop.try_recv() {
+                Err(TryRecvError::Empty) => {}
+                Ok(()) | Err(TryRecvError::Disconnected) => break false,
+            }
+        };
+
+        // SAFETY: `fd` was returned by pidfd_open above and is closed exactly once
+        unsafe { libc::close(fd) };
+        Some(exited)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    pub(super) fn is_alive(pid: u32) -> bool {
+        let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat")) else {
+            return false;
+        };
+        // The state follows the parenthesised command name, which may itself contain ')'
+        match stat
+            .rfind(')')
+            .and_then(|end| stat[end + 1..].split_whitespace().next())
+        {
+            Some(state) => !matches!(state, "Z" | "X" | "x"),
+            None => true,
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+mod imp {
+    pub(super) fn is_alive(pid: u32) -> bool {
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return false;
+        };
+        // SAFETY: signal 0 only checks that the process exists and may be signalled
+        if unsafe { libc::kill(pid, 0) } == 0 {
+            return true;
+        }
+        std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::ffi::c_void;
+
+    const SYNCHRONIZE: u32 = 0x0010_0000;
+    const WAIT_TIMEOUT: u32 = 0x0000_0102;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+        fn WaitForSingleObject(handle: *mut c_void, milliseconds: u32) -> u32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    pub(super) fn is_alive(pid: u32) -> bool {
+        // SAFETY: the handle is checked for null and closed before returning
+        unsafe {
+            let handle = OpenProcess(SYNCHRONIZE, 0, pid);
+            if handle.is_null() {
+                return false;
+            }
+            let alive = WaitForSingleObject(handle, 0) == WAIT_TIMEOUT;
+            CloseHandle(handle);
+            alive
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn waits_for_a_child_to_exit() {
+        let mut child = std::process::Command::new(if cfg!(windows) { "cmd" } else { "sleep" })
+            .args(if cfg!(windows) {
+                &["/C", "ping -n 2 127.0.0.1 >NUL"][..]
+            } else {
+                &["0.3"][..]
+            })
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        assert!(is_alive(pid));
+
+        let (_stop, stop_rx) = mpsc::channel();
+        let waiter = std::thread::spawn(move || wait_for_exit(pid, &stop_rx));
+        child.wait().unwrap();
+        assert!(waiter.join().unwrap());
+        assert!(!is_alive(pid));
+    }
+
+    #[test]
+    fn nspid_lists_the_outermost_pid_first() {
+        let status = "Name:\tgame\nTgid:\t7\nNgid:\t0\nPid:\t7\nPPid:\t1\nNSpid:\t48213\t7\n";
+        assert_eq!(parse_nspid(status), Some(vec![48213, 7]));
+        assert_eq!(parse_nspid("Pid:\t7\nNSpid:\t7\n"), Some(vec![7]));
+        assert_eq!(parse_nspid("Pid:\t7\n"), None);
+    }
+
+    #[test]
+    fn only_the_own_pid_is_translated() {
+        let status = "Pid:\t7\nNSpid:\t48213\t7\n";
+        let info = resolve(7, 7, parse_nspid(status).as_deref(), Some(Sandbox::Flatpak));
+        assert_eq!((info.sent, info.source), (48213, PidSource::Translated));
+
+        let info = resolve(9, 7, parse_nspid(status).as_deref(), None);
+        assert_eq!((info.sent, info.source), (9, PidSource::Unresolved));
+
+        // A /proc mounted from another namespace does not describe this process
+        let info = resolve(7, 7, parse_nspid("NSpid:\t7\t1\n").as_deref(), None);
+        assert_eq!((info.sent, info.source), (7, PidSource::Local));
+
+        let info = resolve(
+            7,
+            7,
+            parse_nspid("NSpid:\t7\n").as_deref(),
+            Some(Sandbox::Docker),
+        );
+        assert_eq!((info.sent, info.source), (7, PidSource::Unresolved));
+
+        let info = resolve(
+            9,
+            7,
+            parse_nspid("NSpid:\t7\n").as_deref(),
+            Some(Sandbox::Snap),
+        );
+        assert_eq!((info.sent, info.source), (9, PidSource::Local));
+    }
+
+    #[test]
+    fn own_pid_resolves_without_a_namespace() {
+        let info = resolve_pid(std::process::id());
+        assert_eq!(info.local, std::process::id());
+        if info.source == PidSource::Local {
+            assert_eq!(info.sent, info.local);
+        }
+    }
+
+    #[test]
+    fn stop_ends_the_wait_early() {
+        let (stop, stop_rx) = mpsc::channel();
+        stop.send(()).unwrap();
+        assert!(!wait_for_exit(std::process::id(), &stop_rx));
+    }
+}
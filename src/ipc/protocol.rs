@@ -1,12 +1,15 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // Copyright (c) 2025-2026 Sreehari Anil and project contributors
 
+use crate::activity::AppliedActivity;
 use crate::error::{
     DiscordIpcError, HandshakeFailureKind, InvalidResponseKind, ProtocolContext,
     ProtocolViolationKind,
 };
+use crate::ipc::connection::ReleaseChannel;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::time::Duration;
 
 fn empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
@@ -17,7 +20,7 @@ where
 }
 
 /// Partial user object from Discord READY event payload.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PartialUser {
     #[serde(default, deserialize_with = "empty_string_as_none")]
     pub id: Option<String>,
@@ -30,10 +33,34 @@ pub struct PartialUser {
     pub bot: Option<bool>,
 }
 
+/// The `config` object of a READY payload, describing the Discord build
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadyConfig {
+    /// CDN host, e.g. `cdn.discordapp.com`
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub cdn_host: Option<String>,
+    /// API endpoint, e.g. `//discord.com/api` or `//canary.discord.com/api`
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub api_endpoint: Option<String>,
+    /// Build environment, e.g. `production`
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub environment: Option<String>,
+}
+
 /// READY event payload returned by Discord IPC after handshake.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReadyEvent {
     pub user: Option<PartialUser>,
+    /// Which Discord build answered; absent for some third-party clients
+    #[serde(default)]
+    pub config: Option<ReadyConfig>,
+}
+
+impl ReadyEvent {
+    /// The release channel of the Discord build that answered, if it can be told
+    pub fn release_channel(&self) -> Option<ReleaseChannel> {
+        self.config.as_ref().and_then(ReleaseChannel::from_config)
+    }
 }
 
 /// Payload for ACTIVITY_JOIN event
@@ -72,6 +99,32 @@ pub enum EventData {
     Unknown { name: String, data: Option<Value> },
 }
 
+/// The kind of an [`EventData`], used to filter event streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EventKind {
+    Ready,
+    ActivityJoin,
+    ActivitySpectate,
+    ActivityJoinRequest,
+    Error,
+    Unknown,
+}
+
+impl EventData {
+    /// The kind of this event
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::Ready(_) => EventKind::Ready,
+            Self::ActivityJoin(_) => EventKind::ActivityJoin,
+            Self::ActivitySpectate(_) => EventKind::ActivitySpectate,
+            Self::ActivityJoinRequest(_) => EventKind::ActivityJoinRequest,
+            Self::Error(_) => EventKind::Error,
+            Self::Unknown { .. } => EventKind::Unknown,
+        }
+    }
+}
+
 /// Discord IPC Opcodes
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -309,6 +362,22 @@ pub fn validate_handshake_response(
     response.parse_ready_handshake()
 }
 
+/// Parse the activity Discord stored from a SET_ACTIVITY response payload.
+pub fn parse_applied_activity(payload: &Value) -> Result<AppliedActivity, DiscordIpcError> {
+    let data = payload
+        .get("data")
+        .filter(|data| !data.is_null())
+        .cloned()
+        .ok_or_else(|| {
+            DiscordIpcError::invalid_response(
+                InvalidResponseKind::MissingResponseData,
+                "SET_ACTIVITY response is missing data".to_string(),
+            )
+        })?;
+
+    serde_json::from_value::<AppliedActivity>(data).map_err(DiscordIpcError::DeserializationFailed)
+}
+
 /// Constants and configuration for Discord IPC protocol
 pub mod constants {
     /// Discord IPC protocol version
@@ -364,6 +433,12 @@ pub mod constants {
     /// - 4 bytes: Opcode (u32, little-endian)
     /// - 4 bytes: Payload length (u32, little-endian)
     pub const IPC_HEADER_SIZE: usize = 8;
+
+    /// Default number of unread messages a client keeps queued
+    ///
+    /// Past this, the oldest unread events are dropped. Replies to requests are
+    /// never dropped.
+    pub const DEFAULT_PENDING_CAPACITY: usize = 256;
 }
 
 /// Configuration for Discord IPC protocol behavior
@@ -371,6 +446,7 @@ pub mod constants {
 /// Allows customization of protocol parameters for different Discord setups
 /// or special use cases (testing, non-standard installations, etc.)
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct IpcConfig {
     /// Maximum number of socket instances to scan during auto-discovery
     pub max_sockets: u8,
@@ -383,6 +459,35 @@ pub struct IpcConfig {
 
     /// IPC protocol version to use in handshake
     pub ipc_version: u32,
+
+    /// Client-side `SET_ACTIVITY` throttling; disabled when `None`
+    pub rate_limit: Option<crate::ipc::RateLimitConfig>,
+
+    /// How long to wait for Discord to answer the handshake; waits forever when `None`
+    pub handshake_timeout: Option<Duration>,
+
+    /// How long to wait for the reply to a request; waits forever when `None`
+    pub read_timeout: Option<Duration>,
+
+    /// How long a single write may block; waits forever when `None`
+    pub write_timeout: Option<Duration>,
+
+    /// Unread messages a client keeps before dropping the oldest events; unbounded when `None`
+    pub pending_capacity: Option<usize>,
+
+    /// Unread events older than this are dropped; kept until read when `None`
+    pub pending_max_age: Option<Duration>,
+
+    /// Process the activity is shown for; the current process when `None`
+    pub pid: Option<u32>,
+
+    /// Directories searched for sockets before the built-in ones (Unix only)
+    pub search_dirs: Vec<String>,
+
+    /// Fork and sandbox locations searched under the runtime directory (Unix only)
+    ///
+    /// Defaults to [`KNOWN_LOCATIONS`](crate::ipc::discovery::KNOWN_LOCATIONS).
+    pub known_locations: Vec<crate::ipc::discovery::KnownLocation>,
 }
 
 impl Default for IpcConfig {
@@ -392,6 +497,15 @@ impl Default for IpcConfig {
             retry_interval_ms: constants::DEFAULT_RETRY_INTERVAL_MS,
             max_payload_size: constants::MAX_PAYLOAD_SIZE,
             ipc_version: constants::IPC_VERSION,
+            rate_limit: None,
+            handshake_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            pending_capacity: Some(constants::DEFAULT_PENDING_CAPACITY),
+            pending_max_age: None,
+            pid: None,
+            search_dirs: Vec::new(),
+            known_locations: crate::ipc::discovery::KNOWN_LOCATIONS.to_vec(),
         }
     }
 }
@@ -444,6 +558,86 @@ impl IpcConfig {
         self
     }
 
+    /// Throttle activity updates on the client
+    ///
+    /// Updates beyond the budget are coalesced: the most recent activity is held back
+    /// and sent once the window has room (see `flush_pending_update` on the clients).
+    /// Identical activities are not resent and `clear_activity` is never held back.
+    pub fn with_rate_limit(mut self, rate_limit: crate::ipc::RateLimitConfig) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Give up on the handshake if Discord does not answer within `timeout`
+    ///
+    /// Used by the synchronous client. Timeouts are applied as socket timeouts on Unix;
+    /// on Windows a helper thread per connection cancels the pipe read or write that runs too long.
+    pub fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = Some(timeout);
+        self
+    }
+
+    /// Give up on a request if Discord does not reply within `timeout`
+    ///
+    /// A timed-out request fails with `DiscordIpcError::RequestTimeout` and leaves the
+    /// connection unusable until it is reconnected. Does not apply to `next_event`.
+    /// Behind an `AsyncClientHandle` only the request fails; the dispatcher keeps
+    /// serving the others.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Give up on a write that blocks for longer than `timeout`
+    pub fn with_write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep at most `capacity` unread messages queued on the client
+    ///
+    /// When the queue is full the oldest unread event is dropped. Replies to requests
+    /// are never dropped, so the queue may briefly exceed `capacity` with replies alone.
+    /// Dropped events are counted in `pending_stats()` on the clients.
+    pub fn with_pending_capacity(mut self, capacity: usize) -> Self {
+        self.pending_capacity = Some(capacity);
+        self
+    }
+
+    /// Drop unread events once they have been queued for longer than `max_age`
+    pub fn with_pending_max_age(mut self, max_age: Duration) -> Self {
+        self.pending_max_age = Some(max_age);
+        self
+    }
+
+    /// Set activities on behalf of process `pid` instead of the current process
+    ///
+    /// Useful for launchers and wrappers whose presence belongs to a game they started.
+    /// Individual calls can still pick a PID with `set_activity_for_pid`.
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Also look for sockets in `dir`, before the built-in locations (Unix only)
+    ///
+    /// Directories are searched in the order they were added.
+    pub fn with_search_dir(mut self, dir: impl Into<String>) -> Self {
+        self.search_dirs.push(dir.into());
+        self
+    }
+
+    /// Also look for sockets in a fork or sandbox location (Unix only)
+    pub fn with_known_location(mut self, location: crate::ipc::discovery::KnownLocation) -> Self {
+        self.known_locations.push(location);
+        self
+    }
+
+    /// The PID sent with `SET_ACTIVITY`
+    pub(crate) fn activity_pid(&self) -> u32 {
+        self.pid.unwrap_or_else(std::process::id)
+    }
+
     /// Validate the configuration
     ///
     /// Returns true if all parameters are within acceptable ranges
@@ -466,6 +660,36 @@ impl IpcConfig {
         if self.max_payload_size > 100 * 1024 * 1024 {
             return Err("max_payload_size too large (maximum 100 MB)");
         }
+        if [
+            self.handshake_timeout,
+            self.read_timeout,
+            self.write_timeout,
+        ]
+        .iter()
+        .any(|timeout| timeout.is_some_and(|timeout| timeout.is_zero()))
+        {
+            return Err("timeouts must be greater than 0");
+        }
+        if self.pending_capacity == Some(0) {
+            return Err("pending_capacity must be greater than 0");
+        }
+        if self
+            .pending_max_age
+            .is_some_and(|max_age| max_age.is_zero())
+        {
+            return Err("pending_max_age must be greater than 0");
+        }
+        if self.pid == Some(0) {
+            return Err("pid must be greater than 0");
+        }
+        if let Some(rate_limit) = &self.rate_limit {
+            if rate_limit.max_updates == 0 {
+                return Err("rate_limit.max_updates must be greater than 0");
+            }
+            if rate_limit.window.is_zero() {
+                return Err("rate_limit.window must be greater than 0");
+            }
+        }
         Ok(())
     }
 }
@@ -500,6 +724,12 @@ mod tests {
 
         let huge_payload = IpcConfig::default().with_max_payload_size(200 * 1024 * 1024);
         assert!(huge_payload.validate().is_err());
+
+        let no_window = IpcConfig::default().with_rate_limit(crate::ipc::RateLimitConfig {
+            max_updates: 5,
+            window: std::time::Duration::ZERO,
+        });
+        assert!(no_window.validate().is_err());
     }
 
     #[test]
@@ -577,6 +807,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_applied_activity_requires_data() {
+        let payload = serde_json::json!({
+            "cmd": "SET_ACTIVITY",
+            "data": { "application_id": "1234", "state": "Exploring" },
+            "nonce": "set-activity-1"
+        });
+        let applied = parse_applied_activity(&payload).unwrap();
+        assert_eq!(applied.state.as_deref(), Some("Exploring"));
+
+        let empty = serde_json::json!({ "cmd": "SET_ACTIVITY", "data": null });
+        assert!(matches!(
+            parse_applied_activity(&empty).unwrap_err(),
+            DiscordIpcError::InvalidResponse {
+                kind: InvalidResponseKind::MissingResponseData,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn validate_handshake_response_rejects_non_ready_events() {
         let payload = serde_json::json!({
@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Client-side throttling for `SET_ACTIVITY`
+//!
+//! Discord accepts roughly five activity updates every twenty seconds and silently
+//! drops the rest. The limiter keeps clients under that budget by holding back the
+//! latest activity until the window has room again, so the final state is never lost.
+
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::activity::Activity;
+
+/// Update budget enforced by the client-side rate limiter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Maximum number of activity updates within `window`
+    pub max_updates: u32,
+
+    /// Length of the sliding window
+    pub window: Duration,
+}
+
+impl Default for RateLimitConfig {
+    /// Discord's limit: 5 updates per 20 seconds
+    fn default() -> Self {
+        Self {
+            max_updates: 5,
+            window: Duration::from_secs(20),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Create a custom update budget
+    pub fn new(max_updates: u32, window: Duration) -> Self {
+        Self {
+            max_updates: max_updates.max(1),
+            window,
+        }
+    }
+}
+
+/// What the client should do with a requested activity
+#[derive(Debug)]
+pub(crate) enum UpdateDecision {
+    /// Send it now
+    Send,
+    /// Discord already shows this activity
+    Unchanged,
+    /// Held back until the window has room; replaces any earlier pending update
+    Deferred,
+}
+
+/// An update held back until the window has room
+#[derive(Debug, Clone)]
+pub(crate) struct PendingUpdate {
+    /// The process the activity is shown for
+    pub(crate) pid: u32,
+    /// The activity to set, or `None` to clear it
+    pub(crate) activity: Option<Activity>,
+}
+
+/// Sliding-window limiter that coalesces activity updates
+///
+/// Updates are tracked per process, so a launcher showing several games keeps the
+/// latest state of each, while all of them share the one budget.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    sent: VecDeque<Instant>,
+    /// Oldest first, at most one per process
+    pending: VecDeque<PendingUpdate>,
+    /// What Discord shows for each process; `None` once it has been cleared
+    last_sent: HashMap<u32, Option<Value>>,
+}
+
+impl RateLimiter {
+    /// The fields of `RateLimitConfig` are public, so `max_updates` is clamped here too
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: RateLimitConfig::new(config.max_updates, config.window),
+            sent: VecDeque::new(),
+            pending: VecDeque::new(),
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// When the next update may be sent, or `None` if it may be sent right away
+    pub(crate) fn next_send_at(&self, now: Instant) -> Option<Instant> {
+        let max = self.config.max_updates as usize;
+        let recent: Vec<Instant> = self
+            .sent
+            .iter()
+            .copied()
+            .filter(|sent| *sent + self.config.window > now)
+            .collect();
+
+        if recent.len() < max {
+            None
+        } else {
+            Some(recent[recent.len() - max] + self.config.window)
+        }
+    }
+
+    /// Decide what to do with an update for `pid`; `None` clears its activity
+    pub(crate) fn submit(
+        &mut self,
+        pid: u32,
+        activity: Option<&Activity>,
+        now: Instant,
+    ) -> UpdateDecision {
+        let value = activity.and_then(|activity| serde_json::to_value(activity).ok());
+        if self.last_sent.get(&pid) == Some(&value) {
+            self.remove_pending(pid);
+            return UpdateDecision::Unchanged;
+        }
+
+        if self.next_send_at(now).is_some() {
+            let update = PendingUpdate {
+                pid,
+                activity: activity.cloned(),
+            };
+            match self.pending.iter_mut().find(|pending| pending.pid == pid) {
+                Some(pending) => *pending = update,
+                None => self.pending.push_back(update),
+            }
+            return UpdateDecision::Deferred;
+        }
+
+        self.remove_pending(pid);
+        UpdateDecision::Send
+    }
+
+    /// Take the oldest pending update if the window has room for it
+    pub(crate) fn take_ready(&mut self, now: Instant) -> Option<PendingUpdate> {
+        if self.next_send_at(now).is_none() {
+            self.pending.pop_front()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Put back an update whose send failed, unless a newer one for its process is pending
+    pub(crate) fn restore(&mut self, update: PendingUpdate) {
+        if !self.pending.iter().any(|pending| pending.pid == update.pid) {
+            self.pending.push_front(update);
+        }
+    }
+
+    /// Record a successful `SET_ACTIVITY` for `pid`; it supersedes that process's pending update
+    pub(crate) fn record_set(&mut self, pid: u32, activity: &Activity, now: Instant) {
+        self.record(now);
+        self.remove_pending(pid);
+        self.last_sent
+            .insert(pid, serde_json::to_value(activity).ok());
+    }
+
+    /// Record a clear for `pid`; it supersedes that process's pending update
+    pub(crate) fn record_clear(&mut self, pid: u32, now: Instant) {
+        self.record(now);
+        self.remove_pending(pid);
+        self.last_sent.insert(pid, None);
+    }
+
+    /// A new connection starts without an activity, so nothing counts as unchanged
+    pub(crate) fn reset_connection(&mut self) {
+        self.last_sent.clear();
+    }
+
+    fn remove_pending(&mut self, pid: u32) {
+        self.pending.retain(|pending| pending.pid != pid);
+    }
+
+    fn record(&mut self, now: Instant) {
+        self.sent.push_back(now);
+        while self.sent.len() > self.config.max_updates as usize {
+            self.sent.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+
+    const PID: u32 = 42;
+
+    fn activity(state: &str) -> Activity {
+        ActivityBuilder::new().state(state).build()
+    }
+
+    #[test]
+    fn defers_updates_beyond_the_budget() {
+        let mut limiter = RateLimiter::new(RateLimitConfig::new(2, Duration::from_secs(10)));
+        let start = Instant::now();
+
+        for state in ["a", "b"] {
+            assert!(matches!(
+                limiter.submit(PID, Some(&activity(state)), start),
+                UpdateDecision::Send
+            ));
+            limiter.record_set(PID, &activity(state), start);
+        }
+
+        assert!(matches!(
+            limiter.submit(PID, Some(&activity("c")), start),
+            UpdateDecision::Deferred
+        ));
+        assert!(matches!(
+            limiter.submit(PID, Some(&activity("d")), start),
+            UpdateDecision::Deferred
+        ));
+        assert_eq!(
+            limiter.next_send_at(start),
+            Some(start + Duration::from_secs(10))
+        );
+        assert!(limiter.take_ready(start).is_none());
+
+        let later = start + Duration::from_secs(10);
+        let latest = limiter.take_ready(later).expect("pending update");
+        assert_eq!(latest.pid, PID);
+        assert_eq!(latest.activity.unwrap().state.as_deref(), Some("d"));
+        assert!(!limiter.has_pending());
+    }
+
+    #[test]
+    fn zero_max_updates_allows_one_update() {
+        let config = RateLimitConfig {
+            max_updates: 0,
+            window: Duration::from_secs(10),
+        };
+        let mut limiter = RateLimiter::new(config);
+        let now = Instant::now();
+
+        assert_eq!(limiter.next_send_at(now), None);
+        limiter.record_set(PID, &activity("a"), now);
+        assert_eq!(
+            limiter.next_send_at(now),
+            Some(now + Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn skips_identical_activities_until_reconnect() {
+        let mut limiter = RateLimiter::new(RateLimitConfig::default());
+        let now = Instant::now();
+
+        limiter.record_set(PID, &activity("same"), now);
+        assert!(matches!(
+            limiter.submit(PID, Some(&activity("same")), now),
+            UpdateDecision::Unchanged
+        ));
+        limiter.record_clear(PID, now);
+        assert!(matches!(
+            limiter.submit(PID, None, now),
+            UpdateDecision::Unchanged
+        ));
+
+        limiter.reset_connection();
+        assert!(matches!(
+            limiter.submit(PID, None, now),
+            UpdateDecision::Send
+        ));
+    }
+
+    #[test]
+    fn clear_is_held_back_and_replaces_a_pending_update() {
+        let mut limiter = RateLimiter::new(RateLimitConfig::new(1, Duration::from_secs(10)));
+        let now = Instant::now();
+
+        limiter.record_set(PID, &activity("a"), now);
+        assert!(matches!(
+            limiter.submit(PID, Some(&activity("b")), now),
+            UpdateDecision::Deferred
+        ));
+        assert!(matches!(
+            limiter.submit(PID, None, now),
+            UpdateDecision::Deferred
+        ));
+
+        let later = now + Duration::from_secs(10);
+        let clear = limiter.take_ready(later).expect("pending clear");
+        assert!(clear.activity.is_none());
+        assert!(!limiter.has_pending());
+    }
+
+    #[test]
+    fn processes_keep_their_own_pending_update() {
+        let mut limiter = RateLimiter::new(RateLimitConfig::new(1, Duration::from_secs(10)));
+        let now = Instant::now();
+
+        limiter.record_set(PID, &activity("a"), now);
+        for pid in [PID, 7] {
+            assert!(matches!(
+                limiter.submit(pid, Some(&activity("b")), now),
+                UpdateDecision::Deferred
+            ));
+        }
+
+        let later = now + Duration::from_secs(10);
+        let first = limiter.take_ready(later).expect("first pending update");
+        assert_eq!(first.pid, PID);
+        limiter.record_set(first.pid, first.activity.as_ref().unwrap(), later);
+        assert!(limiter.take_ready(later).is_none());
+        assert!(limiter.has_pending());
+    }
+}
@@ -0,0 +1,355 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Observable connection state shared by every client
+
+use std::fmt;
+use std::future::poll_fn;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Poll, Waker};
+
+use serde_json::Value;
+
+use crate::error::DiscordIpcError;
+use crate::ipc::protocol::{Opcode, PartialUser, ReadyEvent};
+
+/// Where a client is in its connection lifecycle
+///
+/// The `Display` impl gives a short status line suitable for a UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionState {
+    /// The client was closed locally or lost its connection
+    Disconnected,
+    /// No Discord IPC socket could be found or opened
+    WaitingForDiscord,
+    /// Connecting for the first time; a new client stays here until its first handshake
+    Connecting,
+    /// Socket open, waiting for Discord to answer the handshake
+    Handshaking,
+    /// Handshake completed
+    Connected {
+        /// The user from the READY payload, if Discord sent one
+        user: Option<PartialUser>,
+    },
+    /// Opening a new socket after a connection was lost
+    Reconnecting {
+        /// Reconnect attempts since the last successful handshake, starting at 1
+        attempt: u32,
+    },
+    /// Discord closed the connection
+    Closed {
+        /// The message from Discord's close frame, if it sent one
+        reason: Option<String>,
+    },
+}
+
+impl ConnectionState {
+    /// Returns `true` for [`ConnectionState::Connected`]
+    pub fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected => f.write_str("Disconnected"),
+            Self::WaitingForDiscord => f.write_str("Waiting for Discord…"),
+            Self::Connecting => f.write_str("Connecting"),
+            Self::Handshaking => f.write_str("Handshaking"),
+            Self::Connected { user } => {
+                match user.as_ref().and_then(|user| user.username.as_deref()) {
+                    Some(name) => write!(f, "Connected as {name}"),
+                    None => f.write_str("Connected"),
+                }
+            }
+            Self::Reconnecting { attempt } => write!(f, "Reconnecting (attempt {attempt})"),
+            Self::Closed {
+                reason: Some(reason),
+            } => write!(f, "Closed by Discord: {reason}"),
+            Self::Closed { reason: None } => f.write_str("Closed by Discord"),
+        }
+    }
+}
+
+type Listener = Box<dyn FnMut(&ConnectionState) + Send>;
+
+/// Drives the state transitions of one client and notifies its observers
+pub(crate) struct StateTracker {
+    watch: Arc<Watch>,
+    listener: Option<Listener>,
+    attempts: u32,
+    ever_connected: bool,
+}
+
+impl StateTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            watch: Arc::new(Watch {
+                inner: Mutex::new(WatchState {
+                    state: ConnectionState::Disconnected,
+                    version: 0,
+                    wakers: Vec::new(),
+                    closed: false,
+                }),
+            }),
+            listener: None,
+            attempts: 0,
+            ever_connected: false,
+        }
+    }
+
+    pub(crate) fn get(&self) -> ConnectionState {
+        lock(&self.watch.inner).state.clone()
+    }
+
+    pub(crate) fn set_listener(&mut self, listener: Listener) {
+        self.listener = Some(listener);
+    }
+
+    pub(crate) fn receiver(&self) -> ConnectionStateReceiver {
+        ConnectionStateReceiver {
+            version: lock(&self.watch.inner).version,
+            watch: self.watch.clone(),
+        }
+    }
+
+    /// About to open a new socket
+    pub(crate) fn reconnecting(&mut self) {
+        self.attempts += 1;
+        let state = if self.ever_connected {
+            ConnectionState::Reconnecting {
+                attempt: self.attempts,
+            }
+        } else {
+            ConnectionState::Connecting
+        };
+        self.set(state);
+    }
+
+    /// Opening the socket failed
+    pub(crate) fn open_failed(&mut self, err: &DiscordIpcError) {
+        let state = match err {
+            DiscordIpcError::NoValidSocket
+            | DiscordIpcError::ReleaseChannelNotFound { .. }
+            | DiscordIpcError::UserNotFound { .. }
+            | DiscordIpcError::SocketDiscoveryFailed { .. }
+            | DiscordIpcError::ConnectionFailed(_)
+            | DiscordIpcError::ConnectionTimeout { .. } => ConnectionState::WaitingForDiscord,
+            _ => ConnectionState::Disconnected,
+        };
+        self.set(state);
+    }
+
+    pub(crate) fn handshaking(&mut self) {
+        self.set(ConnectionState::Handshaking);
+    }
+
+    pub(crate) fn connected(&mut self, ready: &ReadyEvent) {
+        self.attempts = 0;
+        self.ever_connected = true;
+        self.set(ConnectionState::Connected {
+            user: ready.user.clone(),
+        });
+    }
+
+    /// Note a close frame from Discord
+    pub(crate) fn frame(&mut self, opcode: Opcode, payload: &Value) {
+        if opcode == Opcode::Close {
+            let reason = payload
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            self.set(ConnectionState::Closed { reason });
+        }
+    }
+
+    /// A request or the handshake failed with `err`
+    pub(crate) fn failed(&mut self, err: &DiscordIpcError) {
+        if matches!(self.get(), ConnectionState::Closed { .. }) {
+            return;
+        }
+        match err {
+            DiscordIpcError::SocketClosed => self.set(ConnectionState::Closed { reason: None }),
+            err if err.is_connection_error() => self.set(ConnectionState::Disconnected),
+            _ => {}
+        }
+    }
+
+    /// The handshake was rejected
+    pub(crate) fn handshake_failed(&mut self) {
+        self.closed();
+    }
+
+    /// The client was closed locally; a close by Discord is kept as the reason
+    pub(crate) fn closed(&mut self) {
+        if !matches!(self.get(), ConnectionState::Closed { .. }) {
+            self.set(ConnectionState::Disconnected);
+        }
+    }
+
+    fn set(&mut self, state: ConnectionState) {
+        {
+            let mut watch = lock(&self.watch.inner);
+            if watch.state == state {
+                return;
+            }
+            watch.state = state.clone();
+            watch.version += 1;
+            for waker in watch.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+        if let Some(listener) = self.listener.as_mut() {
+            listener(&state);
+        }
+    }
+}
+
+impl Drop for StateTracker {
+    fn drop(&mut self) {
+        let mut watch = lock(&self.watch.inner);
+        watch.closed = true;
+        for waker in watch.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+struct Watch {
+    inner: Mutex<WatchState>,
+}
+
+struct WatchState {
+    state: ConnectionState,
+    version: u64,
+    wakers: Vec<Waker>,
+    closed: bool,
+}
+
+/// Watch-style receiver for the [`ConnectionState`] of an async client
+///
+/// Only the latest state is kept; a receiver that is slow to look sees the current
+/// state rather than every intermediate one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "tokio-runtime")]
+/// # async fn example() -> presenceforge::Result {
+/// use presenceforge::async_io::tokio::TokioDiscordIpcClient;
+///
+/// let mut client = TokioDiscordIpcClient::new("client_id").await?;
+/// let mut states = client.state_receiver();
+/// tokio::spawn(async move {
+///     while let Some(state) = states.changed().await {
+///         println!("{state}");
+///     }
+/// });
+/// client.connect().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ConnectionStateReceiver {
+    watch: Arc<Watch>,
+    version: u64,
+}
+
+impl fmt::Debug for ConnectionStateReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionStateReceiver")
+            .field("state", &self.get())
+            .field("has_changed", &self.has_changed())
+            .finish()
+    }
+}
+
+impl ConnectionStateReceiver {
+    /// The current state
+    pub fn get(&self) -> ConnectionState {
+        lock(&self.watch.inner).state.clone()
+    }
+
+    /// Returns `true` if the state changed since this receiver last looked
+    pub fn has_changed(&self) -> bool {
+        lock(&self.watch.inner).version != self.version
+    }
+
+    /// Wait for the state to change and return the new state
+    ///
+    /// Returns `None` once the client has been dropped.
+    pub async fn changed(&mut self) -> Option<ConnectionState> {
+        poll_fn(|cx| {
+            let mut watch = lock(&self.watch.inner);
+            if watch.version != self.version {
+                self.version = watch.version;
+                return Poll::Ready(Some(watch.state.clone()));
+            }
+            if watch.closed {
+                return Poll::Ready(None);
+            }
+            if !watch.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                watch.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reconnect_attempts_count_until_the_next_handshake() {
+        let mut tracker = StateTracker::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        tracker.set_listener(Box::new({
+            let seen = seen.clone();
+            move |state: &ConnectionState| seen.lock().unwrap().push(state.to_string())
+        }));
+
+        tracker.reconnecting();
+        tracker.open_failed(&DiscordIpcError::NoValidSocket);
+        tracker.reconnecting();
+        tracker.handshaking();
+        tracker.connected(&ReadyEvent {
+            user: Some(PartialUser {
+                id: None,
+                username: Some("tester".to_string()),
+                discriminator: None,
+                avatar: None,
+                bot: None,
+            }),
+            config: None,
+        });
+        tracker.frame(Opcode::Close, &json!({ "code": 4000, "message": "Bye" }));
+        tracker.failed(&DiscordIpcError::SocketClosed);
+        tracker.reconnecting();
+        tracker.reconnecting();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "Connecting",
+                "Waiting for Discord…",
+                "Connecting",
+                "Handshaking",
+                "Connected as tester",
+                "Closed by Discord: Bye",
+                "Reconnecting (attempt 1)",
+                "Reconnecting (attempt 2)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,316 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Waiting for a Discord socket to appear
+//!
+//! On Linux the socket directories are watched with inotify, so a connection is
+//! attempted as soon as Discord creates its socket. Elsewhere, and when inotify is
+//! unavailable, [`SocketWatch::wait`] sleeps for the retry interval instead.
+
+use std::time::Duration;
+
+use crate::debug_println;
+use crate::ipc::connection::PipeConfig;
+#[cfg(target_os = "linux")]
+use crate::ipc::discovery::{self, IpcOverride};
+#[cfg(target_os = "linux")]
+use crate::ipc::protocol::constants;
+use crate::ipc::protocol::IpcConfig;
+
+/// Quick retries after a socket appeared, before going back to waiting for events
+///
+/// Discord creates the socket file before it starts listening, so the first
+/// connection attempt can be refused.
+#[cfg(target_os = "linux")]
+const SETTLE_RETRIES: u8 = 10;
+
+/// Directories a socket for `config` can appear in, and the socket name if it is fixed
+#[cfg(target_os = "linux")]
+fn watched_dirs(config: &PipeConfig, ipc_config: &IpcConfig) -> (Vec<String>, Option<String>) {
+    let parent = |path: &str| {
+        std::path::Path::new(path)
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    match config {
+        PipeConfig::CustomPath(path) => {
+            let name = std::path::Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned());
+            (vec![parent(path)], name)
+        }
+        PipeConfig::Auto | PipeConfig::ReleaseChannel(_) | PipeConfig::UserId(_) => {
+            let mut dirs = discovery::candidate_ipc_directories_with_config(ipc_config);
+            // An overriding socket is named like any other, so only its directory is missing
+            if let Some(IpcOverride::Socket(path)) = IpcOverride::from_env() {
+                dirs.push(parent(&path));
+            }
+            (dirs, None)
+        }
+    }
+}
+
+/// Wakes up when a Discord socket may have appeared
+pub(crate) struct SocketWatch {
+    retry: Duration,
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+    #[cfg(target_os = "linux")]
+    dirs: Vec<String>,
+    #[cfg(target_os = "linux")]
+    name: Option<String>,
+    #[cfg(target_os = "linux")]
+    settle: u8,
+}
+
+impl SocketWatch {
+    /// Start watching for sockets matching `config`
+    ///
+    /// Create the watch before the first connection attempt, so a socket created in
+    /// between is not missed.
+    pub(crate) fn new(config: &PipeConfig, ipc_config: &IpcConfig) -> Self {
+        let retry = Duration::from_millis(ipc_config.retry_interval_ms);
+
+        #[cfg(target_os = "linux")]
+        {
+            let (dirs, name) = watched_dirs(config, ipc_config);
+            let watch = Self {
+                retry,
+                inotify: inotify::Inotify::new(),
+                dirs,
+                name,
+                settle: 0,
+            };
+            watch.arm();
+            if watch.inotify.is_none() {
+                debug_println!("inotify unavailable, polling for the Discord socket");
+            }
+            watch
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (config, ipc_config);
+            debug_println!("Polling for the Discord socket");
+            Self { retry }
+        }
+    }
+
+    /// Block for at most `timeout`; returns `true` if connecting is worth another try
+    pub(crate) fn wait(&mut self, timeout: Duration) -> bool {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = &self.inotify {
+            if self.settle > 0 {
+                self.settle -= 1;
+                let events = inotify.wait(timeout.min(self.retry));
+                self.changed(&events);
+                return true;
+            }
+            let events = inotify.wait(timeout);
+            if self.changed(&events) {
+                self.settle = SETTLE_RETRIES;
+                return true;
+            }
+            return false;
+        }
+
+        std::thread::sleep(timeout.min(self.retry));
+        true
+    }
+
+    /// Returns `true` if `events` include a socket; re-arms when directories appear
+    #[cfg(target_os = "linux")]
+    fn changed(&self, events: &[inotify::Event]) -> bool {
+        let mut changed = false;
+        for event in events {
+            if event.is_dir {
+                debug_println!("Directory {} appeared, watching again", event.name);
+                self.arm();
+                changed = true;
+            } else if match &self.name {
+                Some(name) => event.name == *name,
+                None => event.name.starts_with(constants::IPC_SOCKET_PREFIX),
+            } {
+                debug_println!("Socket {} appeared", event.name);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Watch every socket directory, or its closest existing parent if it is missing
+    #[cfg(target_os = "linux")]
+    fn arm(&self) {
+        let Some(inotify) = &self.inotify else {
+            return;
+        };
+        for dir in &self.dirs {
+            let mut dir = std::path::Path::new(dir);
+            while !dir.is_dir() {
+                match dir.parent() {
+                    Some(parent) => dir = parent,
+                    None => break,
+                }
+            }
+            if !inotify.add(dir) {
+                debug_println!("Cannot watch {}", dir.display());
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::CString;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// Size of the fixed part of `struct inotify_event`
+    const HEADER: usize = 16;
+
+    /// A file created in a watched directory
+    pub(super) struct Event {
+        pub(super) name: String,
+        pub(super) is_dir: bool,
+    }
+
+    pub(super) struct Inotify {
+        fd: OwnedFd,
+    }
+
+    impl Inotify {
+        pub(super) fn new() -> Option<Self> {
+            // SAFETY: inotify_init1 takes only flags and returns a new descriptor or -1
+            let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return None;
+            }
+            // SAFETY: inotify_init1 returned a new descriptor that nothing else owns
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+            Some(Self { fd })
+        }
+
+        /// Watch `dir` for created files; watching a directory twice is harmless
+        pub(super) fn add(&self, dir: &Path) -> bool {
+            let Ok(path) = CString::new(dir.as_os_str().as_bytes()) else {
+                return false;
+            };
+            let mask = libc::IN_CREATE | libc::IN_MOVED_TO;
+            // SAFETY: `path` is a NUL-terminated string that outlives the call
+            unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), path.as_ptr(), mask) >= 0 }
+        }
+
+        /// Wait up to `timeout` for files to be created and return them
+        pub(super) fn wait(&self, timeout: Duration) -> Vec<Event> {
+            let mut pollfd = libc::pollfd {
+                fd: self.fd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as libc::c_int;
+            // SAFETY: `pollfd` points to one valid pollfd for the duration of the call
+            if unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } <= 0 {
+                return Vec::new();
+            }
+
+            let mut events = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                // SAFETY: `buf` is writable for `buf.len()` bytes for the duration of the call
+                let read =
+                    unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+                if read <= 0 {
+                    break;
+                }
+                parse(&buf[..read as usize], &mut events);
+            }
+            events
+        }
+    }
+
+    fn parse(mut buf: &[u8], events: &mut Vec<Event>) {
+        while buf.len() >= HEADER {
+            let field =
+                |at: usize| u32::from_ne_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]]);
+            let mask = field(4);
+            let len = field(12) as usize;
+            let Some(name) = buf.get(HEADER..HEADER + len) else {
+                break;
+            };
+            let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+            events.push(Event {
+                name: String::from_utf8_lossy(name).into_owned(),
+                is_dir: mask & libc::IN_ISDIR != 0,
+            });
+            buf = &buf[HEADER + len..];
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::time::Instant;
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("presenceforge-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn wakes_up_when_the_socket_is_created() {
+        let dir = temp_dir();
+        let path = dir.join("discord-ipc-0");
+        let config = PipeConfig::CustomPath(path.to_string_lossy().into_owned());
+        let mut watch = SocketWatch::new(&config, &IpcConfig::default().with_retry_interval(100));
+        assert!(watch.inotify.is_some());
+
+        let started = Instant::now();
+        let creator = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            UnixListener::bind(path).unwrap()
+        });
+        assert!(watch.wait(Duration::from_secs(5)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        drop(creator.join().unwrap());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn other_files_are_ignored() {
+        let dir = temp_dir();
+        let config =
+            PipeConfig::CustomPath(dir.join("discord-ipc-0").to_string_lossy().into_owned());
+        let mut watch = SocketWatch::new(&config, &IpcConfig::default().with_retry_interval(100));
+
+        std::fs::write(dir.join("unrelated"), b"").unwrap();
+        assert!(!watch.wait(Duration::from_millis(100)));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_directories_are_watched_through_their_parent() {
+        let dir = temp_dir();
+        let nested = dir.join("app");
+        let path = nested.join("discord-ipc-0");
+        let config = PipeConfig::CustomPath(path.to_string_lossy().into_owned());
+        let mut watch = SocketWatch::new(&config, &IpcConfig::default().with_retry_interval(100));
+
+        std::fs::create_dir(&nested).unwrap();
+        assert!(watch.wait(Duration::from_secs(5)));
+        // Drain the quick retries that follow a change
+        while watch.settle > 0 {
+            watch.wait(Duration::ZERO);
+        }
+        let _listener = UnixListener::bind(&path).unwrap();
+        assert!(watch.wait(Duration::from_secs(5)));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
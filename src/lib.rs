@@ -55,7 +55,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! presenceforge = { version = "0.2.1", features = ["tokio-runtime"] }
+//! presenceforge = { version = "0.3.0", features = ["tokio-runtime"] }
 //! tokio = { version = "1", features = ["rt-multi-thread", "macros"] }
 //! ```
 //!
@@ -90,7 +90,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! presenceforge = { version = "0.2.1", features = ["async-std-runtime"] }
+//! presenceforge = { version = "0.3.0", features = ["async-std-runtime"] }
 //! async-std = { version = "1", features = ["attributes"] }
 //! ```
 //!
@@ -125,7 +125,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! presenceforge = { version = "0.2.1", features = ["smol-runtime"] }
+//! presenceforge = { version = "0.3.0", features = ["smol-runtime"] }
 //! smol = "2"
 //! ```
 //!
@@ -181,12 +181,16 @@ pub mod ipc;
 pub mod macros;
 pub mod nonce;
 pub mod retry;
+
+#[cfg(all(test, unix))]
+mod test_support;
 // Re-export the main public API
 #[cfg(feature = "secrets")]
 pub use activity::ActivitySecrets;
 pub use activity::{
-    Activity, ActivityAssets, ActivityBuilder, ActivityButton, ActivityParty, ActivityTimestamps,
-    ActivityValidationError,
+    Activity, ActivityAssets, ActivityBuilder, ActivityButton, ActivityParty, ActivityRotation,
+    ActivityTimestamps, ActivityValidationError, AppliedActivity, PresenceStack, PresenceTimestamp,
+    PresenceUpdate,
 };
 pub use error::{
     DiscordIpcError, HandshakeFailureKind, InvalidResponseKind, ProtocolContext,
@@ -194,7 +198,10 @@ pub use error::{
 };
 pub use ipc::protocol::IpcConfig;
 pub use ipc::{
-    Command, DiscoveredPipe, EventData, IpcConnection, Opcode, PartialUser, PipeConfig, ReadyEvent,
+    resolve_pid, Command, ConnectionState, ConnectionStateReceiver, DiscoveredPipe, EventData,
+    EventHandlers, EventKind, EventSubscription, IpcConnection, KnownLocation, Opcode, PartialUser,
+    PendingStats, PidInfo, PidSource, PipeConfig, ProbedPipe, RateLimitConfig, ReadyEvent,
+    ReleaseChannel, Sandbox,
 };
 pub use macros::is_debug_enabled;
 
@@ -217,3 +224,13 @@ pub use async_io::async_std::AsyncStdDiscordIpcClient as AsyncDiscordIpcClient;
     not(feature = "async-std-runtime")
 ))]
 pub use async_io::smol::SmolDiscordIpcClient as AsyncDiscordIpcClient;
+
+// Presence managers per runtime; `async_io::AsyncPresenceManager` is the generic type
+#[cfg(feature = "tokio-runtime")]
+pub use async_io::tokio::TokioPresenceManager;
+
+#[cfg(feature = "async-std-runtime")]
+pub use async_io::async_std::AsyncStdPresenceManager;
+
+#[cfg(feature = "smol-runtime")]
+pub use async_io::smol::SmolPresenceManager;
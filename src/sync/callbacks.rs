@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Callback pump for [`EventHandlers`] on the synchronous client
+
+use crate::error::Result;
+use crate::ipc::{EventData, EventHandlers};
+use crate::sync::client::DiscordIpcClient;
+
+impl DiscordIpcClient {
+    /// Run callbacks for everything that arrived since the last call, without blocking
+    ///
+    /// Call this once per frame from a game loop. `on_ready` fires first after each
+    /// handshake. When the connection is lost, `on_disconnected` fires once and the
+    /// client is closed; reconnect to resume. Returns how many callbacks were
+    /// dispatched.
+    pub fn run_callbacks(&mut self, handlers: &mut EventHandlers) -> Result<usize> {
+        if !self.is_connected() {
+            return Ok(0);
+        }
+
+        let mut dispatched = 0;
+        if let Some(ready) = self.take_pending_ready() {
+            handlers.dispatch(&EventData::Ready(ready));
+            dispatched += 1;
+        }
+
+        loop {
+            match self.poll_event() {
+                Ok(Some(event)) => {
+                    handlers.dispatch(&event);
+                    dispatched += 1;
+                }
+                Ok(None) => return Ok(dispatched),
+                Err(err) if err.is_connection_error() => {
+                    self.close();
+                    handlers.dispatch_disconnected(&err);
+                    return Ok(dispatched + 1);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::{encode_frame, MockDiscord};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn fires_ready_then_events_then_disconnected() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut handlers = EventHandlers::new()
+            .on_ready({
+                let log = log.clone();
+                move |_| log.lock().unwrap().push("ready".to_string())
+            })
+            .on_join({
+                let log = log.clone();
+                move |join| log.lock().unwrap().push(format!("join {}", join.secret))
+            })
+            .on_disconnected({
+                let log = log.clone();
+                move |_| log.lock().unwrap().push("disconnected".to_string())
+            });
+
+        mock.send_raw(&encode_frame(
+            1,
+            &json!({ "cmd": "DISPATCH", "evt": "ACTIVITY_JOIN", "data": { "secret": "s" }, "nonce": null }),
+        ));
+        mock.drop_connections();
+
+        let mut dispatched = 0;
+        while client.is_connected() {
+            dispatched += client.run_callbacks(&mut handlers).unwrap();
+        }
+        assert_eq!(dispatched, 3);
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["ready", "join s", "disconnected"].map(String::from)
+        );
+        assert_eq!(client.run_callbacks(&mut handlers).unwrap(), 0);
+    }
+}
@@ -4,17 +4,21 @@
 #![allow(clippy::collapsible_if)]
 
 use serde_json::{json, Value};
-use std::collections::VecDeque;
-use std::process;
 use std::time::{Duration, Instant};
 
-use crate::activity::Activity;
+use crate::activity::{Activity, AppliedActivity};
 use crate::debug_println;
 use crate::error::{DiscordIpcError, InvalidResponseKind, Result};
-use crate::ipc::protocol::{validate_handshake_response, IpcConfig};
+use crate::ipc::connection::{SocketSearch, SocketTarget};
+use crate::ipc::discovery;
+use crate::ipc::pending::{PendingQueue, PendingStats};
+use crate::ipc::process::{self, PidInfo};
+use crate::ipc::protocol::{parse_applied_activity, validate_handshake_response, IpcConfig};
+use crate::ipc::rate_limit::{RateLimiter, UpdateDecision};
+use crate::ipc::state::StateTracker;
 use crate::ipc::{
-    Command, EventData, HandshakePayload, IpcConnection, IpcMessage, IpcResponse, Opcode,
-    PipeConfig, ReadyEvent,
+    Command, ConnectionState, EventData, HandshakePayload, IpcConnection, IpcMessage, IpcResponse,
+    Opcode, PipeConfig, ReadyEvent,
 };
 use crate::nonce::generate_nonce;
 
@@ -24,9 +28,12 @@ pub struct DiscordIpcClient {
     connection: IpcConnection,
     pipe_config: Option<PipeConfig>,
     timeout_ms: Option<u64>,
-    pending_messages: VecDeque<PendingMessage>,
+    pending_messages: PendingQueue,
     connected: bool,
     ipc_config: IpcConfig,
+    rate_limiter: Option<RateLimiter>,
+    pending_ready: Option<ReadyEvent>,
+    state: StateTracker,
 }
 
 impl DiscordIpcClient {
@@ -82,18 +89,7 @@ impl DiscordIpcClient {
         config: Option<PipeConfig>,
         ipc_config: IpcConfig,
     ) -> Result<Self> {
-        let client_id = client_id.into();
-        let connection = IpcConnection::new_with_configs(config.clone(), ipc_config.clone())?;
-
-        Ok(Self {
-            client_id,
-            connection,
-            pipe_config: config,
-            timeout_ms: None,
-            pending_messages: VecDeque::new(),
-            connected: false,
-            ipc_config,
-        })
+        Self::new_internal(client_id.into(), config, None, ipc_config)
     }
 
     /// Create a new Discord IPC client with a connection timeout (uses auto-discovery)
@@ -167,24 +163,54 @@ impl DiscordIpcClient {
         timeout_ms: u64,
         ipc_config: IpcConfig,
     ) -> Result<Self> {
-        let client_id = client_id.into();
-        let connection = IpcConnection::new_with_configs_and_timeout(
-            config.clone(),
-            timeout_ms,
-            ipc_config.clone(),
-        )?;
+        Self::new_internal(client_id.into(), config, Some(timeout_ms), ipc_config)
+    }
+
+    fn new_internal(
+        client_id: String,
+        config: Option<PipeConfig>,
+        timeout_ms: Option<u64>,
+        ipc_config: IpcConfig,
+    ) -> Result<Self> {
+        let mut state = StateTracker::new();
+        let connection = Self::open(&config, timeout_ms, &ipc_config, &mut state)?;
 
         Ok(Self {
             client_id,
             connection,
             pipe_config: config,
-            timeout_ms: Some(timeout_ms),
-            pending_messages: VecDeque::new(),
+            timeout_ms,
+            pending_messages: PendingQueue::new(&ipc_config),
             connected: false,
+            rate_limiter: ipc_config.rate_limit.clone().map(RateLimiter::new),
+            pending_ready: None,
+            state,
             ipc_config,
         })
     }
 
+    /// Open a socket, reporting `Connecting` (or `Reconnecting`) and any failure to `state`
+    fn open(
+        config: &Option<PipeConfig>,
+        timeout_ms: Option<u64>,
+        ipc_config: &IpcConfig,
+        state: &mut StateTracker,
+    ) -> Result<IpcConnection> {
+        state.reconnecting();
+        let connection = match timeout_ms {
+            Some(timeout) => IpcConnection::new_with_configs_and_timeout(
+                config.clone(),
+                timeout,
+                ipc_config.clone(),
+            ),
+            None => IpcConnection::new_with_configs(config.clone(), ipc_config.clone()),
+        };
+        connection.map_err(|err| {
+            state.open_failed(&err);
+            err
+        })
+    }
+
     /// Perform handshake with Discord
     ///
     /// # Returns
@@ -195,8 +221,28 @@ impl DiscordIpcClient {
     ///
     /// Returns a `DiscordIpcError::HandshakeFailed` if the handshake fails
     pub fn connect(&mut self) -> Result<Value> {
+        let (response, ready) = match self.pipe_config.as_ref().and_then(PipeConfig::target) {
+            Some(target) => self.handshake_target(target)?,
+            None => self.handshake()?,
+        };
+
+        self.connected = true;
+        self.state.connected(&ready);
+        self.pending_ready = Some(ready);
+        Ok(response)
+    }
+
+    fn handshake(&mut self) -> Result<(Value, ReadyEvent)> {
+        self.handshake_within(self.ipc_config.handshake_timeout)
+    }
+
+    /// Handshake, waiting at most `timeout` for Discord's answer
+    fn handshake_within(&mut self, timeout: Option<Duration>) -> Result<(Value, ReadyEvent)> {
         self.pending_messages.clear();
         self.connected = false;
+        if let Some(limiter) = self.rate_limiter.as_mut() {
+            limiter.reset_connection();
+        }
 
         let handshake = HandshakePayload {
             v: self.ipc_config.ipc_version,
@@ -206,14 +252,80 @@ impl DiscordIpcClient {
         let payload =
             serde_json::to_value(handshake).map_err(DiscordIpcError::SerializationFailed)?;
 
-        self.connection.send(Opcode::Handshake, &payload)?;
-
-        let (opcode, response) = self.connection.recv()?;
+        self.state.handshaking();
+        self.connection.set_read_timeout(timeout)?;
+        let response = self
+            .connection
+            .send(Opcode::Handshake, &payload)
+            .and_then(|_| self.connection.recv())
+            .map_err(|err| self.request_failed(err, "handshake", None));
+        self.connection
+            .set_read_timeout(self.ipc_config.read_timeout)?;
+
+        let (opcode, response) = response?;
         debug_println!("Handshake response: {}", response);
-        validate_handshake_response(opcode, &response)?;
+        self.state.frame(opcode, &response);
+        let ready = validate_handshake_response(opcode, &response).map_err(|err| {
+            self.state.handshake_failed();
+            err
+        })?;
+        Ok((response, ready))
+    }
 
-        self.connected = true;
-        Ok(response)
+    /// Handshake with the open socket, then each other socket in turn, until one matches `target`
+    ///
+    /// The search as a whole is bounded by the connect timeout, or by
+    /// [`IpcConfig::handshake_timeout`] when there is none.
+    fn handshake_target(&mut self, target: SocketTarget) -> Result<(Value, ReadyEvent)> {
+        let budget = self
+            .timeout_ms
+            .map(Duration::from_millis)
+            .or(self.ipc_config.handshake_timeout);
+        let mut search = SocketSearch::new(target, budget);
+        let opened = self.connection.path().to_string();
+        let mut candidates = discovery::candidate_paths(&self.ipc_config)
+            .into_iter()
+            .filter(|path| *path != opened);
+
+        loop {
+            let path = self.connection.path().to_string();
+            search.opened();
+            let timeout = search.cap(self.ipc_config.handshake_timeout);
+            match self.handshake_within(timeout) {
+                Ok((response, ready)) if search.accepts(&path, &ready) => {
+                    return Ok((response, ready))
+                }
+                // Say goodbye rather than leave Discord with a dangling client
+                Ok(_) => {
+                    let _ = self.connection.send(Opcode::Close, &json!({}));
+                }
+                Err(err) => search.failed(&path, err),
+            }
+            self.connection.close();
+
+            let next = loop {
+                if search.expired() {
+                    break None;
+                }
+                let Some(path) = candidates.next() else {
+                    break None;
+                };
+                if let Ok(connection) = IpcConnection::new_with_configs(
+                    Some(PipeConfig::CustomPath(path)),
+                    self.ipc_config.clone(),
+                ) {
+                    break Some(connection);
+                }
+            };
+            match next {
+                Some(connection) => self.connection = connection,
+                None => break,
+            }
+        }
+
+        let err = search.into_error();
+        self.state.open_failed(&err);
+        Err(err)
     }
 
     /// Perform handshake and return the typed READY payload when available.
@@ -243,6 +355,130 @@ impl DiscordIpcClient {
     ///
     /// Returns a `DiscordIpcError` if serialization fails or if Discord returns an error
     pub fn set_activity(&mut self, activity: &Activity) -> Result {
+        self.set_activity_for_pid(self.ipc_config.activity_pid(), activity)
+    }
+
+    /// Set Discord Rich Presence activity and return the activity Discord stored
+    ///
+    /// Discord echoes the applied activity in its response, including fields it fills
+    /// in itself (`application_id`, `name`) and normalized assets. Compare it with the
+    /// requested activity using [`AppliedActivity::dropped_fields`].
+    ///
+    /// Returns `None` if nothing was sent: the rate limiter held the activity back
+    /// (see [`flush_pending_update`](Self::flush_pending_update)) or Discord already
+    /// shows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if the request fails or the response carries no activity
+    pub fn set_activity_with_response(
+        &mut self,
+        activity: &Activity,
+    ) -> Result<Option<AppliedActivity>> {
+        let pid = self.ipc_config.activity_pid();
+        if !self.submit(pid, Some(activity))? {
+            return Ok(None);
+        }
+
+        let response = self.send_set_activity(activity, pid)?;
+        self.record_sent(pid, Some(activity));
+        parse_applied_activity(&response).map(Some)
+    }
+
+    /// Set the activity on behalf of process `pid`
+    ///
+    /// Overrides `IpcConfig::pid` for this call only. The rate limiter keeps the
+    /// latest update of each process, all sharing one budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if serialization fails or if Discord returns an error
+    pub fn set_activity_for_pid(&mut self, pid: u32, activity: &Activity) -> Result {
+        if !self.submit(pid, Some(activity))? {
+            return Ok(());
+        }
+
+        self.send_set_activity(activity, pid)?;
+        self.record_sent(pid, Some(activity));
+        Ok(())
+    }
+
+    /// Send the update held back by the rate limiter, if the window has room for it
+    ///
+    /// With rate limiting enabled, call this periodically (for example from your main
+    /// loop) so the most recent activity is delivered. [`next_update_at`](Self::next_update_at)
+    /// tells you when it will succeed.
+    ///
+    /// Returns `true` if an update was sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if sending fails; the update stays pending.
+    pub fn flush_pending_update(&mut self) -> Result<bool> {
+        let Some(update) = self
+            .rate_limiter
+            .as_mut()
+            .and_then(|limiter| limiter.take_ready(Instant::now()))
+        else {
+            return Ok(false);
+        };
+
+        let sent = match &update.activity {
+            Some(activity) => self.send_set_activity(activity, update.pid),
+            None => self.send_clear_activity(update.pid),
+        };
+        if let Err(err) = sent {
+            if let Some(limiter) = self.rate_limiter.as_mut() {
+                limiter.restore(update);
+            }
+            return Err(err);
+        }
+        self.record_sent(update.pid, update.activity.as_ref());
+        Ok(true)
+    }
+
+    /// Returns `true` if the rate limiter is holding back an activity update
+    pub fn has_pending_update(&self) -> bool {
+        self.rate_limiter
+            .as_ref()
+            .is_some_and(|limiter| limiter.has_pending())
+    }
+
+    /// When the rate limiter will allow the next activity update
+    ///
+    /// Returns `None` if an update can be sent right away or rate limiting is disabled.
+    pub fn next_update_at(&self) -> Option<Instant> {
+        self.rate_limiter
+            .as_ref()
+            .and_then(|limiter| limiter.next_send_at(Instant::now()))
+    }
+
+    /// Ask the rate limiter whether to send an update now; `None` clears the activity
+    ///
+    /// Returns `false` if the update was held back or Discord already shows it.
+    fn submit(&mut self, pid: u32, activity: Option<&Activity>) -> Result<bool> {
+        let Some(limiter) = self.rate_limiter.as_mut() else {
+            return Ok(true);
+        };
+        if let Some(activity) = activity {
+            activity.validate()?;
+        }
+        Ok(matches!(
+            limiter.submit(pid, activity, Instant::now()),
+            UpdateDecision::Send
+        ))
+    }
+
+    fn record_sent(&mut self, pid: u32, activity: Option<&Activity>) {
+        if let Some(limiter) = self.rate_limiter.as_mut() {
+            match activity {
+                Some(activity) => limiter.record_set(pid, activity, Instant::now()),
+                None => limiter.record_clear(pid, Instant::now()),
+            }
+        }
+    }
+
+    fn send_set_activity(&mut self, activity: &Activity, pid: u32) -> Result<Value> {
         // Validate the activity first
         activity.validate()?;
 
@@ -252,7 +488,7 @@ impl DiscordIpcClient {
         let message = IpcMessage {
             cmd: Command::SetActivity,
             args: json!({
-                "pid": process::id(),
+                "pid": process::host_pid(pid),
                 "activity": activity
             }),
             nonce: nonce.clone(),
@@ -263,10 +499,9 @@ impl DiscordIpcClient {
         #[cfg(debug_assertions)]
         // Intentional: Print payload for debugging in debug builds only.
         debug_println!("[PAYLOAD]: {:?} ", payload);
-        self.connection.send(Opcode::Frame, &payload)?;
 
         // Receive the response to check for errors
-        let (opcode, response) = self.recv_for_nonce(&nonce)?;
+        let (opcode, response) = self.request("SET_ACTIVITY", &payload, &nonce)?;
 
         // Check if we got the correct response type
         if !opcode.is_frame_response() {
@@ -301,7 +536,7 @@ impl DiscordIpcClient {
             }
         }
 
-        Ok(())
+        Ok(response)
     }
 
     /// Clear Discord Rich Presence activity
@@ -314,13 +549,35 @@ impl DiscordIpcClient {
     ///
     /// Returns a `DiscordIpcError` if communication fails or if Discord returns an error
     pub fn clear_activity(&mut self) -> Result<Value> {
+        self.clear_activity_for_pid(self.ipc_config.activity_pid())
+    }
+
+    /// Clear the activity shown on behalf of process `pid`
+    ///
+    /// With rate limiting, a clear that does not fit in the window is held back like
+    /// any other update and `Value::Null` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if communication fails or if Discord returns an error
+    pub fn clear_activity_for_pid(&mut self, pid: u32) -> Result<Value> {
+        if !self.submit(pid, None)? {
+            return Ok(Value::Null);
+        }
+
+        let response = self.send_clear_activity(pid)?;
+        self.record_sent(pid, None);
+        Ok(response)
+    }
+
+    fn send_clear_activity(&mut self, pid: u32) -> Result<Value> {
         // Generate a cryptographically secure unique nonce
         let nonce = generate_nonce("clear-activity");
 
         let message = IpcMessage {
             cmd: Command::SetActivity,
             args: json!({
-                "pid": process::id(),
+                "pid": process::host_pid(pid),
                 "activity": Value::Null
             }),
             nonce: nonce.clone(),
@@ -328,9 +585,7 @@ impl DiscordIpcClient {
         };
 
         let payload = serde_json::to_value(message)?;
-        self.connection.send(Opcode::Frame, &payload)?;
-
-        let (opcode, response) = self.recv_for_nonce(&nonce)?;
+        let (opcode, response) = self.request("SET_ACTIVITY", &payload, &nonce)?;
         debug_println!("Clear Activity response: {}", response);
 
         // Check if we got the correct response type
@@ -382,9 +637,7 @@ impl DiscordIpcClient {
         };
 
         let payload = serde_json::to_value(message)?;
-        self.connection.send(Opcode::Frame, &payload)?;
-
-        let (_, response) = self.recv_for_nonce(&nonce)?;
+        let (_, response) = self.request("SUBSCRIBE", &payload, &nonce)?;
 
         if let Some(err) = response.get("error") {
             let code = err.get("code").and_then(|c| c.as_i64()).unwrap_or(0) as i32;
@@ -411,9 +664,7 @@ impl DiscordIpcClient {
         };
 
         let payload = serde_json::to_value(message)?;
-        self.connection.send(Opcode::Frame, &payload)?;
-
-        let (_, response) = self.recv_for_nonce(&nonce)?;
+        let (_, response) = self.request("UNSUBSCRIBE", &payload, &nonce)?;
 
         if let Some(err) = response.get("error") {
             let code = err.get("code").and_then(|c| c.as_i64()).unwrap_or(0) as i32;
@@ -433,12 +684,24 @@ impl DiscordIpcClient {
             return Ok(event);
         }
 
+        // Waiting for an event is not a request, so the read timeout does not apply
+        let read_timeout = self.ipc_config.read_timeout;
+        if read_timeout.is_some() {
+            self.connection.set_read_timeout(None)?;
+        }
+        let event = self.wait_for_event();
+        if read_timeout.is_some() {
+            self.connection.set_read_timeout(read_timeout)?;
+        }
+        event.map_err(|err| self.connection_failed(err))
+    }
+
+    fn wait_for_event(&mut self) -> Result<EventData> {
         loop {
-            let (opcode, payload) = self.connection.recv()?;
+            let (opcode, payload) = self.read_frame()?;
 
             if !opcode.is_frame_response() {
-                self.pending_messages
-                    .push_back(PendingMessage::new(opcode, payload));
+                self.pending_messages.push(opcode, payload);
                 continue;
             }
 
@@ -449,24 +712,61 @@ impl DiscordIpcClient {
                 return Ok(event);
             }
 
-            self.pending_messages
-                .push_back(PendingMessage::new(opcode, payload));
+            self.pending_messages.push(opcode, payload);
         }
     }
 
-    /// Checks for a queued event without blocking
+    /// Returns the next event if one has arrived, without blocking
+    ///
+    /// Reads any frames already available on the socket; a frame that has only
+    /// partly arrived stays buffered until a later call. Replies to other requests
+    /// are queued as usual. Call this from a game loop to see join requests without
+    /// a dedicated event thread.
     pub fn poll_event(&mut self) -> Result<Option<EventData>> {
-        self.take_pending_event()
+        if let Some(event) = self.take_pending_event()? {
+            return Ok(Some(event));
+        }
+
+        while let Some((opcode, payload)) = self
+            .connection
+            .try_recv()
+            .map_err(|err| self.connection_failed(err))?
+        {
+            self.state.frame(opcode, &payload);
+            if opcode.is_frame_response() && Self::value_is_event(&payload) {
+                let response: IpcResponse = serde_json::from_value(payload)
+                    .map_err(DiscordIpcError::DeserializationFailed)?;
+                if let Some(event) = response.parse_event()? {
+                    return Ok(Some(event));
+                }
+                continue;
+            }
+
+            self.pending_messages.push(opcode, payload);
+        }
+
+        Ok(None)
     }
 
     /// Send a raw IPC message
     pub fn send_message(&mut self, opcode: Opcode, payload: &Value) -> Result {
-        self.connection.send(opcode, payload)
+        self.connection
+            .send(opcode, payload)
+            .map_err(|err| self.request_failed(err, "write", None))
     }
 
     /// Receive a raw IPC message
     pub fn recv_message(&mut self) -> Result<(Opcode, Value)> {
         self.next_message()
+            .map_err(|err| self.request_failed(err, "read", None))
+    }
+
+    /// Which PID activities are sent with, after translating out of a PID namespace
+    ///
+    /// Check this inside Flatpak or a container: with `PidSource::Unresolved`,
+    /// Discord cannot clear the activity when the process dies.
+    pub fn pid_info(&self) -> PidInfo {
+        process::resolve_pid(self.ipc_config.activity_pid())
     }
 
     /// Returns `true` once a handshake has been successfully completed.
@@ -474,23 +774,54 @@ impl DiscordIpcClient {
         self.connected
     }
 
+    /// The current [`ConnectionState`]
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.get()
+    }
+
+    /// Call `listener` on every [`ConnectionState`] change, replacing any previous listener
+    ///
+    /// The listener runs on the thread that caused the change, inside the client call.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use presenceforge::DiscordIpcClient;
+    ///
+    /// let mut client = DiscordIpcClient::new("client_id")?;
+    /// client.on_state_change(|state| println!("Discord: {state}"));
+    /// client.connect()?; // prints "Discord: Handshaking", then "Discord: Connected as ..."
+    /// # Ok::<(), presenceforge::DiscordIpcError>(())
+    /// ```
+    pub fn on_state_change(&mut self, listener: impl FnMut(&ConnectionState) + Send + 'static) {
+        self.state.set_listener(Box::new(listener));
+    }
+
+    /// Counters for messages queued while waiting for something else
+    ///
+    /// The queue is bounded by `IpcConfig::pending_capacity` and
+    /// `IpcConfig::pending_max_age`; see [`PendingStats`] for what was dropped.
+    pub fn pending_stats(&mut self) -> PendingStats {
+        self.pending_messages.stats()
+    }
+
     /// Remove pending responses older than the provided `max_age` and return how many were dropped.
     pub fn cleanup_pending(&mut self, max_age: Duration) -> usize {
-        if max_age.is_zero() {
-            let dropped = self.pending_messages.len();
-            self.pending_messages.clear();
-            return dropped;
-        }
-
-        let now = Instant::now();
-        let original_len = self.pending_messages.len();
-        self.pending_messages
-            .retain(|message| now.saturating_duration_since(message.received_at) <= max_age);
-        original_len - self.pending_messages.len()
+        self.pending_messages.cleanup(max_age)
     }
 
     /// Close the connection
+    ///
+    /// A connected client first sends Discord a close frame; failing to send it is ignored.
     pub fn close(&mut self) {
+        if self.connected {
+            let _ = self.connection.send(Opcode::Close, &json!({}));
+        }
+        self.shutdown();
+        self.state.closed();
+    }
+
+    fn shutdown(&mut self) {
         self.connection.close();
         self.pending_messages.clear();
         self.connected = false;
@@ -533,18 +864,15 @@ impl DiscordIpcClient {
     /// ```
     pub fn reconnect(&mut self) -> Result<Value> {
         // Close the old connection
-        self.close();
+        self.shutdown();
 
         // Create a new connection with the same configuration
-        self.connection = if let Some(timeout) = self.timeout_ms {
-            IpcConnection::new_with_configs_and_timeout(
-                self.pipe_config.clone(),
-                timeout,
-                self.ipc_config.clone(),
-            )?
-        } else {
-            IpcConnection::new_with_configs(self.pipe_config.clone(), self.ipc_config.clone())?
-        };
+        self.connection = Self::open(
+            &self.pipe_config,
+            self.timeout_ms,
+            &self.ipc_config,
+            &mut self.state,
+        )?;
         self.pending_messages.clear();
         self.connected = false;
 
@@ -552,15 +880,59 @@ impl DiscordIpcClient {
         self.connect()
     }
 
+    /// The READY payload of the last handshake, until a callback pump consumes it
+    pub(crate) fn take_pending_ready(&mut self) -> Option<ReadyEvent> {
+        self.pending_ready.take()
+    }
+
     fn next_message(&mut self) -> Result<(Opcode, Value)> {
         if let Some(message) = self.pending_messages.pop_front() {
-            let PendingMessage {
-                opcode, payload, ..
-            } = message;
-            return Ok((opcode, payload));
+            return Ok(message);
         }
 
-        self.connection.recv()
+        self.read_frame()
+    }
+
+    fn read_frame(&mut self) -> Result<(Opcode, Value)> {
+        let (opcode, payload) = self.connection.recv()?;
+        self.state.frame(opcode, &payload);
+        Ok((opcode, payload))
+    }
+
+    /// Record a lost connection in the connection state
+    fn connection_failed(&mut self, err: DiscordIpcError) -> DiscordIpcError {
+        self.state.failed(&err);
+        err
+    }
+
+    /// Send a frame and wait for the reply carrying `nonce`
+    fn request(
+        &mut self,
+        operation: &'static str,
+        payload: &Value,
+        nonce: &str,
+    ) -> Result<(Opcode, Value)> {
+        self.connection
+            .send(Opcode::Frame, payload)
+            .and_then(|_| self.recv_for_nonce(nonce))
+            .map_err(|err| self.request_failed(err, operation, Some(nonce)))
+    }
+
+    /// Name the request that timed out; the connection cannot be used afterwards
+    fn request_failed(
+        &mut self,
+        err: DiscordIpcError,
+        operation: &'static str,
+        nonce: Option<&str>,
+    ) -> DiscordIpcError {
+        let err = match err {
+            DiscordIpcError::RequestTimeout { .. } => {
+                self.connected = false;
+                DiscordIpcError::request_timeout(operation, nonce.map(str::to_string))
+            }
+            other => other,
+        };
+        self.connection_failed(err)
     }
 
     fn recv_for_nonce(&mut self, expected_nonce: &str) -> Result<(Opcode, Value)> {
@@ -569,30 +941,18 @@ impl DiscordIpcClient {
         }
 
         loop {
-            let (opcode, response) = self.connection.recv()?;
+            let (opcode, response) = self.read_frame()?;
             if Self::value_has_nonce(&response, expected_nonce) {
                 return Ok((opcode, response));
             }
 
-            self.pending_messages
-                .push_back(PendingMessage::new(opcode, response));
+            self.pending_messages.push(opcode, response);
         }
     }
 
     fn take_pending_by_nonce(&mut self, expected_nonce: &str) -> Option<(Opcode, Value)> {
-        let position = self
-            .pending_messages
-            .iter()
-            .position(|message| Self::value_has_nonce(&message.payload, expected_nonce));
-
-        position.and_then(|index| {
-            self.pending_messages.remove(index).map(|message| {
-                let PendingMessage {
-                    opcode, payload, ..
-                } = message;
-                (opcode, payload)
-            })
-        })
+        self.pending_messages
+            .take(|payload| Self::value_has_nonce(payload, expected_nonce))
     }
 
     fn value_has_nonce(value: &Value, expected_nonce: &str) -> bool {
@@ -604,20 +964,14 @@ impl DiscordIpcClient {
     }
 
     fn take_pending_event(&mut self) -> Result<Option<EventData>> {
-        let position = self
-            .pending_messages
-            .iter()
-            .position(|message| Self::value_is_event(&message.payload));
-
-        if let Some(index) = position {
-            if let Some(message) = self.pending_messages.remove(index) {
-                let response: IpcResponse = serde_json::from_value(message.payload)
+        match self.pending_messages.take(Self::value_is_event) {
+            Some((_, payload)) => {
+                let response: IpcResponse = serde_json::from_value(payload)
                     .map_err(DiscordIpcError::DeserializationFailed)?;
-                return response.parse_event();
+                response.parse_event()
             }
+            None => Ok(None),
         }
-
-        Ok(None)
     }
 
     fn value_is_event(value: &Value) -> bool {
@@ -625,25 +979,284 @@ impl DiscordIpcClient {
     }
 }
 
-#[derive(Debug)]
-struct PendingMessage {
-    opcode: Opcode,
-    payload: Value,
-    received_at: Instant,
+impl Drop for DiscordIpcClient {
+    fn drop(&mut self) {
+        self.close();
+    }
 }
 
-impl PendingMessage {
-    fn new(opcode: Opcode, payload: Value) -> Self {
-        Self {
-            opcode,
-            payload,
-            received_at: Instant::now(),
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::ipc::RateLimitConfig;
+    use crate::test_support::MockDiscord;
+
+    /// A directory for sockets named like Discord's
+    fn socket_dir() -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("presenceforge-search-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn user_search_starts_from_the_open_socket_and_closes_rejected_ones() {
+        let dir = socket_dir();
+        let other = MockDiscord::start_at(dir.join("discord-ipc-0"), "1");
+        let wanted = MockDiscord::start_at(dir.join("discord-ipc-1"), "2");
+        let mut client = DiscordIpcClient::new_with_config_and_ipc_config(
+            "client",
+            Some(PipeConfig::UserId("2".to_string())),
+            IpcConfig::default().with_search_dir(dir.to_string_lossy()),
+        )
+        .unwrap();
+        client.connect().unwrap();
+
+        // The socket opened by the constructor is handshaked once and told goodbye
+        assert_eq!(other.next_frame(), Some(json!({ "close": {} })));
+        assert_eq!(other.next_frame_within(Duration::from_millis(200)), None);
+
+        let activity = ActivityBuilder::new().state("found").build();
+        client.set_activity(&activity).unwrap();
+        let frame = wanted.next_frame().unwrap();
+        assert_eq!(frame["args"]["activity"]["state"], "found");
+
+        drop((other, wanted));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn user_search_gives_up_after_the_connect_timeout() {
+        let dir = socket_dir();
+        let hung = MockDiscord::start_at(dir.join("discord-ipc-0"), "1");
+        hung.set_silent(true);
+        let mut client = DiscordIpcClient::new_with_config_timeout_and_ipc_config(
+            "client",
+            Some(PipeConfig::UserId("2".to_string())),
+            300,
+            IpcConfig::default().with_search_dir(dir.to_string_lossy()),
+        )
+        .unwrap();
+
+        let started = Instant::now();
+        let err = client.connect().unwrap_err();
+        assert!(
+            matches!(
+                err,
+                DiscordIpcError::ConnectionTimeout {
+                    timeout_ms: 300,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        drop(hung);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rate_limit_coalesces_updates_and_holds_back_clear() {
+        let mock = MockDiscord::start();
+        let ipc_config = IpcConfig::default()
+            .with_rate_limit(RateLimitConfig::new(2, Duration::from_millis(300)));
+        let mut client = DiscordIpcClient::new_with_config_and_ipc_config(
+            "client",
+            Some(mock.pipe_config()),
+            ipc_config,
+        )
+        .unwrap();
+        client.connect().unwrap();
+
+        for state in ["a", "a", "b", "c", "d"] {
+            client
+                .set_activity(&ActivityBuilder::new().state(state).build())
+                .unwrap();
         }
+
+        assert_eq!(mock.next_frame().unwrap()["args"]["activity"]["state"], "a");
+        assert_eq!(mock.next_frame().unwrap()["args"]["activity"]["state"], "b");
+        assert!(client.has_pending_update());
+        assert!(client.next_update_at().is_some());
+        assert!(!client.flush_pending_update().unwrap());
+
+        std::thread::sleep(Duration::from_millis(350));
+        assert!(client.flush_pending_update().unwrap());
+        assert_eq!(mock.next_frame().unwrap()["args"]["activity"]["state"], "d");
+
+        client
+            .set_activity(&ActivityBuilder::new().state("e").build())
+            .unwrap();
+        assert_eq!(mock.next_frame().unwrap()["args"]["activity"]["state"], "e");
+
+        // The window is full again: neither a response nor a clear jumps the queue
+        let applied = client
+            .set_activity_with_response(&ActivityBuilder::new().state("f").build())
+            .unwrap();
+        assert!(applied.is_none());
+        assert!(client.clear_activity().unwrap().is_null());
+        assert!(mock.next_frame_within(Duration::from_millis(100)).is_none());
+
+        std::thread::sleep(Duration::from_millis(350));
+        assert!(client.flush_pending_update().unwrap());
+        assert!(mock.next_frame().unwrap()["args"]["activity"].is_null());
+        assert!(!client.has_pending_update());
     }
-}
 
-impl Drop for DiscordIpcClient {
-    fn drop(&mut self) {
-        self.close();
+    #[test]
+    fn request_timeout_names_the_request_and_poisons_the_connection() {
+        let mock = MockDiscord::start();
+        let ipc_config = IpcConfig::default()
+            .with_handshake_timeout(Duration::from_millis(100))
+            .with_read_timeout(Duration::from_millis(100));
+        let mut client = DiscordIpcClient::new_with_config_and_ipc_config(
+            "client",
+            Some(mock.pipe_config()),
+            ipc_config,
+        )
+        .unwrap();
+
+        mock.set_silent(true);
+        let err = client.connect().unwrap_err();
+        assert!(matches!(
+            err,
+            DiscordIpcError::RequestTimeout {
+                operation: "handshake",
+                nonce: None
+            }
+        ));
+
+        mock.set_silent(false);
+        client.reconnect().unwrap();
+        mock.set_silent(true);
+        let err = client
+            .set_activity(&ActivityBuilder::new().state("a").build())
+            .unwrap_err();
+        match err {
+            DiscordIpcError::RequestTimeout { operation, nonce } => {
+                assert_eq!(operation, "SET_ACTIVITY");
+                assert!(nonce.unwrap().starts_with("set-activity-"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(!client.is_connected());
+        assert!(matches!(
+            client.clear_activity(),
+            Err(DiscordIpcError::SocketClosed)
+        ));
+
+        mock.set_silent(false);
+        client.reconnect().unwrap();
+        client.clear_activity().unwrap();
+    }
+
+    #[test]
+    fn poll_event_reads_without_blocking_and_buffers_partial_frames() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        assert!(client.poll_event().unwrap().is_none());
+
+        let frame = crate::test_support::encode_frame(
+            1,
+            &json!({
+                "cmd": "DISPATCH",
+                "evt": "ACTIVITY_JOIN",
+                "data": { "secret": "s3cret" },
+                "nonce": null
+            }),
+        );
+        mock.send_raw(&frame[..5]);
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(client.poll_event().unwrap().is_none());
+
+        mock.send_raw(&frame[5..]);
+        let event = (0..100)
+            .find_map(|_| {
+                std::thread::sleep(Duration::from_millis(10));
+                client.poll_event().unwrap()
+            })
+            .expect("event after the rest of the frame arrived");
+        assert!(matches!(event, EventData::ActivityJoin(join) if join.secret == "s3cret"));
+
+        // The socket is back in blocking mode for requests
+        client.clear_activity().unwrap();
+    }
+
+    #[test]
+    fn state_changes_are_reported_through_close_and_reconnect() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        assert_eq!(client.connection_state(), ConnectionState::Connecting);
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        client.on_state_change({
+            let seen = seen.clone();
+            move |state| seen.lock().unwrap().push(state.to_string())
+        });
+
+        client.connect().unwrap();
+        mock.send_raw(&crate::test_support::encode_frame(
+            2,
+            &json!({ "code": 1000, "message": "Bye" }),
+        ));
+        mock.drop_connections();
+        while client.poll_event().is_ok() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        client.reconnect().unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "Handshaking",
+                "Connected as mock",
+                "Closed by Discord: Bye",
+                "Reconnecting (attempt 1)",
+                "Handshaking",
+                "Connected as mock",
+            ]
+        );
+        assert!(client.connection_state().is_connected());
+    }
+
+    #[test]
+    fn unread_events_are_bounded_while_replies_get_through() {
+        let mock = MockDiscord::start();
+        let mut client = DiscordIpcClient::new_with_config_and_ipc_config(
+            "client",
+            Some(mock.pipe_config()),
+            IpcConfig::default().with_pending_capacity(2),
+        )
+        .unwrap();
+        client.connect().unwrap();
+
+        for secret in ["a", "b", "c", "d", "e"] {
+            mock.send_raw(&crate::test_support::encode_frame(
+                1,
+                &json!({
+                    "cmd": "DISPATCH",
+                    "evt": "ACTIVITY_JOIN",
+                    "data": { "secret": secret },
+                    "nonce": null
+                }),
+            ));
+        }
+        client.clear_activity().unwrap();
+
+        let stats = client.pending_stats();
+        assert_eq!(stats.queued, 2);
+        assert_eq!(stats.dropped_overflow, 3);
+        for expected in ["d", "e"] {
+            let event = client.poll_event().unwrap();
+            assert!(
+                matches!(event, Some(EventData::ActivityJoin(join)) if join.secret == expected)
+            );
+        }
     }
 }
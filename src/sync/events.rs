@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Blocking iterator over the events of a synchronous client
+
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::{EventData, EventKind};
+use crate::sync::client::DiscordIpcClient;
+
+impl DiscordIpcClient {
+    /// Iterate over incoming events, blocking for each one
+    ///
+    /// The iterator ends when Discord closes the socket. Other errors are yielded;
+    /// after a connection error the iterator ends as well.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use presenceforge::{DiscordIpcClient, EventData, EventKind};
+    /// use serde_json::json;
+    ///
+    /// let mut client = DiscordIpcClient::new("client_id")?;
+    /// client.connect()?;
+    /// client.subscribe("ACTIVITY_JOIN", json!({}))?;
+    ///
+    /// for event in client.events().of_kind(EventKind::ActivityJoin) {
+    ///     if let EventData::ActivityJoin(join) = event? {
+    ///         println!("Joining with secret {}", join.secret);
+    ///     }
+    /// }
+    /// # Ok::<(), presenceforge::DiscordIpcError>(())
+    /// ```
+    pub fn events(&mut self) -> Events<'_> {
+        Events {
+            client: self,
+            kinds: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+/// Iterator returned by [`DiscordIpcClient::events`]
+pub struct Events<'a> {
+    client: &'a mut DiscordIpcClient,
+    kinds: Vec<EventKind>,
+    finished: bool,
+}
+
+impl Events<'_> {
+    /// Only yield events of `kind`; call repeatedly to accept several kinds
+    pub fn of_kind(mut self, kind: EventKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<EventData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            match self.client.next_event() {
+                Ok(event) if self.kinds.is_empty() || self.kinds.contains(&event.kind()) => {
+                    return Some(Ok(event));
+                }
+                Ok(_) => {}
+                Err(DiscordIpcError::SocketClosed) => self.finished = true,
+                Err(err) => {
+                    self.finished = err.is_connection_error();
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::{encode_frame, MockDiscord};
+    use serde_json::json;
+
+    #[test]
+    fn filters_by_kind_and_ends_when_the_socket_closes() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        for (evt, data) in [
+            ("GUILD_STATUS", json!({})),
+            ("ACTIVITY_JOIN", json!({ "secret": "one" })),
+            ("ACTIVITY_SPECTATE", json!({ "secret": "two" })),
+        ] {
+            mock.send_raw(&encode_frame(
+                1,
+                &json!({ "cmd": "DISPATCH", "evt": evt, "data": data, "nonce": null }),
+            ));
+        }
+        mock.drop_connections();
+
+        let events: Vec<_> = client
+            .events()
+            .of_kind(EventKind::ActivityJoin)
+            .of_kind(EventKind::ActivitySpectate)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind(), EventKind::ActivityJoin);
+        assert_eq!(events[1].kind(), EventKind::ActivitySpectate);
+    }
+}
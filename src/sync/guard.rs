@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! RAII guard that clears the presence when the application goes away
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use crate::activity::Activity;
+use crate::error::Result;
+use crate::sync::client::DiscordIpcClient;
+
+type SharedClient = Arc<Mutex<DiscordIpcClient>>;
+
+/// Owns a client and clears its activity when dropped
+///
+/// Without a guard, a crashed or killed application can leave a stale status behind
+/// until Discord notices the closed socket. The guard clears the activity and closes
+/// the connection on drop, and can optionally do the same from a panic hook or, with
+/// the `signals` feature on Unix, on `SIGINT`/`SIGTERM`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::sync::PresenceGuard;
+/// use presenceforge::{ActivityBuilder, DiscordIpcClient};
+///
+/// let mut client = DiscordIpcClient::new("client_id")?;
+/// client.connect()?;
+///
+/// let guard = PresenceGuard::new(client).with_panic_hook();
+/// guard.set_activity(&ActivityBuilder::new().state("Playing").build())?;
+///
+/// // The activity is cleared when `guard` goes out of scope, even on panic
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+pub struct PresenceGuard {
+    client: Option<SharedClient>,
+}
+
+impl fmt::Debug for PresenceGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PresenceGuard")
+            .field("armed", &self.client.is_some())
+            .finish()
+    }
+}
+
+impl PresenceGuard {
+    /// Guard a connected client
+    pub fn new(client: DiscordIpcClient) -> Self {
+        Self {
+            client: Some(Arc::new(Mutex::new(client))),
+        }
+    }
+
+    /// Access the guarded client
+    pub fn client(&self) -> MutexGuard<'_, DiscordIpcClient> {
+        let shared = self.client.as_ref().expect("client is present until drop");
+        shared
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Set the activity on the guarded client
+    pub fn set_activity(&self, activity: &Activity) -> Result {
+        self.client().set_activity(activity)
+    }
+
+    /// Clear the activity on the guarded client
+    pub fn clear_activity(&self) -> Result {
+        self.client().clear_activity().map(|_| ())
+    }
+
+    /// Also clear the activity when any thread panics
+    ///
+    /// The hook runs before the previously installed hook, which is still called. It
+    /// does nothing once the guard has been dropped.
+    pub fn with_panic_hook(self) -> Self {
+        let client = self.weak();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            clear_weak(&client);
+            previous(info);
+        }));
+        self
+    }
+
+    /// Clear the activity and exit when the process receives `SIGINT` or `SIGTERM`
+    ///
+    /// The process exits with status `128 + signal` after clearing, mirroring the
+    /// default behavior of those signals.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the handlers cannot be registered.
+    #[cfg(all(unix, feature = "signals"))]
+    pub fn with_signal_handler(self) -> Result<Self> {
+        use signal_hook::consts::{SIGINT, SIGTERM};
+        use signal_hook::iterator::Signals;
+
+        let mut signals = Signals::new([SIGINT, SIGTERM])?;
+        let client = self.weak();
+        std::thread::Builder::new()
+            .name("presenceforge-signals".to_string())
+            .spawn(move || {
+                if let Some(signal) = signals.forever().next() {
+                    clear_weak(&client);
+                    std::process::exit(128 + signal);
+                }
+            })?;
+        Ok(self)
+    }
+
+    /// Disarm the guard and take back the client without clearing
+    pub fn into_inner(mut self) -> DiscordIpcClient {
+        let mut shared = self.client.take().expect("client is present until drop");
+        loop {
+            // Hooks only hold weak references, upgraded briefly while they run
+            match Arc::try_unwrap(shared) {
+                Ok(mutex) => {
+                    return mutex
+                        .into_inner()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                }
+                Err(still_shared) => {
+                    shared = still_shared;
+                    std::thread::yield_now();
+                }
+            }
+        }
+    }
+
+    fn weak(&self) -> Weak<Mutex<DiscordIpcClient>> {
+        Arc::downgrade(self.client.as_ref().expect("client is present until drop"))
+    }
+}
+
+impl Drop for PresenceGuard {
+    fn drop(&mut self) {
+        if let Some(shared) = self.client.take() {
+            let mut client = shared
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let _ = client.clear_activity();
+            client.close();
+        }
+    }
+}
+
+/// Best-effort clear from a hook; never blocks on a client that is in use
+fn clear_weak(client: &Weak<Mutex<DiscordIpcClient>>) {
+    if let Some(shared) = client.upgrade() {
+        if let Ok(mut client) = shared.try_lock() {
+            let _ = client.clear_activity();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::MockDiscord;
+
+    fn connected(mock: &MockDiscord) -> DiscordIpcClient {
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+        client
+    }
+
+    #[test]
+    fn clears_on_drop_but_not_after_into_inner() {
+        let mock = MockDiscord::start();
+
+        let guard = PresenceGuard::new(connected(&mock));
+        guard
+            .set_activity(&ActivityBuilder::new().state("Playing").build())
+            .unwrap();
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "Playing"
+        );
+        drop(guard);
+        assert!(mock.next_frame().unwrap()["args"]["activity"].is_null());
+        assert!(mock.next_frame().unwrap().get("close").is_some());
+
+        let guard = PresenceGuard::new(connected(&mock));
+        let _client = guard.into_inner();
+        assert!(mock
+            .next_frame_within(std::time::Duration::from_millis(100))
+            .is_none());
+    }
+}
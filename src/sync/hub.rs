@@ -0,0 +1,440 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Several Discord applications from one process
+//!
+//! [`PresenceHub`] keeps one [`DiscordIpcClient`] per application (client) ID. The
+//! Discord socket is located once and shared by every connection, and events from
+//! all of them come back tagged with the application they belong to.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::activity::Activity;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::protocol::IpcConfig;
+use crate::ipc::{EventData, IpcConnection, PipeConfig};
+use crate::sync::client::DiscordIpcClient;
+
+/// An event or disconnect from one of the applications in a [`PresenceHub`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HubEvent {
+    /// An event delivered on the connection of `client_id`
+    Event {
+        /// The application the event belongs to
+        client_id: String,
+        /// The event itself
+        event: EventData,
+    },
+    /// The connection of `client_id` was lost and has been closed
+    ///
+    /// Call [`PresenceHub::add`] or [`PresenceHub::activate`] to connect it again.
+    Disconnected {
+        /// The application whose connection was lost
+        client_id: String,
+        /// Why the connection was lost
+        error: DiscordIpcError,
+    },
+}
+
+impl HubEvent {
+    /// The application this event belongs to
+    pub fn client_id(&self) -> &str {
+        match self {
+            Self::Event { client_id, .. } | Self::Disconnected { client_id, .. } => client_id,
+        }
+    }
+}
+
+/// Connections to several Discord applications, keyed by client ID
+///
+/// One application is *active* at a time; [`set_activity`](Self::set_activity) and
+/// [`clear_activity`](Self::clear_activity) go to it. Other applications can still
+/// show a presence of their own through [`set_activity_for`](Self::set_activity_for).
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::sync::{HubEvent, PresenceHub};
+/// use presenceforge::ActivityBuilder;
+///
+/// let mut hub = PresenceHub::new();
+///
+/// // The player launched the first game
+/// hub.activate("game_one_client_id")?;
+/// hub.set_activity(&ActivityBuilder::new().state("In the menu").build())?;
+///
+/// // ...and switched to the second one
+/// hub.activate("game_two_client_id")?;
+/// hub.set_activity(&ActivityBuilder::new().state("Racing").build())?;
+///
+/// for event in hub.poll_events() {
+///     if let HubEvent::Event { client_id, event } = event {
+///         println!("{client_id}: {event:?}");
+///     }
+/// }
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+pub struct PresenceHub {
+    pipe_config: Option<PipeConfig>,
+    ipc_config: IpcConfig,
+    socket: Option<PipeConfig>,
+    clients: BTreeMap<String, DiscordIpcClient>,
+    active: Option<String>,
+}
+
+impl fmt::Debug for PresenceHub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PresenceHub")
+            .field("socket", &self.socket)
+            .field("clients", &self.clients.keys().collect::<Vec<_>>())
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for PresenceHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PresenceHub {
+    /// Create an empty hub that finds Discord with auto-discovery
+    pub fn new() -> Self {
+        Self::with_config(None, IpcConfig::default())
+    }
+
+    /// Create an empty hub with pipe and protocol configuration for every connection
+    pub fn with_config(pipe_config: Option<PipeConfig>, ipc_config: IpcConfig) -> Self {
+        Self {
+            pipe_config,
+            ipc_config,
+            socket: None,
+            clients: BTreeMap::new(),
+            active: None,
+        }
+    }
+
+    /// Connect to application `client_id` unless it is already connected
+    ///
+    /// Adding an application does not make it active.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if no Discord socket is found or the handshake fails.
+    pub fn add(&mut self, client_id: impl Into<String>) -> Result<&mut DiscordIpcClient> {
+        let client_id = client_id.into();
+        let connected = self
+            .clients
+            .get(&client_id)
+            .is_some_and(DiscordIpcClient::is_connected);
+        if !connected {
+            let client = self.connect(&client_id)?;
+            self.clients.insert(client_id.clone(), client);
+        }
+        Ok(self
+            .clients
+            .get_mut(&client_id)
+            .expect("client was just inserted"))
+    }
+
+    /// Clear the presence of `client_id` and close its connection
+    ///
+    /// Returns `false` if the hub had no connection for it.
+    pub fn remove(&mut self, client_id: &str) -> bool {
+        let Some(mut client) = self.clients.remove(client_id) else {
+            return false;
+        };
+        if self.active.as_deref() == Some(client_id) {
+            self.active = None;
+        }
+        if client.is_connected() {
+            if let Err(err) = client.clear_activity() {
+                debug_println!("Failed to clear activity of {}: {}", client_id, err);
+            }
+        }
+        true
+    }
+
+    /// Make `client_id` the active application
+    ///
+    /// The previously active application's presence is cleared but its connection is
+    /// kept. The new application gets a fresh connection and handshake, so Discord
+    /// treats it as the most recent application of this process.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DiscordIpcError` if no Discord socket is found or the handshake fails.
+    /// The previous application stays cleared and no application is active.
+    pub fn activate(&mut self, client_id: impl Into<String>) -> Result {
+        let client_id = client_id.into();
+        if let Some(previous) = self.active.take() {
+            if previous == client_id {
+                self.active = Some(previous);
+                return Ok(());
+            }
+            if let Some(client) = self.clients.get_mut(&previous) {
+                if let Err(err) = client.clear_activity() {
+                    debug_println!("Failed to clear activity of {}: {}", previous, err);
+                }
+            }
+        }
+
+        // Drop the old connection before opening the new one
+        self.clients.remove(&client_id);
+        let client = self.connect(&client_id)?;
+        self.clients.insert(client_id.clone(), client);
+        self.active = Some(client_id);
+        Ok(())
+    }
+
+    /// The active application, if one has been activated
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Set the activity of the active application
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::NoActiveApplication` before [`activate`](Self::activate)
+    /// was called, or the error from `set_activity`.
+    pub fn set_activity(&mut self, activity: &Activity) -> Result {
+        let client_id = self
+            .active
+            .clone()
+            .ok_or(DiscordIpcError::NoActiveApplication)?;
+        self.set_activity_for(&client_id, activity)
+    }
+
+    /// Clear the activity of the active application
+    ///
+    /// # Errors
+    ///
+    /// Same as [`set_activity`](Self::set_activity).
+    pub fn clear_activity(&mut self) -> Result {
+        let client_id = self
+            .active
+            .clone()
+            .ok_or(DiscordIpcError::NoActiveApplication)?;
+        self.clear_activity_for(&client_id)
+    }
+
+    /// Set the activity of application `client_id`, whether it is active or not
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::UnknownApplication` if the hub has no connection for
+    /// `client_id`, or the error from `set_activity`.
+    pub fn set_activity_for(&mut self, client_id: &str, activity: &Activity) -> Result {
+        self.existing(client_id)?.set_activity(activity)
+    }
+
+    /// Clear the activity of application `client_id`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`set_activity_for`](Self::set_activity_for).
+    pub fn clear_activity_for(&mut self, client_id: &str) -> Result {
+        self.existing(client_id)?.clear_activity().map(|_| ())
+    }
+
+    /// The connection of application `client_id`, e.g. to subscribe to events
+    pub fn client(&mut self, client_id: &str) -> Option<&mut DiscordIpcClient> {
+        self.clients.get_mut(client_id)
+    }
+
+    /// Application IDs the hub has a connection for, in sorted order
+    pub fn client_ids(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+
+    /// The socket shared by every connection, once it has been found
+    pub fn socket(&self) -> Option<&PipeConfig> {
+        self.socket.as_ref()
+    }
+
+    /// Read every event that has arrived on any connection without blocking
+    ///
+    /// A connection that was lost is closed and reported once with
+    /// [`HubEvent::Disconnected`]; later polls skip it.
+    pub fn poll_events(&mut self) -> Vec<HubEvent> {
+        let mut events = Vec::new();
+        for (client_id, client) in &mut self.clients {
+            if !client.is_connected() {
+                continue;
+            }
+            loop {
+                match client.poll_event() {
+                    Ok(Some(event)) => events.push(HubEvent::Event {
+                        client_id: client_id.clone(),
+                        event,
+                    }),
+                    Ok(None) => break,
+                    Err(error) if error.is_connection_error() => {
+                        client.close();
+                        events.push(HubEvent::Disconnected {
+                            client_id: client_id.clone(),
+                            error,
+                        });
+                        break;
+                    }
+                    Err(err) => debug_println!("Hub skipping frame for {}: {}", client_id, err),
+                }
+            }
+        }
+        events
+    }
+
+    fn existing(&mut self, client_id: &str) -> Result<&mut DiscordIpcClient> {
+        self.clients
+            .get_mut(client_id)
+            .ok_or_else(|| DiscordIpcError::UnknownApplication {
+                client_id: client_id.to_string(),
+            })
+    }
+
+    /// Open and handshake a connection on the shared socket
+    ///
+    /// A discovered socket that stopped working (Discord restarted on another one)
+    /// is forgotten and discovery runs once more.
+    fn connect(&mut self, client_id: &str) -> Result<DiscordIpcClient> {
+        let cached = self.socket.is_some();
+        match self.connect_to_socket(client_id) {
+            Err(err) if cached && err.is_connection_error() && self.discovers() => {
+                debug_println!("Shared socket failed ({}), discovering again", err);
+                self.socket = None;
+                self.connect_to_socket(client_id)
+            }
+            result => result,
+        }
+    }
+
+    fn connect_to_socket(&mut self, client_id: &str) -> Result<DiscordIpcClient> {
+        let socket = self.resolve_socket()?;
+        let mut client = DiscordIpcClient::new_with_config_and_ipc_config(
+            client_id,
+            Some(socket),
+            self.ipc_config.clone(),
+        )?;
+        client.connect()?;
+        Ok(client)
+    }
+
+    fn discovers(&self) -> bool {
+        matches!(self.pipe_config, None | Some(PipeConfig::Auto))
+    }
+
+    fn resolve_socket(&mut self) -> Result<PipeConfig> {
+        if let Some(socket) = &self.socket {
+            return Ok(socket.clone());
+        }
+        let socket = match &self.pipe_config {
+            None | Some(PipeConfig::Auto) => IpcConnection::discover_pipes()
+                .into_iter()
+                .next()
+                .map(|pipe| PipeConfig::CustomPath(pipe.path))
+                .ok_or(DiscordIpcError::NoValidSocket)?,
+            Some(config) => config.clone(),
+        };
+        self.socket = Some(socket.clone());
+        Ok(socket)
+    }
+}
+
+impl Drop for PresenceHub {
+    fn drop(&mut self) {
+        let client_ids: Vec<String> = self.clients.keys().cloned().collect();
+        for client_id in client_ids {
+            self.remove(&client_id);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::{encode_frame, MockDiscord};
+    use serde_json::json;
+
+    fn hub(mock: &MockDiscord) -> PresenceHub {
+        PresenceHub::with_config(Some(mock.pipe_config()), IpcConfig::default())
+    }
+
+    #[test]
+    fn switching_applications_clears_the_previous_one() {
+        let mock = MockDiscord::start();
+        let mut hub = hub(&mock);
+        assert!(matches!(
+            hub.set_activity(&ActivityBuilder::new().state("x").build()),
+            Err(DiscordIpcError::NoActiveApplication)
+        ));
+
+        hub.activate("one").unwrap();
+        hub.set_activity(&ActivityBuilder::new().state("First").build())
+            .unwrap();
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "First"
+        );
+
+        hub.activate("two").unwrap();
+        let cleared = mock.next_frame().unwrap();
+        assert!(cleared["args"]["activity"].is_null());
+        hub.set_activity(&ActivityBuilder::new().state("Second").build())
+            .unwrap();
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "Second"
+        );
+
+        assert_eq!(hub.active(), Some("two"));
+        assert_eq!(hub.client_ids().collect::<Vec<_>>(), ["one", "two"]);
+        assert!(matches!(
+            hub.set_activity_for("three", &ActivityBuilder::new().state("x").build()),
+            Err(DiscordIpcError::UnknownApplication { client_id }) if client_id == "three"
+        ));
+    }
+
+    #[test]
+    fn events_are_tagged_with_their_application() {
+        let mock = MockDiscord::start();
+        let mut hub = hub(&mock);
+        hub.add("one").unwrap();
+        hub.add("two").unwrap();
+
+        mock.send_raw(&encode_frame(
+            1,
+            &json!({ "cmd": "DISPATCH", "evt": "ACTIVITY_JOIN", "data": { "secret": "s" }, "nonce": null }),
+        ));
+        let mut events = Vec::new();
+        for _ in 0..100 {
+            events.extend(hub.poll_events());
+            if events.len() == 2 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let mut tagged: Vec<&str> = events.iter().map(HubEvent::client_id).collect();
+        tagged.sort_unstable();
+        assert_eq!(tagged, ["one", "two"]);
+
+        mock.drop_connections();
+        let mut disconnected = Vec::new();
+        for _ in 0..100 {
+            disconnected.extend(hub.poll_events());
+            if disconnected.len() == 2 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(disconnected
+            .iter()
+            .all(|event| matches!(event, HubEvent::Disconnected { .. })));
+        assert_eq!(disconnected.len(), 2);
+    }
+}
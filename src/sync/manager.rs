@@ -0,0 +1,657 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Background presence manager for the synchronous client
+//!
+//! [`PresenceManager`] owns a [`DiscordIpcClient`] on a worker thread. It waits for
+//! Discord to appear, reconnects when the connection drops and replays the last
+//! activity afterwards, so applications only describe the presence they want. An
+//! idle connection is checked twice a second, so a Discord restart is noticed without
+//! waiting for the next update.
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::activity::Activity;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::protocol::IpcConfig;
+use crate::ipc::{ConnectionState, PartialUser, PipeConfig};
+use crate::retry::RetryConfig;
+use crate::sync::client::DiscordIpcClient;
+
+/// How often an idle connection is checked for a Discord restart
+pub(crate) const IDLE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Configuration for a [`PresenceManager`]
+#[derive(Debug, Clone)]
+pub struct PresenceManagerConfig {
+    /// Pipe selection used for every connection attempt
+    pub pipe_config: Option<PipeConfig>,
+
+    /// Protocol configuration passed to the client
+    pub ipc_config: IpcConfig,
+
+    /// Backoff used to reconnect after an established connection drops
+    pub retry_config: RetryConfig,
+
+    /// How often to look for Discord while it is not running
+    pub discovery_interval: Duration,
+
+    /// Clear the activity before the worker exits
+    pub clear_on_shutdown: bool,
+}
+
+impl Default for PresenceManagerConfig {
+    fn default() -> Self {
+        Self {
+            pipe_config: None,
+            ipc_config: IpcConfig::default(),
+            retry_config: RetryConfig::default(),
+            discovery_interval: Duration::from_secs(2),
+            clear_on_shutdown: true,
+        }
+    }
+}
+
+impl PresenceManagerConfig {
+    /// Create a new configuration with default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the pipe configuration
+    pub fn with_pipe_config(mut self, pipe_config: PipeConfig) -> Self {
+        self.pipe_config = Some(pipe_config);
+        self
+    }
+
+    /// Set the protocol configuration
+    pub fn with_ipc_config(mut self, ipc_config: IpcConfig) -> Self {
+        self.ipc_config = ipc_config;
+        self
+    }
+
+    /// Set the reconnect backoff
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Set how often to look for Discord while it is not running
+    pub fn with_discovery_interval(mut self, discovery_interval: Duration) -> Self {
+        self.discovery_interval = discovery_interval;
+        self
+    }
+
+    /// Set whether the activity is cleared when the manager shuts down
+    pub fn with_clear_on_shutdown(mut self, clear_on_shutdown: bool) -> Self {
+        self.clear_on_shutdown = clear_on_shutdown;
+        self
+    }
+}
+
+/// Events reported by a presence manager
+///
+/// A [`ConnectionState`] is the current state of one client. A manager replaces its
+/// client on every reconnect and also reports what became of each update, so it emits
+/// events instead; [`PresenceStatus::connection_state`] maps the connection events onto
+/// the state they leave the manager in.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum PresenceStatus {
+    /// Discord is not reachable; the manager keeps looking for it
+    WaitingForDiscord,
+    /// Handshake completed
+    Connected { user: Option<PartialUser> },
+    /// Reconnecting after a dropped connection (attempts start at 1)
+    Reconnecting { attempt: u32 },
+    /// The connection to Discord was lost
+    Disconnected { reason: String },
+    /// Discord accepted the current activity
+    ActivityUpdated,
+    /// Discord cleared the activity
+    ActivityCleared,
+    /// Discord rejected an update; the connection is still usable
+    UpdateFailed { reason: String },
+    /// The worker exited
+    Stopped,
+}
+
+impl PresenceStatus {
+    /// The connection state after this event, or `None` for activity results
+    pub fn connection_state(&self) -> Option<ConnectionState> {
+        match self {
+            Self::WaitingForDiscord => Some(ConnectionState::WaitingForDiscord),
+            Self::Connected { user } => Some(ConnectionState::Connected { user: user.clone() }),
+            Self::Reconnecting { attempt } => {
+                Some(ConnectionState::Reconnecting { attempt: *attempt })
+            }
+            Self::Disconnected { .. } | Self::Stopped => Some(ConnectionState::Disconnected),
+            Self::ActivityUpdated | Self::ActivityCleared | Self::UpdateFailed { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ManagerCommand {
+    Set(Box<Activity>),
+    Clear,
+    Shutdown,
+}
+
+/// Cheap, cloneable handle for sending updates to a [`PresenceManager`]
+#[derive(Debug, Clone)]
+pub struct PresenceHandle {
+    commands: Sender<ManagerCommand>,
+}
+
+impl PresenceHandle {
+    /// Set the activity, replacing any pending update
+    ///
+    /// The activity is validated immediately; delivery happens on the worker thread
+    /// and is reported through the status channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::InvalidActivity` for invalid activities and
+    /// `DiscordIpcError::ManagerShutdown` if the manager has stopped.
+    pub fn set(&self, activity: Activity) -> Result {
+        activity.validate()?;
+        self.send(ManagerCommand::Set(Box::new(activity)))
+    }
+
+    /// Clear the activity
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ManagerShutdown` if the manager has stopped.
+    pub fn clear(&self) -> Result {
+        self.send(ManagerCommand::Clear)
+    }
+
+    /// Ask the worker to clear the activity (if configured), close and exit
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ManagerShutdown` if the manager has already stopped.
+    pub fn shutdown(&self) -> Result {
+        self.send(ManagerCommand::Shutdown)
+    }
+
+    fn send(&self, command: ManagerCommand) -> Result {
+        self.commands
+            .send(command)
+            .map_err(|_| DiscordIpcError::ManagerShutdown)
+    }
+}
+
+/// Self-healing presence service running on a background thread
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::sync::{PresenceManager, PresenceManagerConfig, PresenceStatus};
+/// use presenceforge::ActivityBuilder;
+///
+/// let manager = PresenceManager::spawn("client_id", PresenceManagerConfig::default())?;
+/// let presence = manager.handle();
+///
+/// presence.set(ActivityBuilder::new().state("In the menu").build())?;
+///
+/// for status in manager.status().try_iter() {
+///     if let PresenceStatus::Connected { user } = status {
+///         println!("Connected as {:?}", user.and_then(|u| u.username));
+///     }
+/// }
+///
+/// manager.shutdown();
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+pub struct PresenceManager {
+    handle: PresenceHandle,
+    status: Receiver<PresenceStatus>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl PresenceManager {
+    /// Start the worker thread
+    ///
+    /// The worker connects lazily: this returns immediately even if Discord is not running.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the worker thread cannot be spawned.
+    pub fn spawn(client_id: impl Into<String>, config: PresenceManagerConfig) -> Result<Self> {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (status_tx, status_rx) = mpsc::channel();
+
+        let worker = Worker::new(client_id.into(), config, command_rx, status_tx);
+        let join = thread::Builder::new()
+            .name("presenceforge-manager".to_string())
+            .spawn(move || worker.run())?;
+
+        Ok(Self {
+            handle: PresenceHandle {
+                commands: command_tx,
+            },
+            status: status_rx,
+            worker: Some(join),
+        })
+    }
+
+    /// Get a cloneable handle for sending updates from other threads
+    pub fn handle(&self) -> PresenceHandle {
+        self.handle.clone()
+    }
+
+    /// Receiver for state changes reported by the worker
+    pub fn status(&self) -> &Receiver<PresenceStatus> {
+        &self.status
+    }
+
+    /// Set the activity. See [`PresenceHandle::set`].
+    pub fn set(&self, activity: Activity) -> Result {
+        self.handle.set(activity)
+    }
+
+    /// Clear the activity. See [`PresenceHandle::clear`].
+    pub fn clear(&self) -> Result {
+        self.handle.clear()
+    }
+
+    /// Stop the worker and wait for it to exit
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        let _ = self.handle.shutdown();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for PresenceManager {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+struct Worker {
+    client_id: String,
+    config: PresenceManagerConfig,
+    commands: Receiver<ManagerCommand>,
+    status: Sender<PresenceStatus>,
+    client: Option<DiscordIpcClient>,
+    desired: Option<Activity>,
+    reconnect_attempt: Option<u32>,
+    waiting_reported: bool,
+    next_attempt_at: Instant,
+    flush_failed: bool,
+}
+
+impl Worker {
+    fn new(
+        client_id: String,
+        config: PresenceManagerConfig,
+        commands: Receiver<ManagerCommand>,
+        status: Sender<PresenceStatus>,
+    ) -> Self {
+        Self {
+            client_id,
+            config,
+            commands,
+            status,
+            client: None,
+            desired: None,
+            reconnect_attempt: None,
+            waiting_reported: false,
+            next_attempt_at: Instant::now(),
+            flush_failed: false,
+        }
+    }
+
+    fn run(mut self) {
+        loop {
+            if self.client.is_none() && Instant::now() >= self.next_attempt_at {
+                self.try_connect();
+            }
+
+            let wait = self.wake_at().saturating_duration_since(Instant::now());
+            let command = self.commands.recv_timeout(wait);
+
+            match command {
+                Ok(command) => {
+                    if !self.handle(self.latest(command)) {
+                        break;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    self.flush_pending();
+                    self.check_connection();
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.stop();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// When the worker has something to do without being sent a command
+    fn wake_at(&self) -> Instant {
+        let idle_check = Instant::now() + IDLE_CHECK_INTERVAL;
+        match &self.client {
+            None => self.next_attempt_at,
+            Some(client) if client.has_pending_update() && !self.flush_failed => client
+                .next_update_at()
+                .map_or(Instant::now(), |at| at.min(idle_check)),
+            Some(_) => idle_check,
+        }
+    }
+
+    /// Notice a closed connection while idle, so the presence is replayed right away
+    ///
+    /// `poll_event` never blocks: Unix sockets are read in non-blocking mode and
+    /// Windows pipes are peeked first.
+    fn check_connection(&mut self) {
+        let Some(client) = self.client.as_mut() else {
+            return;
+        };
+        // Nothing subscribes to events here, so whatever arrives is dropped
+        loop {
+            match client.poll_event() {
+                Ok(Some(_)) => {}
+                Ok(None) => return,
+                Err(err) if err.is_connection_error() => return self.failed(err),
+                Err(err) => {
+                    debug_println!("Presence manager ignoring unexpected frame: {}", err);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Send the update held back by `IpcConfig::rate_limit` once the window has room
+    fn flush_pending(&mut self) {
+        let Some(client) = self.client.as_mut() else {
+            return;
+        };
+        match client.flush_pending_update() {
+            Ok(true) if self.desired.is_some() => self.emit(PresenceStatus::ActivityUpdated),
+            Ok(true) => self.emit(PresenceStatus::ActivityCleared),
+            Ok(false) => {}
+            Err(err) => {
+                // Discord rejected it; leave it until the next command replaces it
+                self.flush_failed = !err.is_connection_error();
+                self.failed(err);
+            }
+        }
+    }
+
+    /// Skip straight to the most recent queued command; only the final state matters.
+    fn latest(&self, mut command: ManagerCommand) -> ManagerCommand {
+        while !matches!(command, ManagerCommand::Shutdown) {
+            match self.commands.try_recv() {
+                Ok(next) => command = next,
+                Err(_) => break,
+            }
+        }
+        command
+    }
+
+    fn handle(&mut self, command: ManagerCommand) -> bool {
+        self.flush_failed = false;
+        match command {
+            ManagerCommand::Set(activity) => {
+                self.desired = Some(*activity);
+                self.sync_presence();
+                true
+            }
+            ManagerCommand::Clear => {
+                self.desired = None;
+                self.sync_presence();
+                true
+            }
+            ManagerCommand::Shutdown => {
+                self.stop();
+                false
+            }
+        }
+    }
+
+    fn try_connect(&mut self) {
+        if let Some(attempt) = self.reconnect_attempt {
+            self.emit(PresenceStatus::Reconnecting {
+                attempt: attempt + 1,
+            });
+        }
+
+        let connected = DiscordIpcClient::new_with_config_and_ipc_config(
+            self.client_id.clone(),
+            self.config.pipe_config.clone(),
+            self.config.ipc_config.clone(),
+        )
+        .and_then(|mut client| {
+            let ready = client.connect_with_ready()?;
+            Ok((client, ready))
+        });
+
+        match connected {
+            Ok((client, ready)) => {
+                self.client = Some(client);
+                self.reconnect_attempt = None;
+                self.waiting_reported = false;
+                self.emit(PresenceStatus::Connected {
+                    user: ready.and_then(|ready| ready.user),
+                });
+
+                // A fresh connection starts without an activity, so only replay a set one
+                if self.desired.is_some() {
+                    self.sync_presence();
+                }
+            }
+            Err(err) => {
+                debug_println!("Presence manager connection attempt failed: {}", err);
+                let now = Instant::now();
+                match self.reconnect_attempt {
+                    Some(attempt) if attempt + 1 < self.config.retry_config.max_attempts => {
+                        self.reconnect_attempt = Some(attempt + 1);
+                        self.next_attempt_at =
+                            now + self.config.retry_config.delay_for_attempt(attempt);
+                    }
+                    _ => {
+                        self.reconnect_attempt = None;
+                        self.next_attempt_at = now + self.config.discovery_interval;
+                        if !self.waiting_reported {
+                            self.waiting_reported = true;
+                            self.emit(PresenceStatus::WaitingForDiscord);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn sync_presence(&mut self) {
+        let Some(client) = self.client.as_mut() else {
+            return;
+        };
+
+        let result = match &self.desired {
+            Some(activity) => client
+                .set_activity(activity)
+                .map(|_| PresenceStatus::ActivityUpdated),
+            None => client
+                .clear_activity()
+                .map(|_| PresenceStatus::ActivityCleared),
+        };
+
+        match result {
+            Ok(status) => self.emit(status),
+            Err(err) => self.failed(err),
+        }
+    }
+
+    /// Report a failed update, dropping the client if the connection was lost
+    fn failed(&mut self, err: DiscordIpcError) {
+        if err.is_connection_error() {
+            self.client = None;
+            self.reconnect_attempt = Some(0);
+            self.next_attempt_at = Instant::now();
+            self.emit(PresenceStatus::Disconnected {
+                reason: err.to_string(),
+            });
+        } else {
+            self.emit(PresenceStatus::UpdateFailed {
+                reason: err.to_string(),
+            });
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut client) = self.client.take() {
+            if self.config.clear_on_shutdown && self.desired.is_some() {
+                let _ = client.clear_activity();
+            }
+            client.close();
+        }
+        self.emit(PresenceStatus::Stopped);
+    }
+
+    fn emit(&self, status: PresenceStatus) {
+        // Nobody listening is fine; statuses are informational
+        let _ = self.status.send(status);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::MockDiscord;
+
+    fn config_for(mock: &MockDiscord) -> PresenceManagerConfig {
+        PresenceManagerConfig::default()
+            .with_pipe_config(mock.pipe_config())
+            .with_retry_config(RetryConfig::new(3, 10, 50, 2.0))
+            .with_discovery_interval(Duration::from_millis(20))
+    }
+
+    fn wait_for(manager: &PresenceManager, predicate: impl Fn(&PresenceStatus) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Ok(status) = manager.status().recv_timeout(Duration::from_millis(100)) {
+                if predicate(&status) {
+                    return;
+                }
+            }
+        }
+        panic!("expected status was not reported");
+    }
+
+    #[test]
+    fn waits_for_discord_and_rejects_invalid_activities() {
+        let config = PresenceManagerConfig::default()
+            .with_pipe_config(PipeConfig::CustomPath(
+                "/nonexistent/presenceforge/discord-ipc-0".to_string(),
+            ))
+            .with_discovery_interval(Duration::from_millis(20));
+        let manager = PresenceManager::spawn("client", config).unwrap();
+
+        wait_for(&manager, |s| {
+            s.connection_state() == Some(ConnectionState::WaitingForDiscord)
+        });
+
+        let invalid = ActivityBuilder::new().state("x".repeat(200)).build();
+        assert!(matches!(
+            manager.set(invalid),
+            Err(DiscordIpcError::InvalidActivity(_))
+        ));
+
+        let handle = manager.handle();
+        manager.shutdown();
+        assert!(matches!(
+            handle.clear(),
+            Err(DiscordIpcError::ManagerShutdown)
+        ));
+    }
+
+    #[test]
+    fn flushes_updates_held_back_by_the_rate_limit() {
+        let mock = MockDiscord::start();
+        let ipc_config = IpcConfig::default().with_rate_limit(crate::ipc::RateLimitConfig::new(
+            1,
+            Duration::from_millis(200),
+        ));
+        let manager =
+            PresenceManager::spawn("client", config_for(&mock).with_ipc_config(ipc_config))
+                .unwrap();
+
+        manager
+            .set(ActivityBuilder::new().state("first").build())
+            .unwrap();
+        let frame = mock.next_frame().expect("first activity");
+        assert_eq!(frame["args"]["activity"]["state"], "first");
+        wait_for(&manager, |s| matches!(s, PresenceStatus::ActivityUpdated));
+
+        manager
+            .set(ActivityBuilder::new().state("final").build())
+            .unwrap();
+        let frame = mock.next_frame().expect("held back activity");
+        assert_eq!(frame["args"]["activity"]["state"], "final");
+    }
+
+    #[test]
+    fn replays_the_activity_when_discord_restarts_while_idle() {
+        let mock = MockDiscord::start();
+        let manager = PresenceManager::spawn("client", config_for(&mock)).unwrap();
+
+        manager
+            .set(ActivityBuilder::new().state("kept").build())
+            .unwrap();
+        mock.next_frame().expect("first activity");
+        wait_for(&manager, |s| matches!(s, PresenceStatus::ActivityUpdated));
+
+        mock.drop_connections();
+        wait_for(&manager, |s| {
+            matches!(s, PresenceStatus::Disconnected { .. })
+        });
+        let frame = mock.next_frame().expect("replayed activity");
+        assert_eq!(frame["args"]["activity"]["state"], "kept");
+    }
+
+    #[test]
+    fn replays_latest_activity_after_reconnect() {
+        let mock = MockDiscord::start();
+        let manager = PresenceManager::spawn("client", config_for(&mock)).unwrap();
+        let presence = manager.handle();
+
+        presence
+            .set(ActivityBuilder::new().state("first").build())
+            .unwrap();
+        let frame = mock.next_frame().expect("first activity");
+        assert_eq!(frame["args"]["activity"]["state"], "first");
+        wait_for(&manager, |s| matches!(s, PresenceStatus::ActivityUpdated));
+
+        mock.drop_connections();
+        presence
+            .set(ActivityBuilder::new().state("second").build())
+            .unwrap();
+
+        wait_for(&manager, |s| {
+            matches!(s, PresenceStatus::Disconnected { .. })
+        });
+        let frame = mock.next_frame().expect("replayed activity");
+        assert_eq!(frame["args"]["activity"]["state"], "second");
+
+        manager.shutdown();
+        let frame = mock.next_frame().expect("clear on shutdown");
+        assert!(frame["args"]["activity"].is_null());
+        let frame = mock.next_frame().expect("close on shutdown");
+        assert!(frame.get("close").is_some());
+    }
+}
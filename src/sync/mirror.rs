@@ -0,0 +1,372 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! The same presence on every running Discord
+//!
+//! [`PresenceMirror`] connects to each live Discord socket (Stable and Canary side by
+//! side, or two accounts) and repeats every update on all of them.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::activity::Activity;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::ipc::discovery;
+use crate::ipc::protocol::IpcConfig;
+use crate::ipc::PipeConfig;
+use crate::sync::client::DiscordIpcClient;
+
+/// The outcome of one update on one Discord instance
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct InstanceResult {
+    /// The socket of the instance
+    pub path: String,
+    /// What the instance answered
+    ///
+    /// A connection error means the instance went away; it has been dropped from the
+    /// mirror until [`PresenceMirror::refresh`] finds it again.
+    pub result: Result,
+}
+
+impl InstanceResult {
+    /// Returns `true` if the update reached this instance
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// One connection per running Discord, all showing the same presence
+///
+/// Instances that go away are dropped on the next update and reported in its
+/// results; [`refresh`](Self::refresh) picks up instances started later and brings
+/// them up to date. Dropping the mirror clears the presence everywhere.
+///
+/// # Examples
+///
+/// ```no_run
+/// use presenceforge::sync::PresenceMirror;
+/// use presenceforge::ActivityBuilder;
+///
+/// let mut mirror = PresenceMirror::connect("client_id")?;
+/// println!("Connected to {} Discord instances", mirror.len());
+///
+/// let activity = ActivityBuilder::new().state("In the menu").build();
+/// for instance in mirror.set_activity(&activity)? {
+///     if let Err(err) = instance.result {
+///         eprintln!("{}: {}", instance.path, err);
+///     }
+/// }
+/// # Ok::<(), presenceforge::DiscordIpcError>(())
+/// ```
+pub struct PresenceMirror {
+    client_id: String,
+    ipc_config: IpcConfig,
+    /// Sockets to scan instead of running discovery on every refresh
+    candidates: Option<Vec<String>>,
+    instances: Vec<Instance>,
+    activity: Option<Activity>,
+}
+
+struct Instance {
+    path: String,
+    /// `path` with symlinks resolved, so one socket listed twice is connected once
+    socket: PathBuf,
+    client: DiscordIpcClient,
+}
+
+impl fmt::Debug for PresenceMirror {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PresenceMirror")
+            .field("client_id", &self.client_id)
+            .field("instances", &self.paths().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PresenceMirror {
+    /// Connect to every running Discord as application `client_id`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::NoValidSocket` if no instance completed a handshake.
+    pub fn connect(client_id: impl Into<String>) -> Result<Self> {
+        Self::connect_with_config(client_id, IpcConfig::default())
+    }
+
+    /// Connect to every running Discord with custom protocol configuration
+    ///
+    /// `ipc_config.max_sockets` bounds how many sockets are scanned per directory.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub fn connect_with_config(
+        client_id: impl Into<String>,
+        ipc_config: IpcConfig,
+    ) -> Result<Self> {
+        Self::with_candidates(client_id.into(), ipc_config, None)
+    }
+
+    fn with_candidates(
+        client_id: String,
+        ipc_config: IpcConfig,
+        candidates: Option<Vec<String>>,
+    ) -> Result<Self> {
+        let mut mirror = Self {
+            client_id,
+            ipc_config,
+            candidates,
+            instances: Vec::new(),
+            activity: None,
+        };
+        mirror.refresh();
+        if mirror.is_empty() {
+            return Err(DiscordIpcError::NoValidSocket);
+        }
+        Ok(mirror)
+    }
+
+    /// Number of connected instances
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns `true` once every instance has gone away
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Sockets of the connected instances, in discovery order
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.instances.iter().map(|instance| instance.path.as_str())
+    }
+
+    /// The connection to the instance on `path`, e.g. to subscribe to events
+    pub fn client(&mut self, path: &str) -> Option<&mut DiscordIpcClient> {
+        self.instances
+            .iter_mut()
+            .find_map(|instance| (instance.path == path).then_some(&mut instance.client))
+    }
+
+    /// Set the activity on every instance
+    ///
+    /// The activity is also remembered for instances that [`refresh`](Self::refresh)
+    /// connects later.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::InvalidActivity` without sending anything if the
+    /// activity is invalid. Failures of single instances are reported in the results.
+    pub fn set_activity(&mut self, activity: &Activity) -> Result<Vec<InstanceResult>> {
+        activity.validate()?;
+        self.activity = Some(activity.clone());
+        Ok(self.each(|client| client.set_activity(activity)))
+    }
+
+    /// Clear the activity on every instance
+    pub fn clear_activity(&mut self) -> Vec<InstanceResult> {
+        self.activity = None;
+        self.each(|client| client.clear_activity().map(|_| ()))
+    }
+
+    /// Connect to instances that were not running (or went away) before
+    ///
+    /// Discovery runs again, so sockets in directories that appeared later are found
+    /// too. Each new instance is given the current activity. The results list only the
+    /// instances connected by this call.
+    pub fn refresh(&mut self) -> Vec<InstanceResult> {
+        let candidates = match &self.candidates {
+            Some(candidates) => candidates.clone(),
+            None => discovery::candidate_paths(&self.ipc_config),
+        };
+        let mut results = Vec::new();
+        for path in candidates {
+            let socket = resolve(&path);
+            if self
+                .instances
+                .iter()
+                .any(|instance| instance.socket == socket)
+            {
+                continue;
+            }
+            let Ok(mut client) = DiscordIpcClient::new_with_config_and_ipc_config(
+                self.client_id.as_str(),
+                Some(PipeConfig::CustomPath(path.clone())),
+                self.ipc_config.clone(),
+            ) else {
+                continue;
+            };
+            if let Err(err) = client.connect() {
+                debug_println!("Handshake on {} failed: {}", path, err);
+                continue;
+            }
+            debug_println!("Mirroring presence to {}", path);
+
+            let result = match &self.activity {
+                Some(activity) => client.set_activity(activity),
+                None => Ok(()),
+            };
+            let lost = matches!(&result, Err(err) if err.is_connection_error());
+            if !lost {
+                self.instances.push(Instance {
+                    path: path.clone(),
+                    socket,
+                    client,
+                });
+            }
+            results.push(InstanceResult { path, result });
+        }
+        results
+    }
+
+    /// Run `update` on every instance, dropping those whose connection was lost
+    fn each(
+        &mut self,
+        mut update: impl FnMut(&mut DiscordIpcClient) -> Result,
+    ) -> Vec<InstanceResult> {
+        let mut results = Vec::with_capacity(self.instances.len());
+        self.instances.retain_mut(|instance| {
+            let result = update(&mut instance.client);
+            let lost = matches!(&result, Err(err) if err.is_connection_error());
+            if lost {
+                debug_println!("Discord on {} went away", instance.path);
+                instance.client.close();
+            }
+            results.push(InstanceResult {
+                path: instance.path.clone(),
+                result,
+            });
+            !lost
+        });
+        results
+    }
+}
+
+/// `path` with symlinks resolved, or as given if it cannot be resolved
+fn resolve(path: &str) -> PathBuf {
+    // Resolving a Windows pipe would open it, which Discord sees as a connection
+    #[cfg(unix)]
+    if let Ok(resolved) = std::fs::canonicalize(path) {
+        return resolved;
+    }
+    PathBuf::from(path)
+}
+
+impl Drop for PresenceMirror {
+    fn drop(&mut self) {
+        if self.activity.is_some() {
+            self.clear_activity();
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::MockDiscord;
+
+    #[test]
+    fn updates_reach_every_instance() {
+        let stable = MockDiscord::start();
+        let canary = MockDiscord::start();
+        let candidates = vec![
+            stable.path(),
+            "/nonexistent/discord-ipc-0".to_string(),
+            canary.path(),
+        ];
+        let mut mirror = PresenceMirror::with_candidates(
+            "client".into(),
+            IpcConfig::default(),
+            Some(candidates),
+        )
+        .unwrap();
+        assert_eq!(
+            mirror.paths().collect::<Vec<_>>(),
+            [stable.path(), canary.path()]
+        );
+
+        let results = mirror
+            .set_activity(&ActivityBuilder::new().state("Both").build())
+            .unwrap();
+        assert!(results.iter().all(InstanceResult::is_ok));
+        for mock in [&stable, &canary] {
+            assert_eq!(
+                mock.next_frame().unwrap()["args"]["activity"]["state"],
+                "Both"
+            );
+        }
+
+        let results = mirror.clear_activity();
+        assert_eq!(results.len(), 2);
+        for mock in [&stable, &canary] {
+            assert!(mock.next_frame().unwrap()["args"]["activity"].is_null());
+        }
+    }
+
+    #[test]
+    fn lost_instances_are_dropped_and_refreshed() {
+        let stable = MockDiscord::start();
+        let canary = MockDiscord::start();
+        let candidates = vec![stable.path(), canary.path()];
+        let mut mirror = PresenceMirror::with_candidates(
+            "client".into(),
+            IpcConfig::default(),
+            Some(candidates),
+        )
+        .unwrap();
+
+        canary.drop_connections();
+        let results = mirror
+            .set_activity(&ActivityBuilder::new().state("Still here").build())
+            .unwrap();
+        let failed: Vec<_> = results.iter().filter(|result| !result.is_ok()).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, canary.path());
+        assert_eq!(mirror.paths().collect::<Vec<_>>(), [stable.path()]);
+        stable.next_frame().unwrap();
+
+        let results = mirror.refresh();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            canary.next_frame().unwrap()["args"]["activity"]["state"],
+            "Still here"
+        );
+        assert_eq!(mirror.len(), 2);
+    }
+
+    #[test]
+    fn a_socket_listed_twice_is_connected_once() {
+        let mock = MockDiscord::start();
+        let link =
+            std::env::temp_dir().join(format!("presenceforge-link-{}", uuid::Uuid::new_v4()));
+        std::os::unix::fs::symlink(mock.path(), &link).unwrap();
+
+        let candidates = vec![mock.path(), link.to_string_lossy().into_owned()];
+        let mut mirror = PresenceMirror::with_candidates(
+            "client".into(),
+            IpcConfig::default(),
+            Some(candidates),
+        )
+        .unwrap();
+        assert_eq!(mirror.len(), 1);
+        assert!(mirror.refresh().is_empty());
+        std::fs::remove_file(&link).unwrap();
+    }
+
+    #[test]
+    fn no_instance_is_an_error() {
+        let candidates = vec!["/nonexistent/discord-ipc-0".to_string()];
+        assert!(matches!(
+            PresenceMirror::with_candidates(
+                "client".into(),
+                IpcConfig::default(),
+                Some(candidates)
+            ),
+            Err(DiscordIpcError::NoValidSocket)
+        ));
+    }
+}
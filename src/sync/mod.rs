@@ -5,6 +5,25 @@
 //!
 //! This module provides the synchronous version of the Discord IPC client
 
+mod callbacks;
 pub mod client;
+pub mod events;
+pub mod guard;
+pub mod hub;
+pub mod manager;
+pub mod mirror;
+pub mod reader;
+pub mod rotation;
+pub mod stack;
+pub mod watcher;
 
 pub use client::DiscordIpcClient;
+pub use events::Events;
+pub use guard::PresenceGuard;
+pub use hub::{HubEvent, PresenceHub};
+pub use manager::{PresenceHandle, PresenceManager, PresenceManagerConfig, PresenceStatus};
+pub use mirror::{InstanceResult, PresenceMirror};
+pub use reader::EventReader;
+pub use rotation::RotationHandle;
+pub use stack::StackHandle;
+pub use watcher::ChildWatcher;
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Background event reader sharing a synchronous client's events with many consumers
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::debug_println;
+use crate::error::Result;
+use crate::ipc::broadcast::{EventBroadcaster, EventSubscription};
+use crate::sync::client::DiscordIpcClient;
+
+/// How long the reader thread waits between polls of the socket
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+impl DiscordIpcClient {
+    /// Move a connected client onto a background thread that reads its events
+    ///
+    /// Every event is copied to each subscription from
+    /// [`EventReader::event_subscription`]. Requests can still be made through
+    /// [`EventReader::client`]. When the connection is lost the client is closed, the
+    /// thread stops and subscriptions end with `DiscordIpcError::SocketClosed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the thread cannot be spawned.
+    pub fn spawn_event_reader(self) -> Result<EventReader> {
+        let client = Arc::new(Mutex::new(self));
+        let broadcaster = EventBroadcaster::new();
+        let (stop, stop_rx) = mpsc::channel();
+
+        let worker = thread::Builder::new()
+            .name("presenceforge-events".to_string())
+            .spawn({
+                let client = client.clone();
+                let broadcaster = broadcaster.clone();
+                move || run(&client, &broadcaster, stop_rx)
+            })?;
+
+        Ok(EventReader {
+            client,
+            broadcaster,
+            stop,
+            worker: Some(worker),
+        })
+    }
+}
+
+/// Reads events on a background thread and fans them out to subscriptions
+///
+/// Dropping the reader stops the thread and drops the client.
+pub struct EventReader {
+    client: Arc<Mutex<DiscordIpcClient>>,
+    broadcaster: EventBroadcaster,
+    stop: Sender<()>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl fmt::Debug for EventReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventReader")
+            .field("running", &self.is_running())
+            .finish_non_exhaustive()
+    }
+}
+
+impl EventReader {
+    /// Receive every event read from now on, buffering at most `capacity` of them
+    ///
+    /// A capacity of `0` uses [`DEFAULT_SUBSCRIPTION_CAPACITY`](crate::ipc::broadcast::DEFAULT_SUBSCRIPTION_CAPACITY).
+    pub fn event_subscription(&self, capacity: usize) -> EventSubscription {
+        self.broadcaster.subscribe(capacity)
+    }
+
+    /// Lock the client to make requests such as `set_activity`
+    ///
+    /// The reader pauses while the lock is held; keep it short.
+    pub fn client(&self) -> MutexGuard<'_, DiscordIpcClient> {
+        self.client
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn shared_client(&self) -> Arc<Mutex<DiscordIpcClient>> {
+        self.client.clone()
+    }
+
+    /// Returns `true` while the reader thread is running
+    pub fn is_running(&self) -> bool {
+        self.worker
+            .as_ref()
+            .is_some_and(|worker| !worker.is_finished())
+    }
+
+    /// Stop the reader and take back the client
+    ///
+    /// Subscriptions end with `DiscordIpcError::SocketClosed`.
+    pub fn stop(self) -> DiscordIpcClient {
+        let client = self.client.clone();
+        // Dropping the reader joins the thread, leaving `client` as the only owner
+        drop(self);
+        match Arc::try_unwrap(client) {
+            Ok(client) => client
+                .into_inner()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            Err(_) => unreachable!("the reader thread has exited"),
+        }
+    }
+
+    fn finish(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        self.broadcaster.close();
+    }
+}
+
+impl Drop for EventReader {
+    fn drop(&mut self) {
+        if self.worker.is_some() {
+            self.finish();
+        }
+    }
+}
+
+fn run(client: &Mutex<DiscordIpcClient>, broadcaster: &EventBroadcaster, stop: Receiver<()>) {
+    loop {
+        {
+            let mut client = client
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            loop {
+                match client.poll_event() {
+                    Ok(Some(event)) => broadcaster.publish(&event),
+                    Ok(None) => break,
+                    Err(err) if err.is_connection_error() => {
+                        debug_println!("Event reader stopping: {}", err);
+                        client.close();
+                        broadcaster.close();
+                        return;
+                    }
+                    Err(err) => debug_println!("Event reader skipping frame: {}", err),
+                }
+            }
+        }
+
+        match stop.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => {}
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::error::DiscordIpcError;
+    use crate::ipc::EventData;
+    use crate::test_support::{encode_frame, MockDiscord};
+    use serde_json::json;
+
+    #[test]
+    fn fans_events_out_while_requests_go_through() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+        let reader = client.spawn_event_reader().unwrap();
+
+        let mut ui = reader.event_subscription(4);
+        let mut audio = reader.event_subscription(4);
+
+        mock.send_raw(&encode_frame(
+            1,
+            &json!({ "cmd": "DISPATCH", "evt": "ACTIVITY_JOIN", "data": { "secret": "s" }, "nonce": null }),
+        ));
+        reader
+            .client()
+            .set_activity(&ActivityBuilder::new().state("Playing").build())
+            .unwrap();
+
+        for subscription in [&mut ui, &mut audio] {
+            let event = subscription.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert!(matches!(event, Some(EventData::ActivityJoin(join)) if join.secret == "s"));
+        }
+
+        mock.drop_connections();
+        assert!(matches!(ui.recv(), Err(DiscordIpcError::SocketClosed)));
+        assert!(!reader.client().is_connected());
+        assert!(!reader.stop().is_connected());
+    }
+}
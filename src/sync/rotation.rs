@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Driving an [`ActivityRotation`] from a background thread
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use crate::activity::rotation::RotationCommand;
+use crate::activity::ActivityRotation;
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::sync::client::DiscordIpcClient;
+
+/// Controls a rotation running on a background thread
+///
+/// Dropping the handle stops the rotation and drops the client.
+#[derive(Debug)]
+pub struct RotationHandle {
+    commands: Sender<RotationCommand>,
+    worker: Option<JoinHandle<Result<DiscordIpcClient>>>,
+}
+
+impl ActivityRotation {
+    /// Drive a connected client on a background thread
+    ///
+    /// Each entry is sent with `set_activity` and shown for its duration. Activities
+    /// Discord rejects are skipped; a lost connection ends the rotation and the error
+    /// is returned from [`RotationHandle::stop`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the thread cannot be spawned.
+    pub fn spawn(self, client: DiscordIpcClient) -> Result<RotationHandle> {
+        let (commands, receiver) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("presenceforge-rotation".to_string())
+            .spawn(move || run(self, client, receiver))?;
+
+        Ok(RotationHandle {
+            commands,
+            worker: Some(worker),
+        })
+    }
+}
+
+impl RotationHandle {
+    /// Keep the current activity until [`resume`](Self::resume) is called
+    pub fn pause(&self) {
+        let _ = self.commands.send(RotationCommand::Pause);
+    }
+
+    /// Continue the rotation; the current entry gets the rest of its time
+    pub fn resume(&self) {
+        let _ = self.commands.send(RotationCommand::Resume);
+    }
+
+    /// Returns `true` while the rotation thread is running
+    pub fn is_running(&self) -> bool {
+        self.worker
+            .as_ref()
+            .is_some_and(|worker| !worker.is_finished())
+    }
+
+    /// Stop the rotation and take back the client
+    ///
+    /// The last activity stays visible; clear it with the returned client if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the connection error that ended the rotation early. A panic on the
+    /// rotation thread is resumed on the caller.
+    pub fn stop(mut self) -> Result<DiscordIpcClient> {
+        match self.join() {
+            Some(Ok(result)) => result,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => Err(DiscordIpcError::ManagerShutdown),
+        }
+    }
+
+    fn join(&mut self) -> Option<thread::Result<Result<DiscordIpcClient>>> {
+        let _ = self.commands.send(RotationCommand::Stop);
+        self.worker.take().map(JoinHandle::join)
+    }
+}
+
+impl Drop for RotationHandle {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+fn run(
+    mut rotation: ActivityRotation,
+    mut client: DiscordIpcClient,
+    commands: Receiver<RotationCommand>,
+) -> Result<DiscordIpcClient> {
+    loop {
+        let Some((activity, duration)) = rotation.next_entry() else {
+            // Nothing to show; just wait to be stopped
+            while !matches!(commands.recv(), Ok(RotationCommand::Stop) | Err(_)) {}
+            return Ok(client);
+        };
+
+        match client.set_activity(&activity) {
+            Err(err) if err.is_connection_error() => return Err(err),
+            Err(err) => debug_println!("Rotation entry rejected: {}", err),
+            Ok(()) => {}
+        }
+
+        let mut deadline = Instant::now() + duration;
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            match commands.recv_timeout(wait) {
+                Err(RecvTimeoutError::Timeout) => break,
+                Ok(RotationCommand::Resume) => {}
+                Ok(RotationCommand::Pause) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    loop {
+                        match commands.recv() {
+                            Ok(RotationCommand::Resume) => break,
+                            Ok(RotationCommand::Pause) => {}
+                            Ok(RotationCommand::Stop) | Err(_) => return Ok(client),
+                        }
+                    }
+                    deadline = Instant::now() + remaining;
+                }
+                Ok(RotationCommand::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                    return Ok(client)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::ipc::RateLimitConfig;
+    use crate::test_support::MockDiscord;
+    use std::time::Duration;
+
+    #[test]
+    fn rotates_and_pauses() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        let rotation = ActivityRotation::new()
+            .with_rate_limit(RateLimitConfig::new(100, Duration::from_millis(100)))
+            .add(
+                ActivityBuilder::new().state("one").build(),
+                Duration::from_millis(50),
+            )
+            .add(
+                ActivityBuilder::new().state("two").build(),
+                Duration::from_millis(50),
+            )
+            .spawn(client)
+            .unwrap();
+
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "one"
+        );
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "two"
+        );
+
+        rotation.pause();
+        std::thread::sleep(Duration::from_millis(60));
+        while mock.next_frame_within(Duration::from_millis(10)).is_some() {}
+        assert!(mock.next_frame_within(Duration::from_millis(150)).is_none());
+
+        rotation.resume();
+        assert!(mock.next_frame().is_some());
+        assert!(rotation.is_running());
+        rotation.stop().unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Applying a [`PresenceStack`] with the synchronous client
+
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::activity::stack::StackCommand;
+use crate::activity::{Activity, PresenceStack, PresenceUpdate};
+use crate::debug_println;
+use crate::error::{DiscordIpcError, Result};
+use crate::sync::client::DiscordIpcClient;
+
+/// Controls a stack applied from a background thread
+///
+/// Dropping the handle stops the thread and drops the client.
+#[derive(Debug)]
+pub struct StackHandle {
+    commands: Sender<StackCommand>,
+    worker: Option<JoinHandle<Result<DiscordIpcClient>>>,
+}
+
+impl PresenceStack {
+    /// Apply the stack to a connected client from a background thread
+    ///
+    /// Changes made through the returned handle are sent right away, and the thread
+    /// wakes up at [`next_expiry`](Self::next_expiry) to show the next layer. Updates
+    /// Discord rejects are skipped; a lost connection ends the thread and the error is
+    /// returned from [`StackHandle::stop`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the thread cannot be spawned.
+    pub fn spawn(self, client: DiscordIpcClient) -> Result<StackHandle> {
+        let (commands, receiver) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("presenceforge-stack".to_string())
+            .spawn(move || run(self, client, receiver))?;
+
+        Ok(StackHandle {
+            commands,
+            worker: Some(worker),
+        })
+    }
+
+    /// Send the visible activity to `client` if it changed since the last call
+    ///
+    /// Call this after changing the stack and again at [`next_expiry`](Self::next_expiry)
+    /// so expired layers give way to the next one, or use [`spawn`](Self::spawn) to have
+    /// a background thread do both. Returns `true` if an update was sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns the client error; the next call sends the update again.
+    pub fn apply(&mut self, client: &mut DiscordIpcClient) -> Result<bool> {
+        let Some(update) = self.take_update() else {
+            return Ok(false);
+        };
+
+        let result = match update {
+            PresenceUpdate::Set(activity) => client.set_activity(&activity),
+            PresenceUpdate::Clear => client.clear_activity().map(|_| ()),
+        };
+
+        if let Err(err) = result {
+            self.invalidate();
+            return Err(err);
+        }
+        Ok(true)
+    }
+}
+
+impl StackHandle {
+    /// Set a layer that stays until it is removed. See [`PresenceStack::push`].
+    pub fn push(&self, name: impl Into<String>, priority: i32, activity: Activity) {
+        self.send(StackCommand::Push {
+            name: name.into(),
+            priority,
+            activity: Box::new(activity),
+            ttl: None,
+        });
+    }
+
+    /// Set a layer that expires after `ttl`. See [`PresenceStack::push_for`].
+    pub fn push_for(
+        &self,
+        name: impl Into<String>,
+        priority: i32,
+        activity: Activity,
+        ttl: Duration,
+    ) {
+        self.send(StackCommand::Push {
+            name: name.into(),
+            priority,
+            activity: Box::new(activity),
+            ttl: Some(ttl),
+        });
+    }
+
+    /// Remove a layer
+    pub fn remove(&self, name: impl Into<String>) {
+        self.send(StackCommand::Remove(name.into()));
+    }
+
+    /// Returns `true` while the stack thread is running
+    pub fn is_running(&self) -> bool {
+        self.worker
+            .as_ref()
+            .is_some_and(|worker| !worker.is_finished())
+    }
+
+    /// Stop the thread and take back the client
+    ///
+    /// The last activity stays visible; clear it with the returned client if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the connection error that ended the thread early. A panic on the stack
+    /// thread is resumed on the caller.
+    pub fn stop(mut self) -> Result<DiscordIpcClient> {
+        match self.join() {
+            Some(Ok(result)) => result,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => Err(DiscordIpcError::ManagerShutdown),
+        }
+    }
+
+    fn send(&self, command: StackCommand) {
+        let _ = self.commands.send(command);
+    }
+
+    fn join(&mut self) -> Option<thread::Result<Result<DiscordIpcClient>>> {
+        self.send(StackCommand::Stop);
+        self.worker.take().map(JoinHandle::join)
+    }
+}
+
+impl Drop for StackHandle {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+fn run(
+    mut stack: PresenceStack,
+    mut client: DiscordIpcClient,
+    commands: Receiver<StackCommand>,
+) -> Result<DiscordIpcClient> {
+    loop {
+        match stack.apply(&mut client) {
+            Err(err) if err.is_connection_error() => return Err(err),
+            Err(err) => debug_println!("Stack update rejected: {}", err),
+            Ok(_) => {}
+        }
+
+        let command = match stack.next_expiry() {
+            Some(at) => commands.recv_timeout(at.saturating_duration_since(Instant::now())),
+            None => commands.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match command {
+            Ok(command) => {
+                if !stack.handle(command) {
+                    return Ok(client);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(client),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::MockDiscord;
+    use std::time::Duration;
+
+    #[test]
+    fn background_thread_restores_the_next_layer() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        let mut stack = PresenceStack::new();
+        stack.push("game", 0, ActivityBuilder::new().state("game").build());
+        let stack = stack.spawn(client).unwrap();
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "game"
+        );
+
+        stack.push_for(
+            "banner",
+            1,
+            ActivityBuilder::new().state("banner").build(),
+            Duration::from_millis(50),
+        );
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "banner"
+        );
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "game"
+        );
+
+        stack.remove("game");
+        assert!(mock.next_frame().unwrap()["args"]["activity"].is_null());
+        assert!(stack.is_running());
+        stack.stop().unwrap();
+    }
+
+    #[test]
+    fn expired_layer_is_replaced_on_next_apply() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        let mut stack = PresenceStack::new();
+        stack.push("game", 0, ActivityBuilder::new().state("game").build());
+        stack.push_for(
+            "banner",
+            1,
+            ActivityBuilder::new().state("banner").build(),
+            Duration::from_millis(50),
+        );
+
+        assert!(stack.apply(&mut client).unwrap());
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "banner"
+        );
+        assert!(!stack.apply(&mut client).unwrap());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(stack.apply(&mut client).unwrap());
+        assert_eq!(
+            mock.next_frame().unwrap()["args"]["activity"]["state"],
+            "game"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! Clearing a child process's activity once it exits
+
+use std::fmt;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::debug_println;
+use crate::error::Result;
+use crate::ipc::process;
+use crate::sync::client::DiscordIpcClient;
+use crate::sync::reader::EventReader;
+
+/// Clears the activity of a process once it exits
+///
+/// Meant for launchers that set presence on behalf of a game with
+/// `set_activity_for_pid`. On Linux the process is waited on with a pidfd, falling back
+/// to polling `/proc` on older kernels; other platforms poll. Dropping the watcher
+/// stops it without clearing anything.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use presenceforge::{ActivityBuilder, DiscordIpcClient};
+/// use presenceforge::sync::ChildWatcher;
+///
+/// let game = std::process::Command::new("./game").spawn()?;
+/// let mut client = DiscordIpcClient::new("client_id")?;
+/// client.connect()?;
+/// client.set_activity_for_pid(game.id(), &ActivityBuilder::new().state("In game").build())?;
+///
+/// let watcher = ChildWatcher::spawn(Arc::new(Mutex::new(client)), game.id())?;
+/// // Returns once the game has exited and its activity was cleared
+/// watcher.wait()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ChildWatcher {
+    pid: u32,
+    stop: Sender<()>,
+    worker: Option<JoinHandle<Result>>,
+}
+
+impl fmt::Debug for ChildWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChildWatcher")
+            .field("pid", &self.pid)
+            .field("running", &self.is_running())
+            .finish()
+    }
+}
+
+impl ChildWatcher {
+    /// Watch `pid` on a background thread and clear its activity on `client` when it exits
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the thread cannot be spawned.
+    pub fn spawn(client: Arc<Mutex<DiscordIpcClient>>, pid: u32) -> Result<Self> {
+        let (stop, stop_rx) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("presenceforge-child".to_string())
+            .spawn(move || {
+                if !process::wait_for_exit(pid, &stop_rx) {
+                    return Ok(());
+                }
+                debug_println!("Process {} exited, clearing its activity", pid);
+                client
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .clear_activity_for_pid(pid)
+                    .map(|_| ())
+            })?;
+
+        Ok(Self {
+            pid,
+            stop,
+            worker: Some(worker),
+        })
+    }
+
+    /// The watched process
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Returns `true` until the process has exited and its activity was cleared
+    pub fn is_running(&self) -> bool {
+        self.worker
+            .as_ref()
+            .is_some_and(|worker| !worker.is_finished())
+    }
+
+    /// Block until the process exits and its activity has been cleared
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `clear_activity_for_pid` if clearing failed.
+    pub fn wait(mut self) -> Result {
+        match self.worker.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for ChildWatcher {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            let _ = self.stop.send(());
+            let _ = worker.join();
+        }
+    }
+}
+
+impl EventReader {
+    /// Clear the activity of process `pid` on this reader's client once it exits
+    ///
+    /// See [`ChildWatcher`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscordIpcError::ConnectionFailed` if the thread cannot be spawned.
+    pub fn watch_child(&self, pid: u32) -> Result<ChildWatcher> {
+        ChildWatcher::spawn(self.shared_client(), pid)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::activity::ActivityBuilder;
+    use crate::test_support::MockDiscord;
+
+    #[test]
+    fn clears_the_activity_when_the_child_exits() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("0.2")
+            .spawn()
+            .unwrap();
+        client
+            .set_activity_for_pid(child.id(), &ActivityBuilder::new().state("Child").build())
+            .unwrap();
+
+        let set = mock.next_frame().unwrap();
+        assert_eq!(set["args"]["pid"], process::resolve_pid(child.id()).sent);
+
+        let watcher = ChildWatcher::spawn(Arc::new(Mutex::new(client)), child.id()).unwrap();
+        assert!(watcher.is_running());
+        child.wait().unwrap();
+        watcher.wait().unwrap();
+
+        let clear = mock.next_frame().unwrap();
+        assert_eq!(clear["args"]["pid"], set["args"]["pid"]);
+        assert!(clear["args"]["activity"].is_null());
+    }
+
+    #[test]
+    fn dropping_the_watcher_stops_it() {
+        let mock = MockDiscord::start();
+        let mut client =
+            DiscordIpcClient::new_with_config("client", Some(mock.pipe_config())).unwrap();
+        client.connect().unwrap();
+
+        let watcher =
+            ChildWatcher::spawn(Arc::new(Mutex::new(client)), std::process::id()).unwrap();
+        drop(watcher);
+        // The client is closed without clearing the activity
+        let frame = mock.next_frame_within(std::time::Duration::from_millis(100));
+        assert!(frame.is_some_and(|frame| frame.get("close").is_some()));
+    }
+}
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Copyright (c) 2025-2026 Sreehari Anil and project contributors
+
+//! In-process fake Discord IPC server for unit tests
+
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::ipc::PipeConfig;
+
+/// A fake Discord client listening on a temporary Unix socket.
+///
+/// Completes handshakes with a READY dispatch and answers every frame with an
+/// echo carrying the request nonce. Received frames are forwarded to the test.
+pub(crate) struct MockDiscord {
+    path: PathBuf,
+    frames: Receiver<Value>,
+    connections: Arc<Mutex<Vec<UnixStream>>>,
+    silent: Arc<AtomicBool>,
+}
+
+impl MockDiscord {
+    pub(crate) fn start() -> Self {
+        let path =
+            std::env::temp_dir().join(format!("presenceforge-mock-{}.sock", uuid::Uuid::new_v4()));
+        Self::start_at(path, "1")
+    }
+
+    /// Listen on `path`, answering handshakes as the user `user_id`
+    pub(crate) fn start_at(path: PathBuf, user_id: &str) -> Self {
+        let listener = UnixListener::bind(&path).expect("bind mock socket");
+        let (frame_tx, frames) = mpsc::channel();
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let silent = Arc::new(AtomicBool::new(false));
+
+        let accepted = Arc::clone(&connections);
+        let muted = Arc::clone(&silent);
+        let user_id = user_id.to_string();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                if let Ok(clone) = stream.try_clone() {
+                    accepted.lock().unwrap().push(clone);
+                }
+                let frame_tx = frame_tx.clone();
+                let muted = Arc::clone(&muted);
+                let user_id = user_id.clone();
+                thread::spawn(move || serve(stream, frame_tx, muted, &user_id));
+            }
+        });
+
+        Self {
+            path,
+            frames,
+            connections,
+            silent,
+        }
+    }
+
+    pub(crate) fn path(&self) -> String {
+        self.path.to_string_lossy().into_owned()
+    }
+
+    pub(crate) fn pipe_config(&self) -> PipeConfig {
+        PipeConfig::CustomPath(self.path())
+    }
+
+    /// Wait for the next non-handshake frame sent by the client.
+    pub(crate) fn next_frame(&self) -> Option<Value> {
+        self.frames.recv_timeout(Duration::from_secs(5)).ok()
+    }
+
+    /// Wait for the next frame, giving up after `timeout`.
+    pub(crate) fn next_frame_within(&self, timeout: Duration) -> Option<Value> {
+        self.frames.recv_timeout(timeout).ok()
+    }
+
+    /// Stop answering handshakes and frames, as if Discord hung
+    pub(crate) fn set_silent(&self, silent: bool) {
+        self.silent.store(silent, Ordering::SeqCst);
+    }
+
+    /// Write raw bytes to every accepted connection, e.g. part of a frame
+    pub(crate) fn send_raw(&self, bytes: &[u8]) {
+        for mut stream in self.connections.lock().unwrap().iter() {
+            let _ = stream.write_all(bytes);
+        }
+    }
+
+    /// Close every accepted connection, as if Discord restarted.
+    pub(crate) fn drop_connections(&self) {
+        for stream in self.connections.lock().unwrap().drain(..) {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+    }
+}
+
+impl Drop for MockDiscord {
+    fn drop(&mut self) {
+        self.drop_connections();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn serve(mut stream: UnixStream, frames: Sender<Value>, silent: Arc<AtomicBool>, user_id: &str) {
+    loop {
+        let Some((opcode, payload)) = read_frame(&mut stream) else {
+            return;
+        };
+
+        let reply = match opcode {
+            0 => json!({
+                "cmd": "DISPATCH",
+                "evt": "READY",
+                "data": { "v": 1, "user": { "id": user_id, "username": "mock" } }
+            }),
+            1 => {
+                let reply = json!({
+                    "cmd": payload["cmd"],
+                    "data": payload["args"]["activity"],
+                    "evt": null,
+                    "nonce": payload["nonce"]
+                });
+                let _ = frames.send(payload);
+                reply
+            }
+            2 => {
+                let _ = frames.send(json!({ "close": payload }));
+                return;
+            }
+            _ => return,
+        };
+
+        if silent.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        if write_frame(&mut stream, 1, &reply).is_err() {
+            return;
+        }
+    }
+}
+
+pub(crate) fn read_frame(stream: &mut UnixStream) -> Option<(u32, Value)> {
+    let mut header = [0u8; 8];
+    stream.read_exact(&mut header).ok()?;
+    let opcode = u32::from_le_bytes(header[..4].try_into().ok()?);
+    let length = u32::from_le_bytes(header[4..].try_into().ok()?);
+    let mut body = vec![0u8; length as usize];
+    stream.read_exact(&mut body).ok()?;
+    Some((opcode, serde_json::from_slice(&body).ok()?))
+}
+
+pub(crate) fn write_frame(
+    stream: &mut UnixStream,
+    opcode: u32,
+    payload: &Value,
+) -> std::io::Result<()> {
+    stream.write_all(&encode_frame(opcode, payload))
+}
+
+/// Encode a frame the way Discord sends it
+pub(crate) fn encode_frame(opcode: u32, payload: &Value) -> Vec<u8> {
+    let body = serde_json::to_vec(payload).expect("serialize frame");
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend_from_slice(&opcode.to_le_bytes());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&body);
+    frame
+}